@@ -43,10 +43,11 @@ const POWERSHELL_SCRIPT: &str = include_str!("../../../ci/windows/stop.ps1");
 #[cfg(windows)]
 /// Flag for new console Window
 const CREATE_NEW_CONSOLE: u32 = 0x00000010;
-pub(super) const SERVERS: [(&str, [u16; 2]); 3] = [
+pub(super) const SERVERS: [(&str, [u16; 2]); 4] = [
     ("server1", [2003, 2004]),
     ("server2", [2005, 2006]),
     ("server3", [2007, 2008]),
+    ("server4", [2009, 2010]),
 ];
 /// The test suite server host
 const TESTSUITE_SERVER_HOST: &str = "127.0.0.1";
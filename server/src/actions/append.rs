@@ -0,0 +1,73 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `APPEND` queries
+//! This module provides functions to work with `APPEND` queries
+
+use crate::actions::invalid_json_err;
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+use crate::kvengine::AppendError;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "APPEND",
+    summary: "Append a value to a key",
+    since: "0.8.0",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("suffix", ArgumentType::String, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::Grows,
+};
+
+action!(
+    /// Run an `APPEND` query. This appends the suffix to the value of the key (creating the
+    /// key if it doesn't exist) and returns the new length of the value
+    ///
+    /// ## Syntax
+    /// `APPEND <key> <suffix>`
+    fn append(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, suffix) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the suffix
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if registry::state_okay() {
+            match kve.append(Data::copy_from_slice(key), suffix) {
+                Ok(len) => con.write_response(len).await?,
+                Err(AppendError::Encoding) => conwrite!(con, groups::ENCODING_ERROR)?,
+                Err(AppendError::Json(e)) => conwrite!(con, invalid_json_err(e))?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+);
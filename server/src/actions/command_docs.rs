@@ -0,0 +1,323 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Command documentation
+//!
+//! Every action keeps its documentation next to its implementation as a [`CommandDoc`]; this
+//! module collects them so that they can be served by `COMMAND DOCS`. When adding an action,
+//! remember to add its documentation to [`COMMAND_DOCS`]
+
+use crate::{actions, admin, auth, queryengine};
+use core::iter::StepBy;
+use core::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The group an action belongs to
+pub enum CommandGroup {
+    /// Actions on key/value tables
+    String,
+    /// Actions on key/list tables
+    List,
+    /// Actions that work on keys irrespective of the data model
+    Generic,
+    /// Actions for administration, DDL and connection management
+    Server,
+}
+
+impl CommandGroup {
+    /// All the groups
+    pub const ALL: [Self; 4] = [Self::String, Self::List, Self::Generic, Self::Server];
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::List => "list",
+            Self::Generic => "generic",
+            Self::Server => "server",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The type of an argument
+pub enum ArgumentType {
+    /// The name of a key (or list)
+    Key,
+    /// A value or any other string
+    String,
+    /// An unsigned integer
+    Integer,
+    /// An entity (`<keyspace>`, `<table>` or `<keyspace>:<table>`)
+    Entity,
+    /// A fixed keyword, like a subaction
+    Token,
+}
+
+impl ArgumentType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Key => "key",
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Entity => "entity",
+            Self::Token => "token",
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The documentation for an argument. Repeatable arguments have names ending with `...`
+pub struct ArgumentDoc {
+    pub name: &'static str,
+    pub kind: ArgumentType,
+    pub optional: bool,
+}
+
+impl ArgumentDoc {
+    pub const fn new(name: &'static str, kind: ArgumentType, optional: bool) -> Self {
+        Self {
+            name,
+            kind,
+            optional,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The positions of the keys in a query, counting the action's name as position `0`. The keys
+/// are at `first`, `first + step`, ... up to `last` (inclusive); a negative `last` counts from
+/// the end of the query (so `-1` is the last argument)
+pub struct KeySpec {
+    pub first: usize,
+    pub last: isize,
+    pub step: usize,
+}
+
+impl KeySpec {
+    /// The action doesn't take any keys
+    pub const NONE: Self = Self::new(0, 0, 0);
+    /// The action only takes a key as its first argument
+    pub const FIRST: Self = Self::new(1, 1, 1);
+    pub const fn new(first: usize, last: isize, step: usize) -> Self {
+        Self { first, last, step }
+    }
+    /// Returns the positions of the keys in a query with `argc` arguments (including the
+    /// action's name) or `None` if the query has too few arguments to hold the keys
+    pub fn key_positions(&self, argc: usize) -> Option<StepBy<Range<usize>>> {
+        if self.first == 0 {
+            return Some((0..0).step_by(1));
+        }
+        let last = if self.last < 0 {
+            argc as isize + self.last
+        } else {
+            self.last
+        };
+        if last < self.first as isize || last as usize >= argc {
+            None
+        } else {
+            Some((self.first..last as usize + 1).step_by(self.step))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Whether an action can grow memory usage. Those that can are refused once the memory budget
+/// is exhausted
+pub enum MemoryUse {
+    /// The action never grows memory usage (reads, deletes and DDL)
+    None,
+    /// The action can grow memory usage
+    Grows,
+    /// Only the given subactions (ignoring case) can grow memory usage. `at` is the position
+    /// of the subaction in a query, counting the action's name as position `0`
+    GrowsWith {
+        at: usize,
+        subactions: &'static [&'static str],
+    },
+}
+
+impl MemoryUse {
+    /// Returns true if a query can grow memory usage. `arg` returns the argument of the query
+    /// at the given position (if there is one)
+    pub fn grows<'a>(&self, arg: impl FnOnce(usize) -> Option<&'a [u8]>) -> bool {
+        match self {
+            Self::None => false,
+            Self::Grows => true,
+            Self::GrowsWith { at, subactions } => arg(*at).is_some_and(|subaction| {
+                subactions
+                    .iter()
+                    .any(|name| name.as_bytes().eq_ignore_ascii_case(subaction))
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The documentation for an action
+pub struct CommandDoc {
+    /// the name of the action (in uppercase)
+    pub name: &'static str,
+    pub summary: &'static str,
+    /// the version in which the action was added
+    pub since: &'static str,
+    pub group: CommandGroup,
+    pub complexity: &'static str,
+    pub arguments: &'static [ArgumentDoc],
+    /// where the keys are in a query
+    pub keys: KeySpec,
+    /// whether the action can grow memory usage
+    pub memory: MemoryUse,
+}
+
+/// The documentation for every action, sorted by name
+pub static COMMAND_DOCS: &[&CommandDoc] = &[
+    &auth::acl::DOCS,
+    &queryengine::ddl::ALTER_DOCS,
+    &actions::append::DOCS,
+    &auth::DOCS,
+    &admin::command::DOCS,
+    &queryengine::ddl::CREATE_DOCS,
+    &actions::cset::DOCS,
+    &actions::dbsize::DOCS,
+    &admin::debug::DOCS,
+    &actions::incr::DECR_DOCS,
+    &actions::incr::DECRBY_DOCS,
+    &actions::del::DOCS,
+    &queryengine::ddl::DROP_DOCS,
+    &actions::exists::DOCS,
+    &actions::ttl::EXPIRE_DOCS,
+    &actions::ttl::EXPIREAT_DOCS,
+    &actions::ttl::EXPIRETIME_DOCS,
+    &actions::flushdb::DOCS,
+    &actions::get::DOCS,
+    &actions::pop::GETDEL_DOCS,
+    &actions::getex::DOCS,
+    &actions::getset::DOCS,
+    &actions::heya::DOCS,
+    &actions::incr::INCR_DOCS,
+    &actions::incr::INCRBY_DOCS,
+    &actions::incr::INCRBYFLOAT_DOCS,
+    &queryengine::inspect::DOCS,
+    &actions::keylen::DOCS,
+    &actions::keyrange::DOCS,
+    &admin::latency::DOCS,
+    &actions::lists::lget::DOCS,
+    &actions::lists::llen::DOCS,
+    &actions::lists::lmod::DOCS,
+    &actions::lists::lpos::DOCS,
+    &actions::lists::lrange::DOCS,
+    &actions::lists::DOCS,
+    &actions::lskeys::DOCS,
+    &actions::lists::lslist::DOCS,
+    &actions::lists::lswap::DOCS,
+    &actions::del::MDEL_DOCS,
+    &admin::memory::DOCS,
+    &actions::mget::DOCS,
+    &admin::mksnap::DOCS,
+    &actions::lists::mllen::DOCS,
+    &actions::move_key::DOCS,
+    &actions::mpop::DOCS,
+    &actions::mset::DOCS,
+    &actions::mupdate::DOCS,
+    &actions::object::DOCS,
+    &actions::ttl::PERSIST_DOCS,
+    &actions::ttl::PEXPIRE_DOCS,
+    &actions::ttl::PEXPIREAT_DOCS,
+    &actions::ttl::PEXPIRETIME_DOCS,
+    &actions::pop::DOCS,
+    &actions::ttl::PTTL_DOCS,
+    &actions::scan::DOCS,
+    &actions::strong::SDEL_DOCS,
+    &actions::set::DOCS,
+    &actions::setnx::DOCS,
+    &actions::setnxex::DOCS,
+    &actions::strong::SSET_DOCS,
+    &actions::strlen::DOCS,
+    &actions::strong::SUPDATE_DOCS,
+    &actions::swapkeys::DOCS,
+    &admin::sys::DOCS,
+    &actions::ttl::TTL_DOCS,
+    &actions::update::DOCS,
+    &queryengine::USE_DOCS,
+    &actions::uset::DOCS,
+    &actions::whereami::DOCS,
+];
+
+/// Returns the documentation for the action with the given name (ignoring case)
+pub fn find(name: &[u8]) -> Option<&'static CommandDoc> {
+    COMMAND_DOCS
+        .iter()
+        .find(|doc| doc.name.as_bytes().eq_ignore_ascii_case(name))
+        .copied()
+}
+
+#[test]
+fn test_command_docs_sorted_and_unique() {
+    assert!(COMMAND_DOCS
+        .windows(2)
+        .all(|docs| docs[0].name < docs[1].name));
+    assert!(COMMAND_DOCS
+        .iter()
+        .all(|doc| doc.name == doc.name.to_ascii_uppercase()));
+}
+
+#[test]
+fn test_find_command_doc() {
+    assert_eq!(find(b"get").unwrap().name, "GET");
+    assert_eq!(find(b"LMOD").unwrap().name, "LMOD");
+    assert!(find(b"notanaction").is_none());
+}
+
+#[test]
+fn test_key_positions() {
+    let positions = |spec: KeySpec, argc| spec.key_positions(argc).map(|p| p.collect::<Vec<_>>());
+    assert_eq!(positions(KeySpec::NONE, 3), Some(vec![]));
+    assert_eq!(positions(KeySpec::FIRST, 3), Some(vec![1]));
+    assert_eq!(positions(KeySpec::FIRST, 1), None);
+    assert_eq!(positions(KeySpec::new(1, -1, 1), 4), Some(vec![1, 2, 3]));
+    assert_eq!(positions(KeySpec::new(1, -1, 2), 5), Some(vec![1, 3]));
+    assert_eq!(positions(KeySpec::new(1, -1, 2), 1), None);
+    assert_eq!(positions(KeySpec::new(1, 2, 1), 2), None);
+}
+
+#[test]
+fn test_memory_use() {
+    let grows = |query: &[&str]| {
+        find(query[0].as_bytes())
+            .unwrap()
+            .memory
+            .grows(|idx| query.get(idx).map(|arg| arg.as_bytes()))
+    };
+    assert!(grows(&["set", "x", "y"]));
+    assert!(grows(&["lset", "list"]));
+    assert!(!grows(&["del", "x"]));
+    assert!(!grows(&["get", "x"]));
+    assert!(grows(&["lmod", "list", "push", "x"]));
+    assert!(grows(&["lmod", "list", "INSERT", "0", "x"]));
+    assert!(!grows(&["lmod", "push", "pop"]));
+    assert!(!grows(&["lmod", "list"]));
+    assert!(grows(&["debug", "populate", "10"]));
+    assert!(!grows(&["debug", "stringmatch-len", "x"]));
+}
@@ -0,0 +1,77 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `CSET` queries
+//! This module provides functions to work with `CSET` queries
+
+use crate::corestore::htable::UpsertOutcome;
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "CSET",
+    summary: "Set a key only if its value would change",
+    since: "0.8.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("value", ArgumentType::String, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::Grows,
+};
+
+action!(
+    /// Run a `CSET` query
+    ///
+    /// ## Syntax
+    /// `CSET <key> <value>`: sets the key unless it already holds the same value. Returns
+    /// `OKAY` if the key was created, `updated` if its value was replaced and `unchanged`
+    /// if nothing was written
+    fn cset(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, val) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the value
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if let Err(e) = kve.check_json(val) {
+            return conwrite!(con, invalid_json_err(e));
+        }
+        if registry::state_okay() {
+            match kve.upsert_if_changed(Data::copy_from_slice(key), Data::copy_from_slice(val)) {
+                Ok(UpsertOutcome::Inserted) => conwrite!(con, groups::OKAY)?,
+                Ok(UpsertOutcome::Updated) => conwrite!(con, groups::UPDATED)?,
+                Ok(UpsertOutcome::Unchanged) => conwrite!(con, groups::UNCHANGED)?,
+                Err(()) => conwrite!(con, groups::ENCODING_ERROR)?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+);
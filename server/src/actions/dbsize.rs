@@ -26,6 +26,17 @@
 
 use crate::dbnet::connection::prelude::*;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "DBSIZE",
+    summary: "Get the number of entries in a table",
+    since: "0.4.3",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("entity", ArgumentType::Entity, true)],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Returns the number of keys in the database
     fn dbsize(handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
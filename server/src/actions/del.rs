@@ -32,6 +32,25 @@ use crate::dbnet::connection::prelude::*;
 use crate::kvengine::encoding::ENCODING_LUT_ITER;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "DEL",
+    summary: "Delete keys",
+    since: "0.1.0",
+    group: CommandGroup::Generic,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("key ...", ArgumentType::Key, false)],
+    keys: KeySpec::new(1, -1, 1),
+    memory: MemoryUse::None,
+};
+
+/// `MDEL` is an alias for `DEL`
+pub const MDEL_DOCS: CommandDoc = CommandDoc {
+    name: "MDEL",
+    summary: "Delete keys (alias for DEL)",
+    since: "0.8.0",
+    ..DOCS
+};
+
 action!(
     /// Run a `DEL` query
     ///
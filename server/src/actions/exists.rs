@@ -33,6 +33,17 @@ use crate::kvengine::encoding::ENCODING_LUT_ITER;
 use crate::queryengine::ActionIter;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "EXISTS",
+    summary: "Count how many of the keys exist",
+    since: "0.1.0",
+    group: CommandGroup::Generic,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("key ...", ArgumentType::Key, false)],
+    keys: KeySpec::new(1, -1, 1),
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Run an `EXISTS` query
     fn exists(handle: &Corestore, con: &'a mut T, act: ActionIter<'a>) {
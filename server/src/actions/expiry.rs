@@ -0,0 +1,115 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Expiry helpers
+//! This module provides functions to parse the expiry times passed to actions that set
+//! deadlines on keys
+
+use crate::actions::ActionResult;
+use crate::kvengine::expiry;
+use crate::protocol::responses::groups;
+use crate::util;
+
+/// Parse a time (a duration or a timestamp). This fails if it isn't an integer or if it's zero
+fn parse_time(time: &[u8]) -> ActionResult<u64> {
+    match String::from_utf8_lossy(time).parse() {
+        Ok(0) => util::err(groups::INVALID_EXPIRE_TIME),
+        Ok(time) => Ok(time),
+        Err(_) => util::err(groups::WRONGTYPE_ERR),
+    }
+}
+
+/// Convert a time in `multiplier` milliseconds into a deadline, optionally relative to now
+fn to_deadline(time: &[u8], multiplier: u64, relative: bool) -> ActionResult<u64> {
+    let millis = parse_time(time)?.checked_mul(multiplier);
+    let deadline = if relative {
+        millis.and_then(|millis| millis.checked_add(expiry::now_millis()))
+    } else {
+        millis
+    };
+    match deadline {
+        Some(deadline) => Ok(deadline),
+        None => util::err(groups::INVALID_EXPIRE_TIME),
+    }
+}
+
+/// Returns the deadline (a unix timestamp in milliseconds) that is `seconds` seconds from now
+pub fn deadline_after_seconds(seconds: &[u8]) -> ActionResult<u64> {
+    self::to_deadline(seconds, 1000, true)
+}
+
+/// Returns the deadline (a unix timestamp in milliseconds) that is `millis` milliseconds from now
+pub fn deadline_after_millis(millis: &[u8]) -> ActionResult<u64> {
+    self::to_deadline(millis, 1, true)
+}
+
+/// Returns the deadline (a unix timestamp in milliseconds) for a unix timestamp in seconds
+pub fn deadline_at_seconds(timestamp: &[u8]) -> ActionResult<u64> {
+    self::to_deadline(timestamp, 1000, false)
+}
+
+/// Returns the deadline (a unix timestamp in milliseconds) for a unix timestamp in milliseconds
+pub fn deadline_at_millis(timestamp: &[u8]) -> ActionResult<u64> {
+    self::to_deadline(timestamp, 1, false)
+}
+
+#[test]
+fn test_deadlines() {
+    use crate::actions::ActionError;
+    fn err_of(r: ActionResult<u64>) -> &'static [u8] {
+        match r {
+            Err(ActionError::ActionError(e)) => e,
+            _ => panic!("expected an error"),
+        }
+    }
+    let now = expiry::now_millis();
+    let deadline = deadline_after_seconds(b"10").unwrap();
+    assert!(deadline >= now + 10_000 && deadline < now + 20_000);
+    let deadline = deadline_after_millis(b"10").unwrap();
+    assert!(deadline >= now + 10 && deadline < now + 10_000);
+    assert_eq!(
+        deadline_at_seconds(b"1700000000").unwrap(),
+        1_700_000_000_000
+    );
+    assert_eq!(
+        deadline_at_millis(b"1700000000000").unwrap(),
+        1_700_000_000_000
+    );
+    assert_eq!(
+        err_of(deadline_after_seconds(b"0")),
+        groups::INVALID_EXPIRE_TIME
+    );
+    assert_eq!(
+        err_of(deadline_at_millis(b"0")),
+        groups::INVALID_EXPIRE_TIME
+    );
+    assert_eq!(
+        err_of(deadline_at_seconds(u64::MAX.to_string().as_bytes())),
+        groups::INVALID_EXPIRE_TIME
+    );
+    assert_eq!(err_of(deadline_after_seconds(b"-1")), groups::WRONGTYPE_ERR);
+    assert_eq!(err_of(deadline_after_seconds(b"1s")), groups::WRONGTYPE_ERR);
+}
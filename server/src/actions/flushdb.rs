@@ -24,22 +24,56 @@
  *
 */
 
+use crate::actions::ActionResult;
 use crate::dbnet::connection::prelude::*;
 use crate::queryengine::ActionIter;
 
+const FORCE: &[u8] = "force".as_bytes();
+/// Tables with more entries than this can only be flushed with `force` if
+/// `protect_large_flush` is enabled
+const LARGE_FLUSH_THRESHOLD: usize = 100_000;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "FLUSHDB",
+    summary: "Remove all the entries in a table",
+    since: "0.4.3",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("entity", ArgumentType::Entity, true),
+        ArgumentDoc::new("force", ArgumentType::Token, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Delete all the keys in the database
+    /// ## Syntax
+    /// - `FLUSHDB`: flush the current table
+    /// - `FLUSHDB <entity>`: flush the given table
+    /// - `FLUSHDB <entity> force`: flush the given table even if it is too large to be
+    /// flushed while `protect_large_flush` is enabled
     fn flushdb(handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
-        ensure_length(act.len(), |len| len < 2)?;
+        ensure_length(act.len(), |len| len < 3)?;
         if registry::state_okay() {
             if act.is_empty() {
                 // flush the current table
-                get_tbl_ref!(handle, con).truncate_table();
+                let table = get_tbl_ref!(handle, con);
+                ensure_flush_allowed(table.count(), false)?;
+                table.truncate_table();
             } else {
                 // flush the entity
                 let raw_entity = unsafe { act.next_unchecked() };
+                let force = match act.next() {
+                    Some(bts) if bts.eq(FORCE) => true,
+                    None => false,
+                    _ => return util::err(groups::UNKNOWN_ACTION),
+                };
                 let entity = handle_entity!(con, raw_entity);
-                get_tbl!(entity, handle, con).truncate_table();
+                let table = get_tbl!(entity, handle, con);
+                ensure_flush_allowed(table.count(), force)?;
+                table.truncate_table();
             }
             conwrite!(con, responses::groups::OKAY)?;
         } else {
@@ -48,3 +82,11 @@ action!(
         Ok(())
     }
 );
+
+/// Refuse to flush a large table without `force` if `protect_large_flush` is enabled
+fn ensure_flush_allowed(count: usize, force: bool) -> ActionResult<()> {
+    ensure_cond_or_err(
+        force || !registry::protect_large_flush() || count <= LARGE_FLUSH_THRESHOLD,
+        groups::PROTECTED,
+    )
+}
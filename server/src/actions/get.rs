@@ -31,6 +31,17 @@ use crate::dbnet::connection::prelude::*;
 use crate::resp::writer;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "GET",
+    summary: "Get the value of a key",
+    since: "0.1.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("key", ArgumentType::Key, false)],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Run a `GET` query
     fn get(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
@@ -0,0 +1,105 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `GETEX` queries
+//! This module provides functions to work with `GETEX` queries
+
+use crate::actions::expiry;
+use crate::dbnet::connection::prelude::*;
+use crate::resp::writer;
+use crate::util::compiler;
+
+const EX: &[u8] = "EX".as_bytes();
+const PX: &[u8] = "PX".as_bytes();
+const EXAT: &[u8] = "EXAT".as_bytes();
+const PXAT: &[u8] = "PXAT".as_bytes();
+const PERSIST: &[u8] = "PERSIST".as_bytes();
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "GETEX",
+    summary: "Get the value of a key and set or remove its time to live",
+    since: "0.8.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("ex|px|exat|pxat|persist", ArgumentType::Token, true),
+        ArgumentDoc::new("time", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+action!(
+    /// Run a `GETEX` query
+    ///
+    /// ## Syntax
+    /// - `GETEX <key>`: same as `GET`
+    /// - `GETEX <key> EX <seconds>` or `GETEX <key> PX <milliseconds>`: get the value and
+    /// set the key to expire after the given time
+    /// - `GETEX <key> EXAT <unix time>` or `GETEX <key> PXAT <unix time in ms>`: get the value
+    /// and set the key to expire at the given time
+    /// - `GETEX <key> PERSIST`: get the value and remove the key's expiry
+    fn getex(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len > 0 && len < 4)?;
+        let key = unsafe {
+            // SAFETY: the length check above needs at least one argument, the key
+            act.next_unchecked()
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        let ret = match act.next_uppercase().as_deref() {
+            // just a get
+            None => kve.get_cloned(key),
+            Some(PERSIST) => {
+                ensure_length(act.len(), |len| len == 0)?;
+                kve.get_cloned_and_set_deadline(key, None)
+            }
+            Some(option) => {
+                ensure_length(act.len(), |len| len == 1)?;
+                let time = unsafe {
+                    // SAFETY: the option is followed by exactly one argument, as checked just above
+                    act.next_unchecked()
+                };
+                let deadline = match option {
+                    EX => expiry::deadline_after_seconds(time)?,
+                    PX => expiry::deadline_after_millis(time)?,
+                    EXAT => expiry::deadline_at_seconds(time)?,
+                    PXAT => expiry::deadline_at_millis(time)?,
+                    _ => return conwrite!(con, groups::UNKNOWN_ACTION),
+                };
+                kve.get_cloned_and_set_deadline(key, Some(deadline))
+            }
+        };
+        match ret {
+            Ok(Some(val)) => unsafe {
+                writer::write_raw_mono(con, kve.get_value_tsymbol(), &val).await?
+            },
+            Err(_) => compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?,
+            Ok(_) => conwrite!(con, groups::NIL)?,
+        }
+        Ok(())
+    }
+);
@@ -0,0 +1,191 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `INCR`, `DECR`, `INCRBY`, `DECRBY` and `INCRBYFLOAT` queries
+//! This module provides functions to work with `INCR`, `DECR`, `INCRBY`, `DECRBY` and
+//! `INCRBYFLOAT` queries
+
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+use crate::kvengine::{self, IncrError};
+
+pub const INCR_DOCS: CommandDoc = CommandDoc {
+    name: "INCR",
+    summary: "Increment the integer value of a key by one",
+    since: "0.8.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("key", ArgumentType::Key, false)],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::Grows,
+};
+
+pub const DECR_DOCS: CommandDoc = CommandDoc {
+    name: "DECR",
+    summary: "Decrement the integer value of a key by one",
+    ..INCR_DOCS
+};
+
+pub const INCRBY_DOCS: CommandDoc = CommandDoc {
+    name: "INCRBY",
+    summary: "Increment the integer value of a key by the given amount",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("delta", ArgumentType::Integer, false),
+    ],
+    ..INCR_DOCS
+};
+
+pub const DECRBY_DOCS: CommandDoc = CommandDoc {
+    name: "DECRBY",
+    summary: "Decrement the integer value of a key by the given amount",
+    ..INCRBY_DOCS
+};
+
+pub const INCRBYFLOAT_DOCS: CommandDoc = CommandDoc {
+    name: "INCRBYFLOAT",
+    summary: "Increment the float value of a key by the given amount",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("delta", ArgumentType::String, false),
+    ],
+    ..INCR_DOCS
+};
+
+/// Parse a signed base-10 delta
+fn parse_delta(delta: &[u8]) -> Option<i64> {
+    core::str::from_utf8(delta).ok()?.parse().ok()
+}
+
+action!(
+    /// Run an `INCR` query
+    ///
+    /// ## Syntax
+    /// `INCR <key>`
+    fn incr(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        incr_by(handle, con, key, 1).await
+    }
+    /// Run a `DECR` query
+    ///
+    /// ## Syntax
+    /// `DECR <key>`
+    fn decr(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        incr_by(handle, con, key, -1).await
+    }
+    /// Run an `INCRBY` query
+    ///
+    /// ## Syntax
+    /// `INCRBY <key> <delta>`
+    fn incrby(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, delta) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the delta
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        match parse_delta(delta) {
+            Some(delta) => incr_by(handle, con, key, delta).await,
+            None => util::err(groups::WRONGTYPE_ERR),
+        }
+    }
+    /// Run a `DECRBY` query
+    ///
+    /// ## Syntax
+    /// `DECRBY <key> <delta>`
+    fn decrby(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, delta) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the delta
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        match parse_delta(delta).map(i64::checked_neg) {
+            Some(Some(delta)) => incr_by(handle, con, key, delta).await,
+            // the delta can't be negated
+            Some(None) => util::err(groups::OVERFLOW_ERR),
+            None => util::err(groups::WRONGTYPE_ERR),
+        }
+    }
+    /// Run an `INCRBYFLOAT` query
+    ///
+    /// ## Syntax
+    /// `INCRBYFLOAT <key> <delta>`
+    fn incrbyfloat(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, delta) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the delta
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let delta = match kvengine::parse_finite_float(delta) {
+            Some(delta) => delta,
+            None => return util::err(groups::WRONGTYPE_ERR),
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if registry::state_okay() {
+            match kve.incr_by_float(Data::copy_from_slice(key), delta) {
+                Ok(new) => {
+                    con.write_response(StringWrapper(kvengine::format_float(new)))
+                        .await?
+                }
+                Err(IncrError::Encoding) => conwrite!(con, groups::ENCODING_ERROR)?,
+                Err(IncrError::NotANumber) => conwrite!(con, groups::WRONGTYPE_ERR)?,
+                Err(IncrError::Overflow) => conwrite!(con, groups::OVERFLOW_ERR)?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+);
+
+action!(
+    /// Atomically add `delta` to the integer value of the key (0 if it doesn't exist) and
+    /// write the new value. Since Skyhash has no signed integer type, negative values are
+    /// written as strings
+    fn incr_by(handle: &crate::corestore::Corestore, con: &mut T, key: &[u8], delta: i64) {
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if registry::state_okay() {
+            match kve.incr_by(Data::copy_from_slice(key), delta) {
+                Ok(new) if new >= 0 => con.write_response(new as u64).await?,
+                Ok(new) => con.write_response(StringWrapper(new.to_string())).await?,
+                Err(IncrError::Encoding) => conwrite!(con, groups::ENCODING_ERROR)?,
+                Err(IncrError::NotANumber) => conwrite!(con, groups::WRONGTYPE_ERR)?,
+                Err(IncrError::Overflow) => conwrite!(con, groups::OVERFLOW_ERR)?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+);
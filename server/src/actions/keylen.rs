@@ -26,29 +26,37 @@
 
 use crate::dbnet::connection::prelude::*;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "KEYLEN",
+    summary: "Get the length of the value of a key",
+    since: "0.4.4",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("key", ArgumentType::Key, false)],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Run a `KEYLEN` query
     ///
     /// At this moment, `keylen` only supports a single key
     fn keylen(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
         ensure_length(act.len(), |len| len == 1)?;
-        let res: Option<usize> = {
+        let res = {
             let reader = handle.get_table_with::<KVEBlob>()?;
             unsafe {
                 // UNSAFE(@ohsayan): this is completely safe as we've already checked
                 // the number of arguments is one
-                match reader.get(act.next_unchecked()) {
-                    Ok(v) => v.map(|b| b.len()),
-                    Err(_) => None,
-                }
+                reader.value_len(act.next_unchecked())
             }
         };
-        if let Some(value) = res {
+        match res {
             // Good, we got the key's length, write it off to the stream
-            con.write_response(value).await?;
-        } else {
+            Ok(Some(value)) => con.write_response(value).await?,
             // Ah, couldn't find that key
-            con.write_response(responses::groups::NIL).await?;
+            Ok(None) => con.write_response(responses::groups::NIL).await?,
+            Err(()) => conwrite!(con, groups::ENCODING_ERROR)?,
         }
         Ok(())
     }
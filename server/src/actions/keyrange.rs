@@ -0,0 +1,101 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `KEYRANGE` queries
+//! This module provides functions to list the keys of a table that fall within a range, for
+//! tables that keep an ordered index of their keys
+
+use crate::dbnet::connection::prelude::*;
+use crate::resp::writer::TypedArrayWriter;
+
+const LIMIT: &[u8] = "LIMIT".as_bytes();
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "KEYRANGE",
+    summary: "Get the keys between two keys (inclusive) in lexicographic order",
+    since: "0.8.0",
+    group: CommandGroup::Generic,
+    complexity: "O(log n + m)",
+    arguments: &[
+        ArgumentDoc::new("start", ArgumentType::Key, false),
+        ArgumentDoc::new("end", ArgumentType::Key, false),
+        ArgumentDoc::new("limit", ArgumentType::Token, true),
+        ArgumentDoc::new("n", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+action!(
+    /// Run a `KEYRANGE` query. This returns a typed array with the keys between `start` and
+    /// `end` (both inclusive) in ascending order. The table needs to have been created with
+    /// the `ordered` property
+    ///
+    /// ## Syntax
+    /// - `KEYRANGE <start> <end>`
+    /// - `KEYRANGE <start> <end> LIMIT <n>`
+    fn keyrange(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2 || len == 4)?;
+        let (start, end) = unsafe {
+            // SAFETY: the length check above allows two or four arguments, so the bounds are there
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let limit = match act.next_uppercase().as_deref() {
+            None => usize::MAX,
+            Some(LIMIT) => {
+                let limit = unsafe {
+                    // SAFETY: LIMIT was given, so this is the fourth of four arguments
+                    act.next_unchecked()
+                };
+                match String::from_utf8_lossy(limit).parse::<usize>() {
+                    Ok(limit) => {
+                        ensure_boolean_or_aerr(limit != 0)?;
+                        limit
+                    }
+                    Err(_) => return util::err(groups::WRONGTYPE_ERR),
+                }
+            }
+            Some(_) => return util::err(groups::UNKNOWN_ACTION),
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if !kve.is_ordered() {
+            return util::err(groups::UNORDERED_TABLE);
+        }
+        let keys = match kve.key_range(start, end, limit) {
+            Ok(keys) => keys,
+            Err(()) => return util::err(groups::ENCODING_ERROR),
+        };
+        let mut writer = unsafe {
+            // SAFETY: The key tsymbol is verified by the table
+            TypedArrayWriter::new(con, kve.get_key_tsymbol(), keys.len())
+        }
+        .await?;
+        for key in keys {
+            writer.write_element(key).await?;
+        }
+        Ok(())
+    }
+);
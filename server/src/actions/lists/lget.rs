@@ -55,6 +55,21 @@ impl Range {
     }
 }
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LGET",
+    summary: "Get a list or some of its elements",
+    since: "0.7.1",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("list", ArgumentType::Key, false),
+        ArgumentDoc::new("subaction", ArgumentType::Token, true),
+        ArgumentDoc::new("args ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
 action! {
     /// Handle an `LGET` query for the list model (KVExt)
     /// ## Syntax
@@ -0,0 +1,59 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::dbnet::connection::prelude::*;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LLEN",
+    summary: "Get the number of elements in a list",
+    since: "0.8.0",
+    group: CommandGroup::List,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("list", ArgumentType::Key, false)],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle an `LLEN` query for the list model. This returns the length of the list
+    /// or `NIL` if it doesn't exist
+    /// ## Syntax
+    /// `LLEN <listname>`
+    fn llen(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let listmap = handle.get_table_with::<KVEList>()?;
+        let listname = unsafe {
+            // SAFETY: We have checked for there to be one arg
+            act.next_unchecked()
+        };
+        match listmap.list_len(listname) {
+            Ok(Some(len)) => conwrite!(con, len)?,
+            Ok(None) => conwrite!(con, groups::NIL)?,
+            Err(()) => conwrite!(con, groups::ENCODING_ERROR)?,
+        }
+        Ok(())
+    }
+}
@@ -27,22 +27,73 @@
 use super::{writer, OKAY_BADIDX_NIL_NLUT};
 use crate::corestore::Data;
 use crate::dbnet::connection::prelude::*;
+use crate::kvengine::list_items_estimate;
+use crate::resp::writer::TypedArrayWriter;
 use crate::util::compiler;
+use std::collections::HashSet;
 
 const CLEAR: &[u8] = "CLEAR".as_bytes();
+const REVERSE: &[u8] = "REVERSE".as_bytes();
+const ROTATE: &[u8] = "ROTATE".as_bytes();
 const PUSH: &[u8] = "PUSH".as_bytes();
+const PUSHFIRST: &[u8] = "PUSHFIRST".as_bytes();
+const PREPEND: &[u8] = "PREPEND".as_bytes();
 const REMOVE: &[u8] = "REMOVE".as_bytes();
 const INSERT: &[u8] = "INSERT".as_bytes();
+const REPLACE: &[u8] = "REPLACE".as_bytes();
 const POP: &[u8] = "POP".as_bytes();
+const POPCOUNT: &[u8] = "POPCOUNT".as_bytes();
+const SWAP: &[u8] = "SWAP".as_bytes();
+const SLICE: &[u8] = "SLICE".as_bytes();
+const TRIM: &[u8] = "TRIM".as_bytes();
+const CONTAINS: &[u8] = "CONTAINS".as_bytes();
+const COUNT: &[u8] = "COUNT".as_bytes();
+const FIND: &[u8] = "FIND".as_bytes();
+const DEDUP: &[u8] = "DEDUP".as_bytes();
+const CONSECUTIVE: &[u8] = "CONSECUTIVE".as_bytes();
+const SORT: &[u8] = "SORT".as_bytes();
+const ASC: &[u8] = "ASC".as_bytes();
+const DESC: &[u8] = "DESC".as_bytes();
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LMOD",
+    summary: "Modify a list",
+    since: "0.7.1",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("list", ArgumentType::Key, false),
+        ArgumentDoc::new("subaction", ArgumentType::Token, false),
+        ArgumentDoc::new("args ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::GrowsWith {
+        at: 2,
+        subactions: &["PUSH", "PUSHFIRST", "PREPEND", "INSERT", "REPLACE"],
+    },
+};
 
 action! {
     /// Handle `LMOD` queries
     /// ## Syntax
     /// - `LMOD <mylist> push <value>`
+    /// - `LMOD <mylist> pushfirst <value>` (or `prepend`)
     /// - `LMOD <mylist> pop <optional idx>`
+    /// - `LMOD <mylist> popcount <count>`
     /// - `LMOD <mylist> insert <index> <value>`
+    /// - `LMOD <mylist> replace <index> <value>`
     /// - `LMOD <mylist> remove <index>`
     /// - `LMOD <mylist> clear`
+    /// - `LMOD <mylist> reverse`
+    /// - `LMOD <mylist> rotate <count>`
+    /// - `LMOD <mylist> swap <index1> <index2>`
+    /// - `LMOD <mylist> slice <start> <stop>`
+    /// - `LMOD <mylist> trim <start> <stop>`
+    /// - `LMOD <mylist> sort <optional asc|desc>`
+    /// - `LMOD <mylist> contains <value>`
+    /// - `LMOD <mylist> count <value>`
+    /// - `LMOD <mylist> find <value> <optional from_idx>`
+    /// - `LMOD <mylist> dedup <optional consecutive>`
     fn lmod(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
         ensure_length(act.len(), |len| len > 1)?;
         let listmap = handle.get_table_with::<KVEList>()?;
@@ -65,7 +116,52 @@ action! {
                     _ => return conwrite!(con, groups::NIL),
                 };
                 let okay = if registry::state_okay() {
-                    list.write().clear();
+                    let mut wlock = list.write();
+                    listmap.account_memory(list_items_estimate(wlock.iter()), 0);
+                    wlock.clear();
+                    groups::OKAY
+                } else {
+                    groups::SERVER_ERR
+                };
+                conwrite!(con, okay)?;
+            }
+            REVERSE => {
+                ensure_length(act.len(), |len| len == 0)?;
+                let list = match listmap.get_inner_ref().get(listname) {
+                    Some(l) => l,
+                    _ => return conwrite!(con, groups::NIL),
+                };
+                let okay = if registry::state_okay() {
+                    list.write().reverse();
+                    groups::OKAY
+                } else {
+                    groups::SERVER_ERR
+                };
+                conwrite!(con, okay)?;
+            }
+            ROTATE => {
+                ensure_length(act.len(), |len| len == 1)?;
+                // a positive count rotates left and a negative count rotates right
+                let count = unsafe { String::from_utf8_lossy(act.next_unchecked()) };
+                let count = match count.parse::<i64>() {
+                    Ok(int) => int,
+                    Err(_) => return conwrite!(con, groups::WRONGTYPE_ERR),
+                };
+                let list = match listmap.get_inner_ref().get(listname) {
+                    Some(l) => l,
+                    _ => return conwrite!(con, groups::NIL),
+                };
+                let okay = if registry::state_okay() {
+                    let mut wlock = list.write();
+                    let len = wlock.len();
+                    if len > 1 {
+                        let mid = (count.unsigned_abs() % len as u64) as usize;
+                        if count > 0 {
+                            wlock.rotate_left(mid);
+                        } else {
+                            wlock.rotate_right(mid);
+                        }
+                    }
                     groups::OKAY
                 } else {
                     groups::SERVER_ERR
@@ -81,7 +177,35 @@ action! {
                 let venc_ok = listmap.get_val_encoder();
                 let ret = if compiler::likely(act.as_ref().all(venc_ok)) {
                     if registry::state_okay() {
-                        list.write().extend(act.map(Data::copy_from_slice));
+                        let mut wlock = list.write();
+                        let at = wlock.len();
+                        wlock.extend(act.map(Data::copy_from_slice));
+                        listmap.account_memory(0, list_items_estimate(&wlock[at..]));
+                        groups::OKAY
+                    } else {
+                        groups::SERVER_ERR
+                    }
+                } else {
+                    groups::ENCODING_ERROR
+                };
+                conwrite!(con, ret)?;
+            }
+            PUSHFIRST | PREPEND => {
+                ensure_boolean_or_aerr(!act.is_empty())?;
+                let list = match listmap.get_inner_ref().get(listname) {
+                    Some(l) => l,
+                    _ => return conwrite!(con, groups::NIL),
+                };
+                let venc_ok = listmap.get_val_encoder();
+                let ret = if compiler::likely(act.as_ref().all(venc_ok)) {
+                    if registry::state_okay() {
+                        // splice the values in at the front, so that the existing elements
+                        // are only shifted once (and we only reallocate once)
+                        let mut wlock = list.write();
+                        let count = act.len();
+                        wlock.reserve(count);
+                        wlock.splice(0..0, act.map(Data::copy_from_slice));
+                        listmap.account_memory(0, list_items_estimate(&wlock[..count]));
                         groups::OKAY
                     } else {
                         groups::SERVER_ERR
@@ -98,7 +222,8 @@ action! {
                     let maybe_value = listmap.get_inner_ref().get(listname).map(|list| {
                         let mut wlock = list.write();
                         if idx_to_remove < wlock.len() {
-                            wlock.remove(idx_to_remove);
+                            let removed = wlock.remove(idx_to_remove);
+                            listmap.account_memory(list_items_estimate([&removed]), 0);
                             true
                         } else {
                             false
@@ -121,7 +246,9 @@ action! {
                                 let mut wlock = list.write();
                                 if idx_to_insert_at < wlock.len() {
                                     // we can insert
-                                    wlock.insert(idx_to_insert_at, Data::copy_from_slice(bts));
+                                    let item = Data::copy_from_slice(bts);
+                                    listmap.account_memory(0, list_items_estimate([&item]));
+                                    wlock.insert(idx_to_insert_at, item);
                                     true
                                 } else {
                                     // oops, out of bounds
@@ -141,6 +268,40 @@ action! {
                 };
                 conwrite!(con, ret)?;
             }
+            REPLACE => {
+                ensure_length(act.len(), |len| len == 2)?;
+                let idx_to_replace = get_numeric_count!();
+                let bts = unsafe { act.next_unchecked() };
+                let ret = if compiler::likely(listmap.is_val_ok(bts)) {
+                    if registry::state_okay() {
+                        let maybe_replace = match listmap.get(listname) {
+                            Ok(lst) => lst.map(|list| {
+                                let mut wlock = list.write();
+                                match wlock.get_mut(idx_to_replace) {
+                                    Some(element) => {
+                                        // unlike insert, nothing is shifted
+                                        let item = Data::copy_from_slice(bts);
+                                        listmap.account_memory(
+                                            list_items_estimate([&*element]),
+                                            list_items_estimate([&item]),
+                                        );
+                                        *element = item;
+                                        true
+                                    }
+                                    None => false,
+                                }
+                            }),
+                            Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                        };
+                        OKAY_BADIDX_NIL_NLUT[maybe_replace]
+                    } else {
+                        groups::SERVER_ERR
+                    }
+                } else {
+                    groups::ENCODING_ERROR
+                };
+                conwrite!(con, ret)?;
+            }
             POP => {
                 ensure_length(act.len(), |len| len < 2)?;
                 let idx = if act.len() == 1 {
@@ -154,7 +315,7 @@ action! {
                     let maybe_pop = match listmap.get(listname) {
                         Ok(lst) => lst.map(|list| {
                             let mut wlock = list.write();
-                            if let Some(idx) = idx {
+                            let popped = if let Some(idx) = idx {
                                 if idx < wlock.len() {
                                     // so we can pop
                                     Some(wlock.remove(idx))
@@ -163,7 +324,9 @@ action! {
                                 }
                             } else {
                                 wlock.pop()
-                            }
+                            };
+                            listmap.account_memory(list_items_estimate(&popped), 0);
+                            popped
                         }),
                         Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
                     };
@@ -182,6 +345,223 @@ action! {
                     conwrite!(con, groups::SERVER_ERR)?;
                 }
             }
+            POPCOUNT => {
+                ensure_length(act.len(), |len| len == 1)?;
+                let count = get_numeric_count!();
+                if registry::state_okay() {
+                    let maybe_popped = match listmap.get(listname) {
+                        Ok(lst) => lst.map(|list| {
+                            let mut wlock = list.write();
+                            let at = wlock.len().saturating_sub(count);
+                            let mut popped = wlock.split_off(at);
+                            listmap.account_memory(list_items_estimate(&popped), 0);
+                            // return them in the order they were popped
+                            popped.reverse();
+                            popped
+                        }),
+                        Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                    };
+                    match maybe_popped {
+                        Some(popped) => unsafe {
+                            writer::write_typed_array(con, listmap.get_value_tsymbol(), &popped)
+                                .await?
+                        },
+                        None => conwrite!(con, groups::NIL)?,
+                    }
+                } else {
+                    conwrite!(con, groups::SERVER_ERR)?;
+                }
+            }
+            SWAP => {
+                ensure_length(act.len(), |len| len == 2)?;
+                let idx_a = get_numeric_count!();
+                let idx_b = get_numeric_count!();
+                if registry::state_okay() {
+                    let maybe_swap = match listmap.get(listname) {
+                        Ok(lst) => lst.map(|list| {
+                            // we hold the write lock for the whole swap, so no other writer
+                            // can interleave
+                            let mut wlock = list.write();
+                            if idx_a < wlock.len() && idx_b < wlock.len() {
+                                wlock.swap(idx_a, idx_b);
+                                true
+                            } else {
+                                false
+                            }
+                        }),
+                        Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                    };
+                    conwrite!(con, OKAY_BADIDX_NIL_NLUT[maybe_swap])?;
+                } else {
+                    conwrite!(con, groups::SERVER_ERR)?;
+                }
+            }
+            SLICE => {
+                ensure_length(act.len(), |len| len == 2)?;
+                let start = get_numeric_count!();
+                let stop = get_numeric_count!();
+                let maybe_items = match listmap.get(listname) {
+                    Ok(lst) => lst.map(|list| {
+                        let rlock = list.read();
+                        // `stop` is inclusive; clamp to the end of the list. If `start` is past
+                        // `stop` (or past the end) we just return an empty slice
+                        let stop = stop.saturating_add(1).min(rlock.len());
+                        rlock.get(start..stop).map(<[Data]>::to_vec).unwrap_or_default()
+                    }),
+                    Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                };
+                match maybe_items {
+                    Some(items) => writelist!(con, listmap, items),
+                    None => conwrite!(con, groups::NIL)?,
+                }
+            }
+            TRIM => {
+                ensure_length(act.len(), |len| len == 2)?;
+                let start = get_numeric_count!();
+                let stop = get_numeric_count!();
+                let list = match listmap.get_inner_ref().get(listname) {
+                    Some(l) => l,
+                    _ => return conwrite!(con, groups::NIL),
+                };
+                let okay = if registry::state_okay() {
+                    let mut wlock = list.write();
+                    let before = list_items_estimate(wlock.iter());
+                    // like in `slice`, `stop` is inclusive and is clamped to the end of the list
+                    let stop = stop.saturating_add(1).min(wlock.len());
+                    if start < stop {
+                        wlock.truncate(stop);
+                        wlock.drain(..start);
+                    } else {
+                        wlock.clear();
+                    }
+                    listmap.account_memory(before, list_items_estimate(wlock.iter()));
+                    groups::OKAY
+                } else {
+                    groups::SERVER_ERR
+                };
+                conwrite!(con, okay)?;
+            }
+            SORT => {
+                ensure_length(act.len(), |len| len < 2)?;
+                let descending = match act.next_uppercase().as_deref() {
+                    None | Some(ASC) => false,
+                    Some(DESC) => true,
+                    Some(_) => return conwrite!(con, groups::UNKNOWN_ACTION),
+                };
+                let list = match listmap.get_inner_ref().get(listname) {
+                    Some(l) => l,
+                    _ => return conwrite!(con, groups::NIL),
+                };
+                let okay = if registry::state_okay() {
+                    // lexicographic (byte) ordering
+                    let mut wlock = list.write();
+                    if descending {
+                        wlock.sort_unstable_by(|a, b| b.as_ref().cmp(a.as_ref()));
+                    } else {
+                        wlock.sort_unstable_by(|a, b| a.as_ref().cmp(b.as_ref()));
+                    }
+                    groups::OKAY
+                } else {
+                    groups::SERVER_ERR
+                };
+                conwrite!(con, okay)?;
+            }
+            CONTAINS => {
+                ensure_length(act.len(), |len| len == 1)?;
+                let bts = unsafe { act.next_unchecked() };
+                if compiler::unlikely(!listmap.is_val_ok(bts)) {
+                    return conwrite!(con, groups::ENCODING_ERROR);
+                }
+                // a pure read, so we don't care about the flush state
+                let maybe_found = match listmap.get(listname) {
+                    Ok(lst) => lst.map(|list| list.read().iter().any(|val| val.as_ref() == bts)),
+                    Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                };
+                match maybe_found {
+                    Some(found) => conwrite!(con, found as usize)?,
+                    None => conwrite!(con, groups::NIL)?,
+                }
+            }
+            COUNT => {
+                ensure_length(act.len(), |len| len == 1)?;
+                let bts = unsafe { act.next_unchecked() };
+                if compiler::unlikely(!listmap.is_val_ok(bts)) {
+                    return conwrite!(con, groups::ENCODING_ERROR);
+                }
+                // a pure read, so we don't care about the flush state
+                let maybe_count = match listmap.get(listname) {
+                    Ok(lst) => lst.map(|list| {
+                        list.read().iter().filter(|val| val.as_ref() == bts).count()
+                    }),
+                    Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                };
+                match maybe_count {
+                    Some(count) => conwrite!(con, count)?,
+                    None => conwrite!(con, groups::NIL)?,
+                }
+            }
+            FIND => {
+                ensure_length(act.len(), |len| len == 1 || len == 2)?;
+                let bts = unsafe { act.next_unchecked() };
+                let from = if act.len() == 1 {
+                    get_numeric_count!()
+                } else {
+                    0
+                };
+                if compiler::unlikely(!listmap.is_val_ok(bts)) {
+                    return conwrite!(con, groups::ENCODING_ERROR);
+                }
+                let maybe_idx = match listmap.get(listname) {
+                    Ok(lst) => lst.and_then(|list| {
+                        list.read()
+                            .iter()
+                            .skip(from)
+                            .position(|val| val.as_ref() == bts)
+                            .map(|pos| from + pos)
+                    }),
+                    Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+                };
+                match maybe_idx {
+                    Some(idx) => conwrite!(con, idx)?,
+                    // either the list doesn't exist, or there was no match
+                    None => conwrite!(con, groups::NIL)?,
+                }
+            }
+            DEDUP => {
+                ensure_length(act.len(), |len| len < 2)?;
+                let consecutive = match act.next_uppercase().as_deref() {
+                    None => false,
+                    Some(CONSECUTIVE) => true,
+                    Some(_) => return conwrite!(con, groups::UNKNOWN_ACTION),
+                };
+                let list = match listmap.get_inner_ref().get(listname) {
+                    Some(l) => l,
+                    _ => return conwrite!(con, groups::NIL),
+                };
+                let okay = if registry::state_okay() {
+                    let mut wlock = list.write();
+                    let before = list_items_estimate(wlock.iter());
+                    if consecutive {
+                        // like `uniq`; only adjacent duplicates are removed
+                        wlock.dedup();
+                    } else {
+                        // find the first occurrences first, since we can't hold on to the
+                        // elements while `retain` is removing them
+                        let keep: Vec<bool> = {
+                            let mut seen = HashSet::with_capacity(wlock.len());
+                            wlock.iter().map(|val| seen.insert(val.as_ref())).collect()
+                        };
+                        let mut keep = keep.into_iter();
+                        // `retain` visits the elements in order
+                        wlock.retain(|_| keep.next().unwrap_or(true));
+                    }
+                    listmap.account_memory(before, list_items_estimate(wlock.iter()));
+                    groups::OKAY
+                } else {
+                    groups::SERVER_ERR
+                };
+                conwrite!(con, okay)?;
+            }
             _ => conwrite!(con, groups::UNKNOWN_ACTION)?,
         }
         Ok(())
@@ -0,0 +1,158 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+
+const RANK: &[u8] = "RANK".as_bytes();
+const COUNT: &[u8] = "COUNT".as_bytes();
+const MAXLEN: &[u8] = "MAXLEN".as_bytes();
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LPOS",
+    summary: "Get the index of the matching elements in a list",
+    since: "0.8.0",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("list", ArgumentType::Key, false),
+        ArgumentDoc::new("element", ArgumentType::String, false),
+        ArgumentDoc::new("rank|count|maxlen", ArgumentType::Token, true),
+        ArgumentDoc::new("value", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+/// Find the indices of the elements of `list` that are equal to `element`. At most `maxlen`
+/// elements are compared (all of them if it is 0), starting from the head of the list (or
+/// from the tail for a negative `rank`), and the first `|rank| - 1` matches are skipped.
+/// At most `count` indices are returned (all of them if it is 0)
+fn positions(list: &[Data], element: &[u8], rank: i64, count: usize, maxlen: usize) -> Vec<usize> {
+    let maxlen = if maxlen == 0 { list.len() } else { maxlen };
+    let count = if count == 0 { usize::MAX } else { count };
+    let skip = (rank.unsigned_abs() - 1) as usize;
+    let is_match = |(_, item): &(usize, &Data)| item.as_ref() == element;
+    let items = list.iter().enumerate();
+    if rank > 0 {
+        items
+            .take(maxlen)
+            .filter(is_match)
+            .skip(skip)
+            .take(count)
+            .map(|(idx, _)| idx)
+            .collect()
+    } else {
+        items
+            .rev()
+            .take(maxlen)
+            .filter(is_match)
+            .skip(skip)
+            .take(count)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+action! {
+    /// Handle an `LPOS` query for the list model. This returns the index of the first
+    /// element that is equal to `element` (or `NIL` if there isn't one). With `COUNT`, the
+    /// indices of (at most) `count` matching elements are returned as a flat array instead
+    /// (all of them if `count` is 0). `RANK` skips the first `|rank| - 1` matches, searching
+    /// from the tail of the list if it is negative, and `MAXLEN` only compares the first
+    /// `maxlen` elements (from where the search starts). `NIL` is returned if the list
+    /// doesn't exist
+    /// ## Syntax
+    /// `LPOS <listname> <element> [RANK <rank>] [COUNT <count>] [MAXLEN <maxlen>]`
+    fn lpos(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len >= 2 && len % 2 == 0)?;
+        let (listname, element) = unsafe {
+            // SAFETY: We have checked for there to be at least two args
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let (mut rank, mut count, mut maxlen) = (1i64, None, 0usize);
+        while let Some(option) = act.next_uppercase() {
+            let value = unsafe {
+                // SAFETY: We have checked for the options to be in pairs
+                String::from_utf8_lossy(act.next_unchecked())
+            };
+            let parsed = match option.as_ref() {
+                RANK => value.parse().map(|r| rank = r).is_ok(),
+                COUNT => value.parse().map(|c| count = Some(c)).is_ok(),
+                MAXLEN => value.parse().map(|m| maxlen = m).is_ok(),
+                _ => return util::err(groups::UNKNOWN_ACTION),
+            };
+            if !parsed {
+                return util::err(groups::WRONGTYPE_ERR);
+            }
+        }
+        ensure_boolean_or_aerr(rank != 0)?;
+        let listmap = handle.get_table_with::<KVEList>()?;
+        let maybe_positions = listmap.get(listname).map(|list| {
+            list.map(|lst| positions(&lst.read(), element, rank, count.unwrap_or(1), maxlen))
+        });
+        let positions = match maybe_positions {
+            Ok(Some(positions)) => positions,
+            Ok(None) => return conwrite!(con, groups::NIL),
+            Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+        };
+        match (count, positions.first()) {
+            (None, Some(idx)) => con.write_response(*idx).await?,
+            (None, None) => con.write_response(groups::NIL).await?,
+            (Some(_), _) => {
+                con.write_flat_array_length(positions.len()).await?;
+                for idx in positions {
+                    con.write_response(idx).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_positions() {
+    let list: Vec<Data> = ["a", "b", "a", "c", "a"]
+        .into_iter()
+        .map(Data::from)
+        .collect();
+    // first match
+    assert_eq!(positions(&list, b"a", 1, 1, 0), vec![0]);
+    // nth match
+    assert_eq!(positions(&list, b"a", 2, 1, 0), vec![2]);
+    assert_eq!(positions(&list, b"a", 4, 1, 0), Vec::<usize>::new());
+    // from the tail
+    assert_eq!(positions(&list, b"a", -1, 1, 0), vec![4]);
+    assert_eq!(positions(&list, b"a", -2, 0, 0), vec![2, 0]);
+    // all matches
+    assert_eq!(positions(&list, b"a", 1, 0, 0), vec![0, 2, 4]);
+    assert_eq!(positions(&list, b"a", 1, 2, 0), vec![0, 2]);
+    // bounded scan
+    assert_eq!(positions(&list, b"c", 1, 1, 3), Vec::<usize>::new());
+    assert_eq!(positions(&list, b"c", 1, 1, 4), vec![3]);
+    assert_eq!(positions(&list, b"b", -1, 1, 3), Vec::<usize>::new());
+    assert_eq!(positions(&list, b"b", -1, 1, 4), vec![1]);
+}
@@ -0,0 +1,104 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+use crate::resp::writer;
+use core::ops::RangeInclusive;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LRANGE",
+    summary: "Get the elements of a list in the given range",
+    since: "0.8.0",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("list", ArgumentType::Key, false),
+        ArgumentDoc::new("start", ArgumentType::Integer, false),
+        ArgumentDoc::new("stop", ArgumentType::Integer, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+/// Turn the (inclusive) `start` and `stop` indices into a range of valid indices for a list
+/// with `len` elements. Negative indices count from the end of the list and out-of-bounds
+/// indices are clamped. Returns `None` if the range has no elements
+fn clamp_range(start: i64, stop: i64, len: usize) -> Option<RangeInclusive<usize>> {
+    let len = len as i64;
+    let start = if start < 0 { start + len } else { start }.max(0);
+    let stop = if stop < 0 { stop + len } else { stop }.min(len - 1);
+    if start > stop {
+        None
+    } else {
+        Some(start as usize..=stop as usize)
+    }
+}
+
+action! {
+    /// Handle an `LRANGE` query for the list model. This returns the elements from `start`
+    /// to `stop` (both inclusive) as a flat array, or `NIL` if the list doesn't exist
+    /// ## Syntax
+    /// `LRANGE <listname> <start> <stop>`
+    fn lrange(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 3)?;
+        let listmap = handle.get_table_with::<KVEList>()?;
+        let listname = unsafe {
+            // SAFETY: We have checked for there to be three args
+            act.next_unchecked()
+        };
+        let mut next_index = || {
+            unsafe { String::from_utf8_lossy(act.next_unchecked()) }.parse::<i64>().ok()
+        };
+        let (start, stop) = match (next_index(), next_index()) {
+            (Some(start), Some(stop)) => (start, stop),
+            _ => return util::err(groups::WRONGTYPE_ERR),
+        };
+        // only clone the elements while holding the lock; writing them out can take a while
+        let maybe_items = listmap.get(listname).map(|list| {
+            list.map(|lst| {
+                let lst = lst.read();
+                match clamp_range(start, stop, lst.len()) {
+                    Some(range) => lst[range].to_vec(),
+                    None => Vec::new(),
+                }
+            })
+        });
+        let items: Vec<Data> = match maybe_items {
+            Ok(Some(items)) => items,
+            Ok(None) => return conwrite!(con, groups::NIL),
+            Err(()) => return conwrite!(con, groups::ENCODING_ERROR),
+        };
+        con.write_flat_array_length(items.len()).await?;
+        for item in items {
+            unsafe {
+                // tsymbol is verified
+                writer::write_raw_mono(con, listmap.get_value_tsymbol(), &item).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+use crate::resp::writer;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LSLIST",
+    summary: "List the lists in the current table",
+    since: "0.8.0",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("cursor", ArgumentType::Integer, true),
+        ArgumentDoc::new("count", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle an `LSLIST` query for the list model. This returns the names of the lists in
+    /// the current table as a flat array. If a cursor and count are passed, at most `count`
+    /// names are returned, preceded by the cursor to pass to the next `LSLIST` (0 once all
+    /// the names have been returned)
+    /// ## Syntax
+    /// - `LSLIST`
+    /// - `LSLIST <cursor> <count>`
+    fn lslist(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 0 || len == 2)?;
+        let listmap = handle.get_table_with::<KVEList>()?;
+        let pagination = if act.is_empty() {
+            None
+        } else {
+            let (cursor, count) = unsafe {
+                // SAFETY: We have checked for there to be two args
+                (act.next_unchecked(), act.next_unchecked())
+            };
+            let cursor = String::from_utf8_lossy(cursor).parse::<u64>();
+            let count = String::from_utf8_lossy(count).parse::<usize>();
+            match (cursor, count) {
+                (Ok(cursor), Ok(count)) => {
+                    ensure_boolean_or_aerr(count != 0)?;
+                    Some((cursor, count))
+                }
+                _ => return util::err(groups::WRONGTYPE_ERR),
+            }
+        };
+        let list = listmap.get_inner_ref();
+        match pagination {
+            Some((cursor, count)) => {
+                let (keys, next_cursor) = list.scan_keys(cursor, count);
+                con.write_flat_array_length(keys.len() + 1).await?;
+                con.write_response(next_cursor).await?;
+                lslist_write_keys(con, listmap.get_key_tsymbol(), keys).await?;
+            }
+            None => {
+                let (keys, _) = list.scan_keys(0, usize::MAX);
+                con.write_flat_array_length(keys.len()).await?;
+                lslist_write_keys(con, listmap.get_key_tsymbol(), keys).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+action! {
+    /// Write the keys as the elements of a flat array
+    fn lslist_write_keys(con: &mut T, tsymbol: u8, keys: Vec<Data>) {
+        for key in keys {
+            unsafe {
+                // tsymbol is verified
+                writer::write_raw_mono(con, tsymbol, &key).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::actions::swapkeys::swap_response;
+use crate::dbnet::connection::prelude::*;
+use crate::util::compiler;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LSWAP",
+    summary: "Swap the contents of two lists",
+    since: "0.8.0",
+    group: CommandGroup::List,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("list1", ArgumentType::Key, false),
+        ArgumentDoc::new("list2", ArgumentType::Key, false),
+    ],
+    keys: KeySpec::new(1, 2, 1),
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle an `LSWAP` query. This atomically exchanges the contents of two existing lists
+    /// ## Syntax
+    /// `LSWAP <list1> <list2>`
+    fn lswap(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let listmap = handle.get_table_with::<KVEList>()?;
+        let (list1, list2) = unsafe {
+            // SAFETY: We have checked for there to be two args
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        if registry::state_okay() {
+            match listmap.swap_values(list1, list2) {
+                Ok(found) => conwrite!(con, swap_response(found))?,
+                Err(()) => compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+}
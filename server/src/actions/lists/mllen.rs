@@ -0,0 +1,61 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::dbnet::connection::prelude::*;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MLLEN",
+    summary: "Get the number of elements in multiple lists",
+    since: "0.8.0",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("list ...", ArgumentType::Key, false)],
+    keys: KeySpec::new(1, -1, 1),
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle an `MLLEN` query for the list model. This returns a flat array with the
+    /// length of every list, in the order in which they were requested, with `NIL` for
+    /// the lists that don't exist (or aren't correctly encoded for the table)
+    /// ## Syntax
+    /// `MLLEN <list1> <list2> ...`
+    fn mllen(handle: &Corestore, con: &mut T, act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len != 0)?;
+        let listmap = handle.get_table_with::<KVEList>()?;
+        let lens: Vec<Option<usize>> = act
+            .map(|listname| listmap.list_len(listname).ok().flatten())
+            .collect();
+        con.write_flat_array_length(lens.len()).await?;
+        for len in lens {
+            match len {
+                Some(len) => con.write_response(len).await?,
+                None => con.write_response(groups::NIL).await?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -28,7 +28,13 @@
 mod macros;
 // modules
 pub mod lget;
+pub mod llen;
 pub mod lmod;
+pub mod lpos;
+pub mod lrange;
+pub mod lslist;
+pub mod lswap;
+pub mod mllen;
 
 use crate::corestore::booltable::BytesBoolTable;
 use crate::corestore::booltable::BytesNicheLUT;
@@ -41,6 +47,20 @@ const OKAY_OVW_BLUT: BytesBoolTable = BytesBoolTable::new(groups::OKAY, groups::
 const OKAY_BADIDX_NIL_NLUT: BytesNicheLUT =
     BytesNicheLUT::new(groups::NIL, groups::OKAY, groups::LISTMAP_BAD_INDEX);
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LSET",
+    summary: "Create a list with the provided elements",
+    since: "0.7.1",
+    group: CommandGroup::List,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("list", ArgumentType::Key, false),
+        ArgumentDoc::new("value ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::Grows,
+};
+
 action! {
     /// Handle an `LSET` query for the list model
     /// Syntax: `LSET <listname> <values ...>`
@@ -48,15 +68,9 @@ action! {
         ensure_length(act.len(), |len| len > 0)?;
         let listmap = handle.get_table_with::<KVEList>()?;
         let listname = unsafe { act.next_unchecked_bytes() };
-        let list = listmap.get_inner_ref();
         if registry::state_okay() {
-            let did = if let Some(entry) = list.fresh_entry(listname.into()) {
-                let v: Vec<Data> = act.map(Data::copy_from_slice).collect();
-                entry.insert(LockedVec::new(v));
-                true
-            } else {
-                false
-            };
+            let v: Vec<Data> = act.map(Data::copy_from_slice).collect();
+            let did = listmap.set_unchecked(listname.into(), LockedVec::new(v));
             conwrite!(con, OKAY_OVW_BLUT[did])?;
         } else {
             conwrite!(con, groups::SERVER_ERR)?;
@@ -31,6 +31,20 @@ use crate::resp::writer::TypedArrayWriter;
 
 const DEFAULT_COUNT: usize = 10;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LSKEYS",
+    summary: "List the keys in a table",
+    since: "0.6.0",
+    group: CommandGroup::Generic,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("entity", ArgumentType::Entity, true),
+        ArgumentDoc::new("limit", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Run an `LSKEYS` query
     fn lskeys(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
@@ -66,13 +80,10 @@ action!(
             (get_tbl!(entity, handle, con), count)
         };
         let tsymbol = match table.get_model_ref() {
-            DataModel::KV(kv) => kv.get_value_tsymbol(),
-            DataModel::KVExtListmap(kv) => kv.get_value_tsymbol(),
-        };
-        let items: Vec<Data> = match table.get_model_ref() {
-            DataModel::KV(kv) => kv.get_inner_ref().get_keys(count),
-            DataModel::KVExtListmap(kv) => kv.get_inner_ref().get_keys(count),
+            DataModel::KV(kv) => kv.get_key_tsymbol(),
+            DataModel::KVExtListmap(kv) => kv.get_key_tsymbol(),
         };
+        let items: Vec<Data> = table.get_keys(count);
         let mut writer = unsafe {
             // SAFETY: We have checked kty ourselves
             TypedArrayWriter::new(con, tsymbol, items.len())
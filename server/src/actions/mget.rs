@@ -25,32 +25,36 @@
 */
 
 use crate::dbnet::connection::prelude::*;
-use crate::kvengine::encoding::ENCODING_LUT_ITER;
 use crate::queryengine::ActionIter;
 use crate::resp::writer::TypedArrayWriter;
-use crate::util::compiler;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MGET",
+    summary: "Get the values of multiple keys",
+    since: "0.4.0",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("key ...", ArgumentType::Key, false)],
+    keys: KeySpec::new(1, -1, 1),
+    memory: MemoryUse::None,
+};
 
 action!(
-    /// Run an `MGET` query
-    ///
+    /// Run an `MGET` query. Keys that don't exist (or aren't correctly encoded for the
+    /// table) are returned as nulls, in the order in which they were requested
     fn mget(handle: &crate::corestore::Corestore, con: &mut T, act: ActionIter<'a>) {
         ensure_length(act.len(), |size| size != 0)?;
         let kve = handle.get_table_with::<KVEBlob>()?;
-        let encoding_is_okay = ENCODING_LUT_ITER[kve.is_key_encoded()](act.as_ref());
-        if compiler::likely(encoding_is_okay) {
-            let mut writer = unsafe {
-                // SAFETY: We are getting the value type ourselves
-                TypedArrayWriter::new(con, kve.get_value_tsymbol(), act.len())
-            }
-            .await?;
-            for key in act {
-                match kve.get_cloned_unchecked(key) {
-                    Some(v) => writer.write_element(&v).await?,
-                    None => writer.write_null().await?,
-                }
+        let mut writer = unsafe {
+            // SAFETY: We are getting the value type ourselves
+            TypedArrayWriter::new(con, kve.get_value_tsymbol(), act.len())
+        }
+        .await?;
+        for key in act {
+            match kve.get_cloned(key) {
+                Ok(Some(v)) => writer.write_element(&v).await?,
+                Ok(None) | Err(_) => writer.write_null().await?,
             }
-        } else {
-            compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?;
         }
         Ok(())
     }
@@ -32,26 +32,45 @@
 
 #[macro_use]
 mod macros;
+pub mod append;
+pub mod command_docs;
+pub mod cset;
 pub mod dbsize;
 pub mod del;
 pub mod exists;
+pub mod expiry;
 pub mod flushdb;
 pub mod get;
+pub mod getex;
+pub mod getset;
+pub mod incr;
 pub mod keylen;
+pub mod keyrange;
 pub mod lists;
 pub mod lskeys;
 pub mod mget;
+pub mod move_key;
 pub mod mpop;
 pub mod mset;
 pub mod mupdate;
+pub mod object;
 pub mod pop;
+pub mod scan;
 pub mod set;
+pub mod setnx;
+pub mod setnxex;
+pub mod strlen;
 pub mod strong;
+pub mod swapkeys;
+pub mod ttl;
 pub mod update;
 pub mod uset;
 pub mod whereami;
+use self::command_docs::CommandDoc;
 use crate::corestore::memstore::DdlError;
-use crate::protocol::responses::groups;
+use crate::kvengine::json::JsonError;
+use crate::protocol::{responses::groups, UnsafeSlice};
+use crate::registry;
 use crate::util;
 use std::io::Error as IoError;
 
@@ -60,8 +79,11 @@ pub type ActionResult<T> = Result<T, ActionError>;
 
 /// Errors that can occur while running actions
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum ActionError {
     ActionError(&'static [u8]),
+    /// An error response that is built at runtime
+    DynamicError(Vec<u8>),
     IoError(std::io::Error),
 }
 
@@ -118,10 +140,62 @@ pub fn ensure_cond_or_err(cond: bool, err: &'static [u8]) -> ActionResult<()> {
     }
 }
 
+/// Ensure that the memory budget hasn't been exhausted if the query can grow memory usage (as
+/// told by [`CommandDoc::memory`]). `doc` is the documentation of the query's action. Every
+/// query is checked before it's run, so actions don't have to do this themselves
+pub fn ensure_memory_okay(doc: &CommandDoc, stage: &[UnsafeSlice]) -> ActionResult<()> {
+    if util::compiler::likely(registry::memory_okay()) {
+        return Ok(());
+    }
+    let arg = |idx: usize| {
+        stage.get(idx).map(|arg| unsafe {
+            // SAFETY: the stage is checked before the query is run, while its buffer is alive
+            arg.as_slice()
+        })
+    };
+    if doc.memory.grows(arg) {
+        Err(ActionError::DynamicError(memory_limit_err(
+            registry::memory_estimate(),
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the error string sent to clients when the memory budget is exhausted. This looks
+/// like: `!err-memory-limit:<estimate>:<budget>` (both in bytes)
+pub fn memory_limit_err(estimate: usize) -> Vec<u8> {
+    format!(
+        "!err-memory-limit:{}:{}\n",
+        estimate,
+        registry::memory_budget()
+    )
+    .into_bytes()
+}
+
+/// Returns the error string sent to clients when a value isn't well-formed JSON, for
+/// tables with the `validate:json` property. This looks like:
+/// `!err-invalid-json:<offset>:<reason>`
+pub fn invalid_json_err(e: JsonError) -> Vec<u8> {
+    format!("!err-invalid-json:{}:{}\n", e.offset, e.reason).into_bytes()
+}
+
 pub mod heya {
     //! Respond to `HEYA` queries
     use crate::dbnet::connection::prelude::*;
     use crate::resp::BytesWrapper;
+
+    pub const DOCS: CommandDoc = CommandDoc {
+        name: "HEYA",
+        summary: "Check if the server is alive, optionally echoing a message",
+        since: "0.1.0",
+        group: CommandGroup::Server,
+        complexity: "O(1)",
+        arguments: &[ArgumentDoc::new("message", ArgumentType::String, true)],
+        keys: KeySpec::NONE,
+        memory: MemoryUse::None,
+    };
+
     action!(
         /// Returns a `HEY!` `Response`
         fn heya(_handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
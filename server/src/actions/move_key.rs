@@ -0,0 +1,83 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::corestore::table::DescribeTable;
+use crate::dbnet::connection::prelude::*;
+use crate::queryengine::parser::Entity;
+use crate::util::compiler;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MOVE",
+    summary: "Move a key into another table",
+    since: "0.8.0",
+    group: CommandGroup::Generic,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("table", ArgumentType::Entity, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Run a `MOVE` query. This atomically moves a key and its value from the current table
+    /// into the destination table (which has to be in the current keyspace), returning 1 if the
+    /// key was moved and 0 if it doesn't exist in the current table or already exists in the
+    /// destination table
+    /// ## Syntax
+    /// `MOVE <key> <dest_table>`
+    fn move_key(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        let (key, raw_entity) = unsafe {
+            // SAFETY: We have checked for there to be two args
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let entity = handle_entity!(con, raw_entity);
+        if let Entity::Full(ksid, _) = entity {
+            // keys never leave their keyspace
+            match handle.get_ids() {
+                (Some(cks), _) if cks.eq(ksid) => {}
+                _ => return util::err(groups::CROSS_KEYSPACE),
+            }
+        }
+        let dest = get_tbl!(entity, handle, con);
+        let dest = match KVEBlob::try_get(&dest) {
+            Some(dest) => dest,
+            None => return util::err(groups::WRONG_MODEL),
+        };
+        if registry::state_okay() {
+            match kve.move_to(dest, key) {
+                Ok(moved) => conwrite!(con, moved as usize)?,
+                Err(()) => compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+}
@@ -32,6 +32,17 @@ use crate::queryengine::ActionIter;
 use crate::resp::writer::TypedArrayWriter;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MPOP",
+    summary: "Delete multiple keys and return their values",
+    since: "0.7.0",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("key ...", ArgumentType::Key, false)],
+    keys: KeySpec::new(1, -1, 1),
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Run an MPOP action
     fn mpop(handle: &corestore::Corestore, con: &mut T, act: ActionIter<'a>) {
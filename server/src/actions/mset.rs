@@ -29,27 +29,37 @@ use crate::dbnet::connection::prelude::*;
 use crate::kvengine::encoding::ENCODING_LUT_ITER_PAIR;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MSET",
+    summary: "Set multiple keys if they don't exist",
+    since: "0.4.0",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new(
+        "key value ...",
+        ArgumentType::String,
+        false,
+    )],
+    keys: KeySpec::new(1, -1, 2),
+    memory: MemoryUse::Grows,
+};
+
 action!(
     /// Run an `MSET` query
     fn mset(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
-        let howmany = act.len();
-        ensure_length(howmany, |size| size & 1 == 0 && size != 0)?;
+        ensure_length(act.len(), |size| size & 1 == 0 && size != 0)?;
         let kve = handle.get_table_with::<KVEBlob>()?;
         let encoding_is_okay = ENCODING_LUT_ITER_PAIR[kve.get_encoding_tuple()](&act);
         if compiler::likely(encoding_is_okay) {
-            let done_howmany: Option<usize> = if registry::state_okay() {
-                let mut didmany = 0;
+            if let Err(e) = kve.check_json_all(act.as_ref().skip(1).step_by(2)) {
+                return conwrite!(con, invalid_json_err(e));
+            }
+            if registry::state_okay() {
+                // keys that already exist are left as they are
                 while let (Some(key), Some(val)) = (act.next(), act.next()) {
-                    didmany += kve
-                        .set_unchecked(Data::copy_from_slice(key), Data::copy_from_slice(val))
-                        as usize;
+                    kve.set_unchecked(Data::copy_from_slice(key), Data::copy_from_slice(val));
                 }
-                Some(didmany)
-            } else {
-                None
-            };
-            if let Some(done_howmany) = done_howmany {
-                con.write_response(done_howmany as usize).await?;
+                con.write_response(responses::groups::OKAY).await?;
             } else {
                 con.write_response(responses::groups::SERVER_ERR).await?;
             }
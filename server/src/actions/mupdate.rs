@@ -29,6 +29,21 @@ use crate::dbnet::connection::prelude::*;
 use crate::kvengine::encoding::ENCODING_LUT_ITER_PAIR;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MUPDATE",
+    summary: "Update the values of multiple existing keys",
+    since: "0.4.0",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new(
+        "key value ...",
+        ArgumentType::String,
+        false,
+    )],
+    keys: KeySpec::new(1, -1, 2),
+    memory: MemoryUse::Grows,
+};
+
 action!(
     /// Run an `MUPDATE` query
     fn mupdate(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
@@ -38,6 +53,9 @@ action!(
         let encoding_is_okay = ENCODING_LUT_ITER_PAIR[kve.get_encoding_tuple()](&act);
         let done_howmany: Option<usize>;
         if compiler::likely(encoding_is_okay) {
+            if let Err(e) = kve.check_json_all(act.as_ref().skip(1).step_by(2)) {
+                return conwrite!(con, invalid_json_err(e));
+            }
             if registry::state_okay() {
                 let mut didmany = 0;
                 while let (Some(key), Some(val)) = (act.next(), act.next()) {
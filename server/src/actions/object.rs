@@ -0,0 +1,190 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `OBJECT` queries
+//! This module provides functions to inspect how values would be represented internally
+
+use crate::corestore::table::DataModel;
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+use crate::kvengine::encoding_registry::{self, EncodingFlags, TableModelType};
+use crate::util::compiler;
+
+const ENCODING: &[u8] = "ENCODING".as_bytes();
+
+/// The largest string (in bytes) that is a candidate for inline storage
+pub const EMBSTR_MAX_LEN: usize = 44;
+/// The most elements a list can have and still be a candidate for compact storage
+pub const LISTPACK_MAX_ENTRIES: usize = 128;
+/// The largest element (in bytes) that a list can have and still be a candidate for compact storage
+pub const LISTPACK_MAX_VALUE: usize = 64;
+/// An `i64` has at most 20 characters (including the sign)
+const INT_MAX_LEN: usize = 20;
+
+/// Returns true if the value is the canonical representation of an `i64`, that is: an optional
+/// `-` followed by digits, with no leading zeroes (and no `-0`)
+fn is_canonical_int(val: &[u8]) -> bool {
+    if val.is_empty() || val.len() > INT_MAX_LEN {
+        return false;
+    }
+    let digits = match val {
+        [b'-', digits @ ..] => digits,
+        digits => digits,
+    };
+    let canonical = match digits {
+        [] => false,
+        [b'0'] => digits.len() == val.len(),
+        [b'0', ..] => false,
+        _ => digits.iter().all(u8::is_ascii_digit),
+    };
+    // SAFETY: `canonical` is only true if every byte after the optional sign is an ASCII
+    // digit, which is valid UTF-8
+    canonical
+        && unsafe { core::str::from_utf8_unchecked(val) }
+            .parse::<i64>()
+            .is_ok()
+}
+
+/// Classifies a value like Redis does: an integer if it fits in an `i64`, compact for strings of
+/// up to 44 bytes and large otherwise
+fn classify_value(val: &[u8]) -> EncodingFlags {
+    if is_canonical_int(val) {
+        EncodingFlags::Int
+    } else if val.len() <= EMBSTR_MAX_LEN {
+        EncodingFlags::Compact
+    } else {
+        EncodingFlags::Large
+    }
+}
+
+/// Classifies a list like Redis does: compact if it has at most 128 elements, none of which
+/// is larger than 64 bytes, and large otherwise
+fn classify_list(list: &[Data]) -> EncodingFlags {
+    if list.len() <= LISTPACK_MAX_ENTRIES && list.iter().all(|v| v.len() <= LISTPACK_MAX_VALUE) {
+        EncodingFlags::Compact
+    } else {
+        EncodingFlags::Large
+    }
+}
+
+/// Returns the encoding of a value in a key/value table
+pub fn encoding_of(val: &[u8]) -> &'static str {
+    encoding_registry::type_name(TableModelType::KV, classify_value(val))
+}
+
+/// Returns the encoding of a list in a key/list table
+pub fn list_encoding_of(list: &[Data]) -> &'static str {
+    encoding_registry::type_name(TableModelType::KVExtListmap, classify_list(list))
+}
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "OBJECT",
+    summary: "Inspect how a value is represented",
+    since: "0.8.0",
+    group: CommandGroup::Generic,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("encoding", ArgumentType::Token, false),
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+    ],
+    keys: KeySpec::new(2, 2, 1),
+    memory: MemoryUse::None,
+};
+
+action!(
+    /// Run an `OBJECT` query
+    ///
+    /// ## Syntax
+    /// - `OBJECT ENCODING <key>`: returns the encoding of the value (or list)
+    fn object(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        match unsafe { act.next_uppercase_unchecked() }.as_ref() {
+            ENCODING => {
+                let key = unsafe { act.next_unchecked() };
+                let tbl = get_tbl_ref!(handle, con);
+                let encoding = match tbl.get_model_ref() {
+                    DataModel::KV(kve) => kve.get(key).map(|v| v.map(|val| encoding_of(&val))),
+                    DataModel::KVExtListmap(kve) => kve
+                        .get(key)
+                        .map(|v| v.map(|list| list_encoding_of(&list.read()))),
+                    #[allow(unreachable_patterns)]
+                    _ => return conwrite!(con, groups::WRONG_MODEL),
+                };
+                let encoding = match encoding {
+                    Ok(encoding) => encoding,
+                    Err(()) => return compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR)),
+                };
+                match encoding {
+                    Some(encoding) => conwrite!(con, encoding)?,
+                    None => conwrite!(con, groups::NIL)?,
+                }
+            }
+            _ => conwrite!(con, groups::UNKNOWN_ACTION)?,
+        }
+        Ok(())
+    }
+);
+
+#[test]
+fn test_encoding_of() {
+    assert_eq!(encoding_of(b"0"), "int");
+    assert_eq!(encoding_of(b"12345"), "int");
+    assert_eq!(encoding_of(b"-12345"), "int");
+    assert_eq!(encoding_of(b"9223372036854775807"), "int");
+    assert_eq!(encoding_of(b"-9223372036854775808"), "int");
+    // doesn't fit in an i64
+    assert_eq!(encoding_of(b"9223372036854775808"), "embstr");
+    // not canonical
+    assert_eq!(encoding_of(b"007"), "embstr");
+    assert_eq!(encoding_of(b"-0"), "embstr");
+    assert_eq!(encoding_of(b"+1"), "embstr");
+    assert_eq!(encoding_of(b""), "embstr");
+    assert_eq!(encoding_of(b"-"), "embstr");
+    assert_eq!(encoding_of(b"hello"), "embstr");
+    assert_eq!(encoding_of(&[b'x'; EMBSTR_MAX_LEN]), "embstr");
+    assert_eq!(encoding_of(&[b'x'; EMBSTR_MAX_LEN + 1]), "raw");
+    assert_eq!(encoding_of(&[b'1'; EMBSTR_MAX_LEN + 1]), "raw");
+}
+
+#[test]
+fn test_list_encoding_of() {
+    let small = Data::from("hello");
+    let big = Data::from(vec![b'x'; LISTPACK_MAX_VALUE + 1]);
+    assert_eq!(list_encoding_of(&[]), "listpack");
+    assert_eq!(
+        list_encoding_of(&[small.clone(), small.clone()]),
+        "listpack"
+    );
+    assert_eq!(
+        list_encoding_of(&vec![small.clone(); LISTPACK_MAX_ENTRIES]),
+        "listpack"
+    );
+    assert_eq!(
+        list_encoding_of(&vec![small.clone(); LISTPACK_MAX_ENTRIES + 1]),
+        "quicklist"
+    );
+    assert_eq!(list_encoding_of(&[small, big]), "quicklist");
+}
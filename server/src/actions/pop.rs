@@ -28,6 +28,25 @@ use crate::dbnet::connection::prelude::*;
 use crate::resp::writer;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "POP",
+    summary: "Delete a key and return its value",
+    since: "0.6.2",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("key", ArgumentType::Key, false)],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+/// `GETDEL` is an alias for `POP`
+pub const GETDEL_DOCS: CommandDoc = CommandDoc {
+    name: "GETDEL",
+    summary: "Delete a key and return its value (alias for POP)",
+    since: "0.8.0",
+    ..DOCS
+};
+
 action! {
     fn pop(handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
         ensure_length(act.len(), |len| len == 1)?;
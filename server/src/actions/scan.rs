@@ -0,0 +1,100 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `SCAN` queries
+//! This module provides functions to iterate over all the keys of a table in batches, without
+//! locking the whole table
+
+use crate::dbnet::connection::prelude::*;
+use crate::resp::writer::TypedArrayWriter;
+
+const COUNT: &[u8] = "COUNT".as_bytes();
+const DEFAULT_COUNT: usize = 10;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SCAN",
+    summary: "Iterate over the keys in a table with a cursor",
+    since: "0.8.0",
+    group: CommandGroup::Generic,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("cursor", ArgumentType::Integer, false),
+        ArgumentDoc::new("count", ArgumentType::Token, true),
+        ArgumentDoc::new("n", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+action!(
+    /// Run a `SCAN` query. This returns a typed array with the cursor to pass to the next
+    /// `SCAN` (0 once all the keys have been returned) followed by at most `n` keys. Every key
+    /// that is present for the entire duration of a scan is returned
+    ///
+    /// ## Syntax
+    /// - `SCAN <cursor>`
+    /// - `SCAN <cursor> COUNT <n>`
+    fn scan(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1 || len == 3)?;
+        let cursor = unsafe {
+            // SAFETY: the length check above allows one or three arguments, so the cursor is there
+            act.next_unchecked()
+        };
+        let cursor = match String::from_utf8_lossy(cursor).parse::<u64>() {
+            Ok(cursor) => cursor,
+            Err(_) => return util::err(groups::WRONGTYPE_ERR),
+        };
+        let count = match act.next_uppercase().as_deref() {
+            None => DEFAULT_COUNT,
+            Some(COUNT) => {
+                let count = unsafe {
+                    // SAFETY: COUNT was given, so this is the third of three arguments
+                    act.next_unchecked()
+                };
+                match String::from_utf8_lossy(count).parse::<usize>() {
+                    Ok(count) => {
+                        ensure_boolean_or_aerr(count != 0)?;
+                        count
+                    }
+                    Err(_) => return util::err(groups::WRONGTYPE_ERR),
+                }
+            }
+            Some(_) => return util::err(groups::UNKNOWN_ACTION),
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        let (keys, next_cursor) = kve.scan_live_keys(cursor, count);
+        let mut writer = unsafe {
+            // SAFETY: The key tsymbol is verified by the table
+            TypedArrayWriter::new(con, kve.get_key_tsymbol(), keys.len() + 1)
+        }
+        .await?;
+        writer.write_element(next_cursor.to_string()).await?;
+        for key in keys {
+            writer.write_element(key).await?;
+        }
+        Ok(())
+    }
+);
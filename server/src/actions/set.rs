@@ -36,6 +36,20 @@ use corestore::Data;
 const SET_NLUT: BytesNicheLUT =
     BytesNicheLUT::new(groups::ENCODING_ERROR, groups::OKAY, groups::OVERWRITE_ERR);
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SET",
+    summary: "Set a key if it doesn't exist",
+    since: "0.1.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("value", ArgumentType::String, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::Grows,
+};
+
 action!(
     /// Run a `SET` query
     fn set(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
@@ -43,14 +57,15 @@ action!(
         if registry::state_okay() {
             let did_we = {
                 let writer = handle.get_table_with::<KVEBlob>()?;
-                match unsafe {
+                let (key, val) = unsafe {
                     // UNSAFE(@ohsayan): This is completely safe as we've already checked
                     // that there are exactly 2 arguments
-                    writer.set(
-                        Data::copy_from_slice(act.next().unsafe_unwrap()),
-                        Data::copy_from_slice(act.next().unsafe_unwrap()),
-                    )
-                } {
+                    (act.next().unsafe_unwrap(), act.next().unsafe_unwrap())
+                };
+                if let Err(e) = writer.check_json(val) {
+                    return conwrite!(con, invalid_json_err(e));
+                }
+                match writer.set(Data::copy_from_slice(key), Data::copy_from_slice(val)) {
                     Ok(true) => Some(true),
                     Ok(false) => Some(false),
                     Err(()) => None,
@@ -0,0 +1,61 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `STRLEN` queries
+//! This module provides functions to work with `STRLEN` queries
+
+use crate::dbnet::connection::prelude::*;
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "STRLEN",
+    summary: "Get the length of the value of a key, or 0 if it doesn't exist",
+    since: "0.8.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("key", ArgumentType::Key, false)],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+action!(
+    /// Run a `STRLEN` query. Unlike `KEYLEN`, this returns 0 for keys that don't exist
+    ///
+    /// ## Syntax
+    /// `STRLEN <key>`
+    fn strlen(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        match kve.value_len(key) {
+            Ok(len) => con.write_response(len.unwrap_or(0)).await?,
+            Err(()) => conwrite!(con, groups::ENCODING_ERROR)?,
+        }
+        Ok(())
+    }
+);
@@ -35,7 +35,11 @@
 //! slow things down due to the checks performed.
 //! Do note that this isn't the same as the gurantees provided by ACID transactions
 
-pub use self::{sdel::sdel, sset::sset, supdate::supdate};
+pub use self::{
+    sdel::{sdel, DOCS as SDEL_DOCS},
+    sset::{sset, DOCS as SSET_DOCS},
+    supdate::{supdate, DOCS as SUPDATE_DOCS},
+};
 mod sdel;
 mod sset;
 mod supdate;
@@ -31,6 +31,17 @@ use crate::protocol::iter::DerefUnsafeSlice;
 use crate::util::compiler;
 use core::slice::Iter;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SDEL",
+    summary: "Delete multiple keys if all of them exist",
+    since: "0.4.3",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("key ...", ArgumentType::Key, false)],
+    keys: KeySpec::new(1, -1, 1),
+    memory: MemoryUse::None,
+};
+
 action! {
     /// Run an `SDEL` query
     ///
@@ -112,7 +123,6 @@ pub(super) fn snapshot_and_del<'a, T: 'a + DerefUnsafeSlice>(
         if iter_stat_ok {
             // nice, all keys exist; let's plonk 'em
             let kve = kve;
-            let lowtable = kve.get_inner_ref();
             act.zip(snapshots).for_each(|(key, snapshot)| {
                 let key = unsafe {
                     // UNSAFE(@ohsayan): The caller has passed a slice and they should
@@ -123,7 +133,7 @@ pub(super) fn snapshot_and_del<'a, T: 'a + DerefUnsafeSlice>(
                 // value after we snapshotted it. In that case, let this key
                 // be whatever the "newer" value is. Since our snapshot is a "happens-before"
                 // thing, this is absolutely fine
-                let _ = lowtable.remove_if(key, |_, val| val.eq(&snapshot));
+                let _ = kve.remove_if_unchecked(key, |val| val.eq(&snapshot));
             });
             StrongActionResult::Okay
         } else {
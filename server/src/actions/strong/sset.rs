@@ -33,6 +33,21 @@ use crate::protocol::iter::DerefUnsafeSlice;
 use crate::util::compiler;
 use core::slice::Iter;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SSET",
+    summary: "Set multiple keys if none of them exist",
+    since: "0.4.3",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new(
+        "key value ...",
+        ArgumentType::String,
+        false,
+    )],
+    keys: KeySpec::new(1, -1, 2),
+    memory: MemoryUse::Grows,
+};
+
 action! {
     /// Run an `SSET` query
     ///
@@ -42,6 +57,9 @@ action! {
         let howmany = act.len();
         ensure_length(howmany, |size| size & 1 == 0 && size != 0)?;
         let kve = handle.get_table_with::<KVEBlob>()?;
+        if let Err(e) = kve.check_json_all(act.as_ref().skip(1).step_by(2)) {
+            return conwrite!(con, invalid_json_err(e));
+        }
         if registry::state_okay() {
             let encoder = kve.get_double_encoder();
             let outcome = unsafe {
@@ -77,14 +95,13 @@ pub(super) fn snapshot_and_insert<'a, T: 'a + DerefUnsafeSlice>(
     mut act: Iter<'a, T>,
 ) -> StrongActionResult {
     let mut enc_err = false;
-    let lowtable = kve.get_inner_ref();
     let key_iter_stat_ok;
     {
         key_iter_stat_ok = act.as_ref().chunks_exact(2).all(|kv| unsafe {
             let key = ucidx!(kv, 0).deref_slice();
             let value = ucidx!(kv, 1).deref_slice();
             if compiler::likely(encoder(key, value)) {
-                lowtable.get(key).is_none()
+                !kve.exists_unchecked(key)
             } else {
                 enc_err = true;
                 false
@@ -100,16 +117,13 @@ pub(super) fn snapshot_and_insert<'a, T: 'a + DerefUnsafeSlice>(
     }
     if registry::state_okay() {
         if key_iter_stat_ok {
-            let _kve = kve;
-            let lowtable = lowtable;
             // fine, the keys were non-existent when we looked at them
             while let (Some(key), Some(value)) = (act.next(), act.next()) {
                 unsafe {
-                    if let Some(fresh) =
-                        lowtable.fresh_entry(Data::copy_from_slice(key.deref_slice()))
-                    {
-                        fresh.insert(Data::copy_from_slice(value.deref_slice()));
-                    }
+                    kve.set_unchecked(
+                        Data::copy_from_slice(key.deref_slice()),
+                        Data::copy_from_slice(value.deref_slice()),
+                    );
                     // we don't care if some other thread initialized the value we checked
                     // it. We expected a fresh entry, so that's what we'll check and use
                 }
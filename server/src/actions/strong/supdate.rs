@@ -33,6 +33,21 @@ use crate::protocol::iter::DerefUnsafeSlice;
 use crate::util::compiler;
 use core::slice::Iter;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SUPDATE",
+    summary: "Update multiple keys if all of them exist",
+    since: "0.4.3",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new(
+        "key value ...",
+        ArgumentType::String,
+        false,
+    )],
+    keys: KeySpec::new(1, -1, 2),
+    memory: MemoryUse::Grows,
+};
+
 action! {
     /// Run an `SUPDATE` query
     ///
@@ -42,6 +57,9 @@ action! {
         let howmany = act.len();
         ensure_length(howmany, |size| size & 1 == 0 && size != 0)?;
         let kve = handle.get_table_with::<KVEBlob>()?;
+        if let Err(e) = kve.check_json_all(act.as_ref().skip(1).step_by(2)) {
+            return conwrite!(con, invalid_json_err(e));
+        }
         if registry::state_okay() {
             let encoder = kve.get_double_encoder();
             let outcome = unsafe {
@@ -113,22 +131,17 @@ pub(super) fn snapshot_and_update<'a, T: 'a + DerefUnsafeSlice>(
             let kve = kve;
             // good, so all the values existed when we snapshotted them; let's update 'em
             let mut snap_cc = snapshots.into_iter();
-            let lowtable = kve.get_inner_ref();
             while let (Some(key), Some(value), Some(snapshot)) =
                 (act.next(), act.next(), snap_cc.next())
             {
                 unsafe {
                     // When we snapshotted, we looked at `snapshot`. If the value is still the
                     // same, then we'll update it. Otherwise, let it be
-                    if let Some(mut mutable) =
-                        lowtable.mut_entry(Data::copy_from_slice(key.deref_slice()))
-                    {
-                        if mutable.value().eq(&snapshot) {
-                            mutable.insert(Data::copy_from_slice(value.deref_slice()));
-                        } else {
-                            drop(mutable);
-                        }
-                    }
+                    let _ = kve.update_if_unchecked(
+                        Data::copy_from_slice(key.deref_slice()),
+                        Data::copy_from_slice(value.deref_slice()),
+                        |val| val.eq(&snapshot),
+                    );
                 }
             }
             StrongActionResult::Okay
@@ -0,0 +1,75 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::dbnet::connection::prelude::*;
+use crate::util::compiler;
+
+/// Returns the response for a swap, given if the first and the second key were found
+pub(super) const fn swap_response(found: (bool, bool)) -> &'static [u8] {
+    match found {
+        (true, true) => groups::OKAY,
+        (false, true) => groups::FIRST_KEY_NOT_FOUND,
+        (true, false) => groups::SECOND_KEY_NOT_FOUND,
+        (false, false) => groups::BOTH_KEYS_NOT_FOUND,
+    }
+}
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SWAPKEYS",
+    summary: "Swap the values of two keys",
+    since: "0.8.0",
+    group: CommandGroup::Generic,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key1", ArgumentType::Key, false),
+        ArgumentDoc::new("key2", ArgumentType::Key, false),
+    ],
+    keys: KeySpec::new(1, 2, 1),
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Run a `SWAPKEYS` query. This atomically exchanges the values of two existing keys
+    /// ## Syntax
+    /// `SWAPKEYS <key1> <key2>`
+    fn swapkeys(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        let (key1, key2) = unsafe {
+            // SAFETY: We have checked for there to be two args
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        if registry::state_okay() {
+            match kve.swap_values(key1, key2) {
+                Ok(found) => conwrite!(con, swap_response(found))?,
+                Err(()) => compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `EXPIRE`, `EXPIREAT`, `PERSIST`, `TTL` and `EXPIRETIME` queries
+//! This module provides functions to set, remove and inspect the deadlines of keys, along with
+//! their millisecond variants (like `PEXPIRE`). Deadlines of keys in persistent tables are
+//! flushed along with the tables
+
+use crate::actions::expiry;
+use crate::dbnet::connection::prelude::*;
+use crate::util::compiler;
+
+pub const EXPIRE_DOCS: CommandDoc = CommandDoc {
+    name: "EXPIRE",
+    summary: "Set a key to expire after the given number of seconds",
+    since: "0.8.0",
+    group: CommandGroup::Generic,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("seconds", ArgumentType::Integer, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::None,
+};
+
+pub const PEXPIRE_DOCS: CommandDoc = CommandDoc {
+    name: "PEXPIRE",
+    summary: "Set a key to expire after the given number of milliseconds",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("milliseconds", ArgumentType::Integer, false),
+    ],
+    ..EXPIRE_DOCS
+};
+
+pub const EXPIREAT_DOCS: CommandDoc = CommandDoc {
+    name: "EXPIREAT",
+    summary: "Set a key to expire at the given unix timestamp (in seconds)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("timestamp", ArgumentType::Integer, false),
+    ],
+    ..EXPIRE_DOCS
+};
+
+pub const PEXPIREAT_DOCS: CommandDoc = CommandDoc {
+    name: "PEXPIREAT",
+    summary: "Set a key to expire at the given unix timestamp (in milliseconds)",
+    ..EXPIREAT_DOCS
+};
+
+pub const PERSIST_DOCS: CommandDoc = CommandDoc {
+    name: "PERSIST",
+    summary: "Remove the expiry of a key",
+    arguments: &[ArgumentDoc::new("key", ArgumentType::Key, false)],
+    ..EXPIRE_DOCS
+};
+
+pub const TTL_DOCS: CommandDoc = CommandDoc {
+    name: "TTL",
+    summary: "Get the number of seconds left before a key expires",
+    ..PERSIST_DOCS
+};
+
+pub const PTTL_DOCS: CommandDoc = CommandDoc {
+    name: "PTTL",
+    summary: "Get the number of milliseconds left before a key expires",
+    ..PERSIST_DOCS
+};
+
+pub const EXPIRETIME_DOCS: CommandDoc = CommandDoc {
+    name: "EXPIRETIME",
+    summary: "Get the unix timestamp (in seconds) at which a key expires",
+    ..PERSIST_DOCS
+};
+
+pub const PEXPIRETIME_DOCS: CommandDoc = CommandDoc {
+    name: "PEXPIRETIME",
+    summary: "Get the unix timestamp (in milliseconds) at which a key expires",
+    ..PERSIST_DOCS
+};
+
+action!(
+    /// Run an `EXPIRE` query. Returns 1 if the deadline was set and 0 if the key doesn't exist
+    ///
+    /// ## Syntax
+    /// `EXPIRE <key> <seconds>`
+    fn expire(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, seconds) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the TTL
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let deadline = expiry::deadline_after_seconds(seconds)?;
+        set_deadline(handle, con, key, Some(deadline)).await
+    }
+    /// Run a `PEXPIRE` query. Returns 1 if the deadline was set and 0 if the key doesn't exist
+    ///
+    /// ## Syntax
+    /// `PEXPIRE <key> <milliseconds>`
+    fn pexpire(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, millis) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the TTL
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let deadline = expiry::deadline_after_millis(millis)?;
+        set_deadline(handle, con, key, Some(deadline)).await
+    }
+    /// Run an `EXPIREAT` query. Returns 1 if the deadline was set and 0 if the key doesn't
+    /// exist. If the timestamp has already passed, the key expires right away
+    ///
+    /// ## Syntax
+    /// `EXPIREAT <key> <timestamp>`
+    fn expireat(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, timestamp) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the timestamp
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let deadline = expiry::deadline_at_seconds(timestamp)?;
+        set_deadline(handle, con, key, Some(deadline)).await
+    }
+    /// Run a `PEXPIREAT` query. This is the same as `EXPIREAT`, except that the timestamp is
+    /// in milliseconds
+    ///
+    /// ## Syntax
+    /// `PEXPIREAT <key> <timestamp>`
+    fn pexpireat(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2)?;
+        let (key, timestamp) = unsafe {
+            // SAFETY: the length check above allows exactly two arguments, the key and the timestamp
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let deadline = expiry::deadline_at_millis(timestamp)?;
+        set_deadline(handle, con, key, Some(deadline)).await
+    }
+    /// Run a `PERSIST` query. Returns 1 if the key's deadline was removed and 0 if the key
+    /// doesn't exist or has no deadline
+    ///
+    /// ## Syntax
+    /// `PERSIST <key>`
+    fn persist(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        set_deadline(handle, con, key, None).await
+    }
+    /// Run a `TTL` query. Returns the number of seconds left before the key expires (rounded
+    /// down), `-1` if the key has no deadline and `-2` if it doesn't exist. Since Skyhash has
+    /// no signed integer type, negative values are written as strings
+    ///
+    /// ## Syntax
+    /// `TTL <key>`
+    fn ttl(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        write_deadline(handle, con, key, 1000, true).await
+    }
+    /// Run a `PTTL` query. This is the same as `TTL`, except that the time left is returned
+    /// in milliseconds
+    ///
+    /// ## Syntax
+    /// `PTTL <key>`
+    fn pttl(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        write_deadline(handle, con, key, 1, true).await
+    }
+    /// Run an `EXPIRETIME` query. Returns the unix timestamp (in seconds, rounded down) at
+    /// which the key expires, `-1` if the key has no deadline and `-2` if it doesn't exist
+    ///
+    /// ## Syntax
+    /// `EXPIRETIME <key>`
+    fn expiretime(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        write_deadline(handle, con, key, 1000, false).await
+    }
+    /// Run a `PEXPIRETIME` query. This is the same as `EXPIRETIME`, except that the timestamp
+    /// is returned in milliseconds
+    ///
+    /// ## Syntax
+    /// `PEXPIRETIME <key>`
+    fn pexpiretime(handle: &crate::corestore::Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        let key = unsafe {
+            // SAFETY: the length check above allows exactly one argument, the key
+            act.next_unchecked()
+        };
+        write_deadline(handle, con, key, 1, false).await
+    }
+);
+
+action!(
+    /// Write the key's deadline (or the time left before it, if `relative`) in units of
+    /// `unit` milliseconds (rounded down), or `-1`/`-2` if the key has no deadline or
+    /// doesn't exist
+    fn write_deadline(handle: &Corestore, con: &mut T, key: &[u8], unit: u64, relative: bool) {
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        let deadline = if relative {
+            kve.time_to_live(key)
+        } else {
+            kve.get_deadline(key)
+        };
+        match deadline {
+            Ok(Some(Some(millis))) => con.write_response(millis / unit).await?,
+            Ok(Some(None)) => con.write_response(StringWrapper("-1".to_owned())).await?,
+            Ok(None) => con.write_response(StringWrapper("-2".to_owned())).await?,
+            Err(_) => compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?,
+        }
+        Ok(())
+    }
+);
+
+action!(
+    /// Set (or remove, if `deadline` is `None`) the deadline of the key and write whether
+    /// anything changed
+    fn set_deadline(handle: &Corestore, con: &mut T, key: &[u8], deadline: Option<u64>) {
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if registry::state_okay() {
+            match kve.set_deadline(key, deadline) {
+                Ok(changed) => con.write_response(changed as usize).await?,
+                Err(_) => compiler::cold_err(conwrite!(con, groups::ENCODING_ERROR))?,
+            }
+        } else {
+            conwrite!(con, groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+);
@@ -35,6 +35,20 @@ use crate::dbnet::connection::prelude::*;
 const UPDATE_NLUT: BytesNicheLUT =
     BytesNicheLUT::new(groups::ENCODING_ERROR, groups::OKAY, groups::NIL);
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "UPDATE",
+    summary: "Update the value of an existing key",
+    since: "0.1.0",
+    group: CommandGroup::String,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("key", ArgumentType::Key, false),
+        ArgumentDoc::new("value", ArgumentType::String, false),
+    ],
+    keys: KeySpec::FIRST,
+    memory: MemoryUse::Grows,
+};
+
 action!(
     /// Run an `UPDATE` query
     fn update(handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
@@ -42,14 +56,15 @@ action!(
         if registry::state_okay() {
             let did_we = {
                 let writer = handle.get_table_with::<KVEBlob>()?;
-                match unsafe {
+                let (key, val) = unsafe {
                     // UNSAFE(@ohsayan): This is completely safe as we've already checked
                     // that there are exactly 2 arguments
-                    writer.update(
-                        Data::copy_from_slice(act.next_unchecked()),
-                        Data::copy_from_slice(act.next_unchecked()),
-                    )
-                } {
+                    (act.next_unchecked(), act.next_unchecked())
+                };
+                if let Err(e) = writer.check_json(val) {
+                    return conwrite!(con, invalid_json_err(e));
+                }
+                match writer.update(Data::copy_from_slice(key), Data::copy_from_slice(val)) {
                     Ok(true) => Some(true),
                     Ok(false) => Some(false),
                     Err(()) => None,
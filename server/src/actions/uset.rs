@@ -30,6 +30,21 @@ use crate::kvengine::encoding::ENCODING_LUT_ITER_PAIR;
 use crate::queryengine::ActionIter;
 use crate::util::compiler;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "USET",
+    summary: "Set or update multiple keys",
+    since: "0.4.4",
+    group: CommandGroup::String,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new(
+        "key value ...",
+        ArgumentType::String,
+        false,
+    )],
+    keys: KeySpec::new(1, -1, 2),
+    memory: MemoryUse::Grows,
+};
+
 action!(
     /// Run an `USET` query
     ///
@@ -40,6 +55,9 @@ action!(
         let kve = handle.get_table_with::<KVEBlob>()?;
         let encoding_is_okay = ENCODING_LUT_ITER_PAIR[kve.get_encoding_tuple()](&act);
         if compiler::likely(encoding_is_okay) {
+            if let Err(e) = kve.check_json_all(act.as_ref().skip(1).step_by(2)) {
+                return conwrite!(con, invalid_json_err(e));
+            }
             if registry::state_okay() {
                 while let (Some(key), Some(val)) = (act.next(), act.next()) {
                     kve.upsert_unchecked(Data::copy_from_slice(key), Data::copy_from_slice(val));
@@ -27,6 +27,17 @@
 use crate::dbnet::connection::prelude::*;
 use crate::resp::writer::NonNullArrayWriter;
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "WHEREAMI",
+    summary: "Get the current keyspace and table",
+    since: "0.7.0",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action! {
     fn whereami(store: &Corestore, con: &mut T, act: ActionIter<'a>) {
         ensure_length(act.len(), |len| len == 0)?;
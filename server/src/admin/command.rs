@@ -0,0 +1,185 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `COMMAND` queries
+//! This module provides functions to get information about the available actions
+
+use crate::actions::{
+    command_docs::{self, COMMAND_DOCS},
+    ActionResult,
+};
+use crate::dbnet::connection::prelude::*;
+use crate::resp::{
+    writer::{NonNullArrayWriter, TypedArrayWriter},
+    TSYMBOL_UNICODE_STRING,
+};
+
+const SUBACTION_COUNT: &[u8] = "COUNT".as_bytes();
+const SUBACTION_DOCS: &[u8] = "DOCS".as_bytes();
+const SUBACTION_GETKEYS: &[u8] = "GETKEYS".as_bytes();
+const SUBACTION_LIST: &[u8] = "LIST".as_bytes();
+const FILTERBY: &[u8] = "FILTERBY".as_bytes();
+const FILTER_PATTERN: &[u8] = "PATTERN".as_bytes();
+const FILTER_ACLCAT: &[u8] = "ACLCAT".as_bytes();
+const FILTER_MODULE: &[u8] = "MODULE".as_bytes();
+/// keys are returned as binary strings since they may not be unicode
+const TSYMBOL_BINARY: u8 = b'?';
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "COMMAND",
+    summary: "Get information about the available actions",
+    since: "0.8.0",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("count|docs|getkeys|list", ArgumentType::Token, false),
+        ArgumentDoc::new("args ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle `COMMAND` queries
+    /// ## Syntax
+    /// - `COMMAND DOCS <optional action1> <action2> ...`: returns an array with the documentation
+    /// of every provided action (or of all actions). Each entry is an array of the name, summary,
+    /// version in which it was added, group, complexity and an array of its arguments, which are
+    /// arrays of the name, type and whether it is optional (`1` or `0`). Unknown actions are
+    /// skipped
+    /// - `COMMAND GETKEYS <action> <arg1> <arg2> ...`: returns the keys that the provided query
+    /// would access, without running it
+    /// - `COMMAND LIST <optional FILTERBY PATTERN|ACLCAT|MODULE <filter>>`: returns the names of
+    /// all actions, or only of those whose names match the glob pattern or that belong to the
+    /// group (like `list`). Since there are no modules, filtering by module returns nothing
+    /// - `COMMAND COUNT <optional FILTERBY PATTERN|ACLCAT|MODULE <filter>>`: returns the number
+    /// of actions that `COMMAND LIST` would return for the same filter
+    fn command(_handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len != 0)?;
+        match unsafe { act.next_uppercase_unchecked() }.as_ref() {
+            SUBACTION_DOCS => {
+                let docs: Vec<&CommandDoc> = if act.is_empty() {
+                    COMMAND_DOCS.to_vec()
+                } else {
+                    act.filter_map(command_docs::find).collect()
+                };
+                con.write_array_length(docs.len()).await?;
+                for doc in docs {
+                    command_write_doc(con, doc).await?;
+                }
+            }
+            SUBACTION_GETKEYS => {
+                ensure_length(act.len(), |len| len != 0)?;
+                let query: Vec<&[u8]> = act.collect();
+                let doc = match command_docs::find(query[0]) {
+                    Some(doc) => doc,
+                    None => return util::err(groups::UNKNOWN_ACTION),
+                };
+                let positions = match doc.keys.key_positions(query.len()) {
+                    Some(positions) => positions,
+                    None => return util::err(groups::ACTION_ERR),
+                };
+                let mut writer = unsafe {
+                    // SAFETY: keys are always valid binary strings
+                    TypedArrayWriter::new(con, TSYMBOL_BINARY, positions.len())
+                }
+                .await?;
+                for position in positions {
+                    writer.write_element(query[position]).await?;
+                }
+            }
+            SUBACTION_COUNT => {
+                let count = command_filter_docs(&mut act)?.len();
+                con.write_response(count).await?;
+            }
+            SUBACTION_LIST => {
+                let names: Vec<&str> = command_filter_docs(&mut act)?
+                    .into_iter()
+                    .map(|doc| doc.name)
+                    .collect();
+                let mut writer = unsafe {
+                    // SAFETY: action names are always unicode
+                    NonNullArrayWriter::new(con, TSYMBOL_UNICODE_STRING, names.len())
+                }
+                .await?;
+                for name in names {
+                    writer.write_element(name).await?;
+                }
+            }
+            _ => return util::err(groups::UNKNOWN_ACTION),
+        }
+        Ok(())
+    }
+}
+
+/// Returns the documentation of all actions, or only of those matching the
+/// `FILTERBY PATTERN|ACLCAT|MODULE <filter>` that remains in `act`
+fn command_filter_docs(act: &mut ActionIter<'_>) -> ActionResult<Vec<&'static CommandDoc>> {
+    ensure_length(act.len(), |len| len == 0 || len == 3)?;
+    if ActionIter::is_empty(act) {
+        return Ok(COMMAND_DOCS.to_vec());
+    }
+    let (filterby, kind, filter) = unsafe {
+        // SAFETY: We have checked that there are exactly 3 arguments
+        (
+            act.next_uppercase_unchecked(),
+            act.next_uppercase_unchecked(),
+            act.next_uppercase_unchecked(),
+        )
+    };
+    ensure_boolean_or_aerr(filterby.as_ref() == FILTERBY)?;
+    let is_match: fn(&CommandDoc, &[u8]) -> bool = match kind.as_ref() {
+        FILTER_PATTERN => |doc, pattern| util::glob_match(pattern, doc.name.as_bytes()),
+        FILTER_ACLCAT => |doc, group| doc.group.as_str().as_bytes().eq_ignore_ascii_case(group),
+        FILTER_MODULE => |_, _| false,
+        _ => return util::err(groups::UNKNOWN_ACTION),
+    };
+    Ok(COMMAND_DOCS
+        .iter()
+        .copied()
+        .filter(|doc| is_match(doc, &filter))
+        .collect())
+}
+
+action! {
+    /// Write the documentation for an action as an array
+    fn command_write_doc(con: &mut T, doc: &'static CommandDoc) {
+        con.write_array_length(6).await?;
+        con.write_response(doc.name).await?;
+        con.write_response(doc.summary).await?;
+        con.write_response(doc.since).await?;
+        con.write_response(doc.group.as_str()).await?;
+        con.write_response(doc.complexity).await?;
+        con.write_array_length(doc.arguments.len()).await?;
+        for arg in doc.arguments {
+            con.write_array_length(3).await?;
+            con.write_response(arg.name).await?;
+            con.write_response(arg.kind.as_str()).await?;
+            con.write_response(arg.optional as usize).await?;
+        }
+        Ok(())
+    }
+}
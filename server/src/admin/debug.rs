@@ -0,0 +1,180 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Debugging helpers
+//!
+//! The `DEBUG` action has subactions that are only meant to be used while testing or
+//! benchmarking a server
+
+use crate::actions::{memory_limit_err, ActionError};
+use crate::corestore::Data;
+use crate::dbnet::connection::prelude::*;
+
+const POPULATE: &[u8] = "POPULATE".as_bytes();
+const STRINGMATCH_LEN: &[u8] = "STRINGMATCH-LEN".as_bytes();
+const NOCASE: &[u8] = "NOCASE".as_bytes();
+/// The prefix used for the keys created by `DEBUG POPULATE` if none is given
+const DEFAULT_POPULATE_PREFIX: &str = "key";
+/// The size of the values created by `DEBUG POPULATE` if none is given
+const DEFAULT_POPULATE_SIZE: usize = 16;
+/// The most keys that a single `DEBUG POPULATE` can set
+const MAX_POPULATE_COUNT: usize = 10_000_000;
+/// The largest value that `DEBUG POPULATE` can create (1 MiB)
+const MAX_POPULATE_SIZE: usize = 1024 * 1024;
+/// The characters used for the random values, so that they're valid in any table
+const POPULATE_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "DEBUG",
+    summary: "Run helpers for testing and benchmarking",
+    since: "0.8.0",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("populate|stringmatch-len", ArgumentType::Token, false),
+        ArgumentDoc::new("count|pattern", ArgumentType::String, false),
+        ArgumentDoc::new("prefix|string", ArgumentType::String, true),
+        ArgumentDoc::new("size|nocase", ArgumentType::String, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::GrowsWith {
+        at: 1,
+        subactions: &["POPULATE"],
+    },
+};
+
+/// Returns a random value of `size` alphanumeric characters
+fn random_value(size: usize) -> Data {
+    let mut bytes = vec![0; size];
+    openssl::rand::rand_bytes(&mut bytes).unwrap();
+    bytes
+        .iter_mut()
+        .for_each(|b| *b = POPULATE_CHARSET[*b as usize % POPULATE_CHARSET.len()]);
+    Data::from(bytes)
+}
+
+action! {
+    /// Handle `DEBUG` queries
+    /// ## Syntax
+    /// - `DEBUG POPULATE <count> <optional prefix> <optional size>`: sets the keys
+    /// `<prefix>:0` to `<prefix>:<count - 1>` in the current table to random values of
+    /// `size` bytes, returning the number of keys that were set. Keys that already exist are
+    /// left untouched. The prefix defaults to `key` and the size to 16. The budget is checked
+    /// before every key is set; if it runs out, the keys set till then are kept and the
+    /// memory limit error is returned
+    /// - `DEBUG STRINGMATCH-LEN <pattern> <string> <optional NOCASE>`: returns whether the
+    /// string matches the glob pattern (used by `COMMAND LIST` and ACL key patterns) and the
+    /// number of bytes of the string that the pattern consumed, as a flat array
+    fn debug(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| (2..=4).contains(&len))?;
+        match unsafe { act.next_uppercase_unchecked() }.as_ref() {
+            POPULATE => debug_populate(handle, con, act).await?,
+            STRINGMATCH_LEN => debug_stringmatch_len(handle, con, act).await?,
+            _ => return util::err(groups::UNKNOWN_ACTION),
+        }
+        Ok(())
+    }
+}
+
+action! {
+    fn debug_stringmatch_len(_handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2 || len == 3)?;
+        let (pattern, input) = unsafe {
+            // SAFETY: the length check above allows two or three arguments, so the pattern and
+            // the input are there
+            (act.next_unchecked(), act.next_unchecked())
+        };
+        let nocase = match act.next_uppercase().as_deref() {
+            None => false,
+            Some(NOCASE) => true,
+            Some(_) => return util::err(groups::UNKNOWN_ACTION),
+        };
+        let (matched, consumed) = util::glob_match_len(pattern, input, nocase);
+        con.write_flat_array_length(2).await?;
+        con.write_response(matched as usize).await?;
+        con.write_response(consumed).await?;
+        Ok(())
+    }
+    fn debug_populate(handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        let count = unsafe { act.next_unchecked() };
+        let count = match String::from_utf8_lossy(count).parse::<usize>() {
+            Ok(count) => count,
+            Err(_) => return util::err(groups::WRONGTYPE_ERR),
+        };
+        let prefix = act.next().unwrap_or(DEFAULT_POPULATE_PREFIX.as_bytes());
+        let size = match act.next_string_owned() {
+            Some(size) => match size.parse::<usize>() {
+                Ok(size) => size,
+                Err(_) => return util::err(groups::WRONGTYPE_ERR),
+            },
+            None => DEFAULT_POPULATE_SIZE,
+        };
+        if count > MAX_POPULATE_COUNT || size > MAX_POPULATE_SIZE {
+            return util::err(groups::OVERFLOW_ERR);
+        }
+        let kve = handle.get_table_with::<KVEBlob>()?;
+        if !kve.is_key_ok(prefix) {
+            return util::err(groups::ENCODING_ERROR);
+        }
+        if registry::state_okay() {
+            // the registry's estimate is only refreshed periodically, so add whatever we've
+            // set so far to it
+            let (budget, estimate) = (registry::memory_budget(), registry::memory_estimate());
+            let table_estimate = kve.memory_estimate();
+            let estimate_now =
+                || estimate + kve.memory_estimate().saturating_sub(table_estimate);
+            let mut key = prefix.to_vec();
+            key.push(b':');
+            let prefix_len = key.len();
+            let mut didmany = 0;
+            for i in 0..count {
+                let current_estimate = estimate_now();
+                if !registry::memory_okay() || (budget != 0 && current_estimate > budget) {
+                    return Err(ActionError::DynamicError(memory_limit_err(current_estimate)));
+                }
+                let val = random_value(size);
+                if let Err(e) = kve.check_json(&val) {
+                    return conwrite!(con, invalid_json_err(e));
+                }
+                key.truncate(prefix_len);
+                key.extend_from_slice(i.to_string().as_bytes());
+                didmany += kve.set_unchecked(Data::copy_from_slice(&key), val) as usize;
+            }
+            con.write_response(didmany).await?;
+        } else {
+            con.write_response(groups::SERVER_ERR).await?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_random_value() {
+    let value = random_value(32);
+    assert_eq!(value.len(), 32);
+    assert!(value.iter().all(|b| POPULATE_CHARSET.contains(b)));
+    assert!(random_value(0).is_empty());
+}
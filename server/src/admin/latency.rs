@@ -0,0 +1,236 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Latency monitoring
+//!
+//! The latency monitor keeps the most recent execution times (in microseconds) for every action
+//! in a fixed-size ring, along with a histogram of all the execution times. Recording a sample is
+//! just a couple of relaxed atomic stores so that the dispatch path isn't slowed down
+
+use crate::corestore::{htable::Coremap, lazy::Lazy};
+use crate::dbnet::connection::prelude::*;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// The number of samples kept per action
+pub const LATENCY_HISTORY_LEN: usize = 160;
+/// The upper bounds (exclusive, in microseconds) of the histogram buckets; the last bucket has
+/// everything else
+const LATENCY_BUCKET_BOUNDS: [u64; LATENCY_BUCKETS - 1] = [100, 1_000, 10_000, 100_000];
+/// The number of histogram buckets: <100µs, <1ms, <10ms, <100ms and >=100ms
+pub const LATENCY_BUCKETS: usize = 5;
+/// The default width of a latency graph
+const DEFAULT_GRAPH_WIDTH: usize = 40;
+/// The blocks used to draw a sparkline (from the lowest to the highest)
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const GRAPH: &[u8] = "GRAPH".as_bytes();
+const ORD_RLX: Ordering = Ordering::Relaxed;
+
+type LatencyMap = Coremap<&'static str, LatencyHistory>;
+
+static LATENCY_MAP: Lazy<LatencyMap, fn() -> LatencyMap> = Lazy::new(Coremap::new);
+
+/// A ring of the most recent latency samples for an action
+pub struct LatencyHistory {
+    samples: [AtomicU64; LATENCY_HISTORY_LEN],
+    /// the total number of samples ever recorded
+    cursor: AtomicUsize,
+    /// the number of samples that fell in each bucket
+    buckets: [AtomicU64; LATENCY_BUCKETS],
+}
+
+impl LatencyHistory {
+    pub fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)] // only used to initialize the arrays
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        Self {
+            samples: [ZERO; LATENCY_HISTORY_LEN],
+            cursor: AtomicUsize::new(0),
+            buckets: [ZERO; LATENCY_BUCKETS],
+        }
+    }
+    /// Record a sample (in microseconds)
+    pub fn record(&self, sample: u64) {
+        let pos = self.cursor.fetch_add(1, ORD_RLX) % LATENCY_HISTORY_LEN;
+        self.samples[pos].store(sample, ORD_RLX);
+        let bucket = LATENCY_BUCKET_BOUNDS
+            .iter()
+            .position(|bound| sample < *bound)
+            .unwrap_or(LATENCY_BUCKETS - 1);
+        self.buckets[bucket].fetch_add(1, ORD_RLX);
+    }
+    /// Returns the number of samples in each histogram bucket
+    pub fn histogram(&self) -> [u64; LATENCY_BUCKETS] {
+        let mut histogram = [0; LATENCY_BUCKETS];
+        for (count, bucket) in histogram.iter_mut().zip(self.buckets.iter()) {
+            *count = bucket.load(ORD_RLX);
+        }
+        histogram
+    }
+    /// Returns at most `count` of the most recent samples, from the oldest to the newest
+    pub fn recent(&self, count: usize) -> Vec<u64> {
+        let total = self.cursor.load(ORD_RLX);
+        let count = count.min(total).min(LATENCY_HISTORY_LEN);
+        (total - count..total)
+            .map(|pos| self.samples[pos % LATENCY_HISTORY_LEN].load(ORD_RLX))
+            .collect()
+    }
+}
+
+/// Record the execution time of an action
+pub fn record(action: &'static str, elapsed: Duration) {
+    let sample = elapsed.as_micros() as u64;
+    match LATENCY_MAP.get(action) {
+        Some(history) => history.record(sample),
+        None => {
+            // first sample for this action (a racing insert is fine; we'll just use theirs)
+            LATENCY_MAP.true_if_insert(action, LatencyHistory::new());
+            if let Some(history) = LATENCY_MAP.get(action) {
+                history.record(sample);
+            }
+        }
+    }
+}
+
+/// Returns the latency histogram of an action, if it was ever run
+pub fn histogram(action: &str) -> Option<[u64; LATENCY_BUCKETS]> {
+    LATENCY_MAP.get(action).map(|history| history.histogram())
+}
+
+/// Returns the latency histograms of all the actions that were ever run, sorted by name
+pub fn histograms() -> Vec<(&'static str, [u64; LATENCY_BUCKETS])> {
+    let mut histograms: Vec<_> = LATENCY_MAP
+        .iter()
+        .map(|kv| (*kv.key(), kv.value().histogram()))
+        .collect();
+    histograms.sort_unstable_by_key(|(action, _)| *action);
+    histograms
+}
+
+/// Draw a sparkline for the given samples, scaled between the lowest and the highest sample
+pub fn sparkline(samples: &[u64]) -> String {
+    let min = samples.iter().copied().min().unwrap_or(0);
+    let max = samples.iter().copied().max().unwrap_or(0);
+    let range = max - min;
+    samples
+        .iter()
+        .map(|sample| {
+            // all the samples get the lowest spark if they're the same
+            let idx = ((sample - min) * (SPARKS.len() as u64 - 1))
+                .checked_div(range)
+                .unwrap_or(0);
+            SPARKS[idx as usize]
+        })
+        .collect()
+}
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "LATENCY",
+    summary: "Inspect the recent execution times of an action",
+    since: "0.8.0",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("graph", ArgumentType::Token, false),
+        ArgumentDoc::new("action", ArgumentType::String, false),
+        ArgumentDoc::new("width", ArgumentType::Integer, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle `LATENCY` queries
+    /// ## Syntax
+    /// - `LATENCY GRAPH <action> <optional width>`: returns a sparkline of the most recent
+    /// execution times of the action. The width defaults to 40 and can be at most 160
+    fn latency(_handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 2 || len == 3)?;
+        match unsafe { act.next_uppercase_unchecked() }.as_ref() {
+            GRAPH => {
+                let action = unsafe { act.next_uppercase_unchecked() };
+                let width = match act.next_string_owned() {
+                    Some(width) => match width.parse::<usize>() {
+                        Ok(width) if width != 0 && width <= LATENCY_HISTORY_LEN => width,
+                        _ => return util::err(groups::WRONGTYPE_ERR),
+                    },
+                    None => DEFAULT_GRAPH_WIDTH,
+                };
+                let samples = match String::from_utf8(action.to_vec()) {
+                    Ok(action) => LATENCY_MAP
+                        .get(action.as_str())
+                        .map(|history| history.recent(width)),
+                    Err(_) => None,
+                };
+                match samples {
+                    Some(samples) if !samples.is_empty() => {
+                        con.write_response(StringWrapper(sparkline(&samples))).await?
+                    }
+                    _ => con.write_response(groups::NIL).await?,
+                }
+            }
+            _ => return util::err(groups::UNKNOWN_ACTION),
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_latency_history_ring() {
+    let history = LatencyHistory::new();
+    assert!(history.recent(10).is_empty());
+    history.record(1);
+    history.record(2);
+    assert_eq!(history.recent(10), vec![1, 2]);
+    assert_eq!(history.recent(1), vec![2]);
+    for sample in 0..(LATENCY_HISTORY_LEN as u64 + 5) {
+        history.record(sample);
+    }
+    let recent = history.recent(LATENCY_HISTORY_LEN);
+    assert_eq!(recent.len(), LATENCY_HISTORY_LEN);
+    assert_eq!(recent[0], 5);
+    assert_eq!(*recent.last().unwrap(), LATENCY_HISTORY_LEN as u64 + 4);
+}
+
+#[test]
+fn test_latency_histogram() {
+    let history = LatencyHistory::new();
+    assert_eq!(history.histogram(), [0; LATENCY_BUCKETS]);
+    let samples = [0, 99, 100, 999, 1_000, 9_999, 10_000, 99_999, 100_000];
+    for sample in samples.into_iter().chain([u64::MAX]) {
+        history.record(sample);
+    }
+    history.record(50);
+    assert_eq!(history.histogram(), [3, 2, 2, 2, 2]);
+}
+
+#[test]
+fn test_sparkline() {
+    assert_eq!(sparkline(&[]), "");
+    assert_eq!(sparkline(&[5, 5, 5]), "▁▁▁");
+    assert_eq!(sparkline(&[0, 7, 14]), "▁▄█");
+    assert_eq!(sparkline(&[10, 80, 30, 20]), "▁█▃▂");
+}
@@ -0,0 +1,93 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Memory introspection
+//!
+//! The `MEMORY` action reports details about the server's memory usage
+
+use crate::dbnet::connection::prelude::*;
+
+const MALLOC_STATS: &[u8] = "MALLOC-STATS".as_bytes();
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MEMORY",
+    summary: "Inspect the memory usage of the server",
+    since: "0.8.0",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("malloc-stats", ArgumentType::Token, false)],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+#[cfg(all(not(target_env = "msvc"), not(miri)))]
+/// Returns the statistics printed by jemalloc
+fn malloc_stats() -> String {
+    use core::ffi::{c_char, c_void};
+    use std::ffi::CStr;
+    unsafe extern "C" fn write_cb(out: *mut c_void, msg: *const c_char) {
+        // SAFETY: `out` is the `Vec` that malloc_stats_print was given below, and jemalloc
+        // always passes a nul-terminated message
+        let out = &mut *(out as *mut Vec<u8>);
+        out.extend_from_slice(CStr::from_ptr(msg).to_bytes());
+    }
+    let mut out: Vec<u8> = Vec::new();
+    unsafe {
+        jemalloc_sys::malloc_stats_print(
+            Some(write_cb),
+            &mut out as *mut Vec<u8> as *mut c_void,
+            core::ptr::null(),
+        );
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(not(all(not(target_env = "msvc"), not(miri))))]
+/// The system allocator doesn't have any statistics to report
+fn malloc_stats() -> String {
+    "Stats not supported for the current allocator".to_owned()
+}
+
+action! {
+    /// Handle `MEMORY` queries
+    /// ## Syntax
+    /// - `MEMORY MALLOC-STATS`: returns the statistics reported by the memory allocator
+    fn memory(_handle: &Corestore, con: &mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |len| len == 1)?;
+        match unsafe { act.next_uppercase_unchecked() }.as_ref() {
+            MALLOC_STATS => con.write_response(StringWrapper(malloc_stats())).await?,
+            _ => return util::err(groups::UNKNOWN_ACTION),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(not(target_env = "msvc"), not(miri)))]
+#[test]
+fn test_malloc_stats() {
+    let stats = malloc_stats();
+    assert!(stats.contains("jemalloc statistics"));
+}
@@ -30,6 +30,17 @@ use crate::storage::v1::sengine::SnapshotActionResult;
 use core::str;
 use std::path::{Component, PathBuf};
 
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "MKSNAP",
+    summary: "Create a snapshot",
+    since: "0.4.5",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[ArgumentDoc::new("snapname", ArgumentType::String, true)],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action!(
     /// Create a snapshot
     ///
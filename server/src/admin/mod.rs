@@ -26,5 +26,9 @@
 
 //! Modules for administration of Skytable
 
+pub mod command;
+pub mod debug;
+pub mod latency;
+pub mod memory;
 pub mod mksnap;
 pub mod sys;
@@ -25,46 +25,138 @@
 */
 
 use crate::{
+    admin::latency,
     corestore::booltable::BoolTable,
     dbnet::connection::prelude::*,
     protocol::{PROTOCOL_VERSION, PROTOCOL_VERSIONSTRING},
-    storage::v1::interface::DIR_ROOT,
+    resp::{writer::NonNullArrayWriter, TSYMBOL_UNICODE_STRING},
+    storage::v1::{interface::DIR_ROOT, migrate},
 };
 use ::libsky::VERSION;
 
 const INFO: &[u8] = b"info";
 const METRIC: &[u8] = b"metric";
+const MIGRATESTATUS: &[u8] = b"migratestatus";
 const INFO_PROTOCOL: &[u8] = b"protocol";
 const INFO_PROTOVER: &[u8] = b"protover";
 const INFO_VERSION: &[u8] = b"version";
+const INFO_UPTIME: &[u8] = b"uptime";
+const INFO_STARTUP_TIME: &[u8] = b"startup_time";
+const INFO_TTL_PERSISTENCE: &[u8] = b"ttl_persistence";
 const METRIC_HEALTH: &[u8] = b"health";
 const METRIC_STORAGE_USAGE: &[u8] = b"storage";
+const METRIC_MEMORY_BUDGET: &[u8] = b"memory-budget";
+const METRIC_CONNECTIONS: &[u8] = b"connections";
+const METRIC_QUERIES: &[u8] = b"queries";
+const METRIC_LATENCY: &[u8] = b"latency";
 const ERR_UNKNOWN_PROPERTY: &[u8] = b"!16\nunknown-property\n";
 const ERR_UNKNOWN_METRIC: &[u8] = b"!14\nunknown-metric\n";
 
 const HEALTH_TABLE: BoolTable<&str> = BoolTable::new("good", "critical");
+const MEMORY_BUDGET_TABLE: BoolTable<&str> = BoolTable::new("within-budget", "over-budget");
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "SYS",
+    summary: "Get information about the system and its metrics",
+    since: "0.7.5",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("info|metric|migratestatus", ArgumentType::Token, false),
+        ArgumentDoc::new("property", ArgumentType::String, true),
+        ArgumentDoc::new("arg", ArgumentType::String, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
 
 action! {
     fn sys(_handle: &Corestore, con: &mut T, iter: ActionIter<'_>) {
         let mut iter = iter;
-        ensure_boolean_or_aerr(iter.len() == 2)?;
+        ensure_boolean_or_aerr((1..=3).contains(&iter.len()))?;
         match unsafe { iter.next_lowercase_unchecked() }.as_ref() {
-            INFO => sys_info(con, &mut iter).await,
-            METRIC => sys_metric(con, &mut iter).await,
+            INFO if iter.len() == 1 => sys_info(con, &mut iter).await,
+            // only `latency` takes an argument
+            METRIC if iter.len() == 1 || iter.len() == 2 => sys_metric(con, &mut iter).await,
+            MIGRATESTATUS if iter.is_empty() => sys_migratestatus(con).await,
+            INFO | METRIC | MIGRATESTATUS => util::err(groups::ACTION_ERR),
             _ => util::err(groups::UNKNOWN_ACTION),
         }
     }
+    /// Returns the format version of every table file on disk (read from its header) as
+    /// `<ks>:<tbl> v<version>`, so that operators can tell if a `--migrate` is pending
+    fn sys_migratestatus(con: &mut T) {
+        match migrate::container_versions() {
+            Ok(versions) => {
+                let mut writer = unsafe {
+                    NonNullArrayWriter::new(con, TSYMBOL_UNICODE_STRING, versions.len()).await?
+                };
+                for (container, version) in versions {
+                    writer.write_element(format!("{container} v{version}")).await?;
+                }
+            }
+            Err(e) => {
+                log::error!("Failed to read storage format versions with: {e}");
+                con.write_response(groups::SERVER_ERR).await?
+            }
+        }
+        Ok(())
+    }
     fn sys_info(con: &mut T, iter: &mut ActionIter<'_>) {
         match unsafe { iter.next_lowercase_unchecked() }.as_ref() {
             INFO_PROTOCOL => con.write_response(PROTOCOL_VERSIONSTRING).await?,
             INFO_PROTOVER => con.write_response(PROTOCOL_VERSION).await?,
             INFO_VERSION => con.write_response(VERSION).await?,
+            INFO_UPTIME => con.write_response(registry::uptime()).await?,
+            INFO_STARTUP_TIME => con.write_response(registry::startup_time()).await?,
+            // the deadlines of keys are flushed along with their tables
+            INFO_TTL_PERSISTENCE => con.write_response("persistent").await?,
             _ => return util::err(ERR_UNKNOWN_PROPERTY),
         }
         Ok(())
     }
+    /// Returns the latency histogram (<100µs, <1ms, <10ms, <100ms and >=100ms) of an action
+    /// as a flat array of counts, or of every action that has run as an array of
+    /// `[<action>, [<count 1> ... <count 5>]]` pairs
+    fn sys_metric_latency(con: &mut T, iter: &mut ActionIter<'_>) {
+        match iter.next_uppercase() {
+            Some(action) => {
+                let histogram = match core::str::from_utf8(&action) {
+                    Ok(action) => latency::histogram(action),
+                    Err(_) => None,
+                };
+                match histogram {
+                    Some(histogram) => {
+                        con.write_flat_array_length(histogram.len()).await?;
+                        for count in histogram {
+                            con.write_response(count).await?;
+                        }
+                    }
+                    None => con.write_response(groups::NIL).await?,
+                }
+            }
+            None => {
+                let histograms = latency::histograms();
+                con.write_array_length(histograms.len()).await?;
+                for (action, histogram) in histograms {
+                    con.write_array_length(2).await?;
+                    con.write_response(action).await?;
+                    con.write_flat_array_length(histogram.len()).await?;
+                    for count in histogram {
+                        con.write_response(count).await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
     fn sys_metric(con: &mut T, iter: &mut ActionIter<'_>) {
-        match unsafe { iter.next_lowercase_unchecked() }.as_ref() {
+        let metric = unsafe { iter.next_lowercase_unchecked() };
+        if metric.as_ref() == METRIC_LATENCY {
+            return sys_metric_latency(con, iter).await;
+        }
+        ensure_boolean_or_aerr(ActionIter::is_empty(iter))?;
+        match metric.as_ref() {
             METRIC_HEALTH => {
                 con.write_response(HEALTH_TABLE[registry::state_okay()]).await?
             }
@@ -77,6 +169,15 @@ action! {
                     },
                 }
             }
+            METRIC_MEMORY_BUDGET => {
+                // estimate, budget (0 if unlimited) and whether writes are being refused
+                con.write_flat_array_length(3).await?;
+                con.write_response(registry::memory_estimate()).await?;
+                con.write_response(registry::memory_budget()).await?;
+                con.write_response(MEMORY_BUDGET_TABLE[registry::memory_okay()]).await?;
+            }
+            METRIC_CONNECTIONS => con.write_response(registry::connections()).await?,
+            METRIC_QUERIES => con.write_response(registry::queries()).await?,
             _ => return util::err(ERR_UNKNOWN_METRIC),
         }
         Ok(())
@@ -30,7 +30,7 @@ use crate::{
     corestore::Corestore,
     dbnet::{self, Terminator},
     diskstore::flock::FileLock,
-    services,
+    registry, services,
     storage::v1::sengine::SnapshotEngine,
     util::{
         error::{Error, SkyResult},
@@ -56,11 +56,24 @@ pub async fn run(
         bgsave,
         snapshot,
         maxcon,
+        membudget,
+        max_pipeline_length,
+        max_query_size,
+        buffer_shrink_threshold,
+        client_idle_timeout_secs,
+        protect_large_flush,
+        verbose_protocol_errors,
         auth,
+        unixsock,
         ..
     }: ConfigurationSet,
     restore_filepath: Option<String>,
 ) -> SkyResult<Corestore> {
+    registry::record_startup_time();
+    registry::set_max_pipeline_length(max_pipeline_length);
+    registry::set_buffer_shrink_threshold(buffer_shrink_threshold);
+    registry::set_protect_large_flush(protect_large_flush);
+    registry::set_verbose_protocol_errors(verbose_protocol_errors);
     // Intialize the broadcast channel
     let (signal, _) = broadcast::channel(1);
     let engine = match &snapshot {
@@ -78,7 +91,8 @@ pub async fn run(
     let auth_provider = match auth.origin_key {
         Some(key) => {
             let authref = db.get_store().setup_auth();
-            AuthProvider::new(authref, Some(key.into_inner()))
+            let aclref = db.get_store().setup_acl();
+            AuthProvider::new(authref, aclref, Some(key.into_inner()), auth.aclfile)
         }
         None => AuthProvider::new_disabled(),
     };
@@ -95,13 +109,31 @@ pub async fn run(
         snapshot,
         Terminator::new(signal.subscribe()),
     ));
+    let membudget_handle = tokio::spawn(services::membudget::membudget_service(
+        db.clone(),
+        membudget,
+        Terminator::new(signal.subscribe()),
+    ));
+    let expiry_handle = tokio::spawn(services::expiry::expiry_service(
+        db.clone(),
+        Terminator::new(signal.subscribe()),
+    ));
 
     // bind to signals
     let termsig =
         TerminationSignal::init().map_err(|e| Error::ioerror_extra(e, "binding to signals"))?;
     // start the server (single or multiple listeners)
-    let mut server =
-        dbnet::connect(ports, maxcon, db.clone(), auth_provider, signal.clone()).await?;
+    let mut server = dbnet::connect(
+        ports,
+        unixsock,
+        maxcon,
+        max_query_size,
+        client_idle_timeout_secs,
+        db.clone(),
+        auth_provider,
+        signal.clone(),
+    )
+    .await?;
 
     tokio::select! {
         _ = server.run_server() => {},
@@ -116,6 +148,8 @@ pub async fn run(
     // wait for the background services to terminate
     let _ = snapshot_handle.await;
     let _ = bgsave_handle.await;
+    let _ = membudget_handle.await;
+    let _ = expiry_handle.await;
     Ok(db)
 }
 
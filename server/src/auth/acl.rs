@@ -0,0 +1,336 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # `ACL` queries
+//!
+//! This module lets root manage the ACL rules of users (see [`super::rules`]), checks every
+//! query against the rules of the current user and keeps a log of the most recent ACL
+//! violations (a user, or an anonymous connection, trying to run an action that they aren't
+//! allowed to run). The log is shared by all connections and only keeps the last
+//! [`ACL_LOG_MAX_LEN`] events
+
+use super::errors;
+use super::rules::Rule;
+use super::AuthError;
+use crate::actions::command_docs::COMMAND_DOCS;
+use crate::actions::{ActionError, ActionResult};
+use crate::corestore::lazy::Lazy;
+use crate::dbnet::connection::prelude::*;
+use crate::protocol::UnsafeSlice;
+use crate::resp::{writer::NonNullArrayWriter, TSYMBOL_UNICODE_STRING};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Result as IoResult;
+use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The maximum number of events kept in the ACL log
+pub const ACL_LOG_MAX_LEN: usize = 128;
+const ACL_WHOAMI: &[u8] = b"WHOAMI";
+const ACL_CAT: &[u8] = b"CAT";
+const ACL_LOG: &[u8] = b"LOG";
+const ACL_LOG_RESET: &[u8] = b"RESET";
+const ACL_SETUSER: &[u8] = b"SETUSER";
+const ACL_GETUSER: &[u8] = b"GETUSER";
+const ACL_LIST: &[u8] = b"LIST";
+const ACL_SAVE: &[u8] = b"SAVE";
+const ACL_LOAD: &[u8] = b"LOAD";
+
+type AclLog = Mutex<VecDeque<AclLogEntry>>;
+
+static ACL_LOG_ENTRIES: Lazy<AclLog, fn() -> AclLog> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+#[derive(Debug, Clone, PartialEq)]
+/// An ACL violation
+pub struct AclLogEntry {
+    /// the action that was denied
+    pub action: String,
+    /// the user that ran the action (`None` if the connection wasn't authenticated)
+    pub user: Option<String>,
+    /// the address of the client
+    pub addr: SocketAddr,
+    /// the unix timestamp (in seconds) of the violation
+    pub timestamp: u64,
+}
+
+/// Record an ACL violation, evicting the oldest event if the log is full
+pub fn log_violation(action: impl Into<String>, user: Option<String>, addr: SocketAddr) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|dur| dur.as_secs())
+        .unwrap_or(0);
+    let mut log = ACL_LOG_ENTRIES.lock();
+    if log.len() == ACL_LOG_MAX_LEN {
+        log.pop_back();
+    }
+    log.push_front(AclLogEntry {
+        action: action.into(),
+        user,
+        addr,
+        timestamp,
+    });
+}
+
+/// Record an ACL violation if `ret` failed because of insufficient permissions
+pub fn log_if_denied<T, Strm>(
+    ret: &ActionResult<()>,
+    action: &str,
+    auth: &AuthProviderHandle<'_, T, Strm>,
+) where
+    T: ClientConnection<Strm>,
+    Strm: Stream,
+{
+    if let Err(ActionError::ActionError(errors::AUTH_CODE_PERMS)) = ret {
+        log_violation(action, auth.provider().whoami().ok(), auth.peer());
+    }
+}
+
+/// Make sure that the current user's ACL rules allow running the query (`AUTH` is always
+/// allowed so that users can log out). `doc` is the documentation of the query's action.
+/// Denied queries are logged
+pub fn ensure_permitted<T, Strm>(
+    auth: &AuthProviderHandle<'_, T, Strm>,
+    doc: &CommandDoc,
+    stage: &[UnsafeSlice],
+) -> ActionResult<()>
+where
+    T: ClientConnection<Strm>,
+    Strm: Stream,
+{
+    let arg = |idx: usize| unsafe {
+        // SAFETY: the stage points into the connection's buffer, which isn't touched until
+        // the stage has been run
+        stage[idx].as_slice()
+    };
+    if doc.name == super::DOCS.name {
+        return Ok(());
+    }
+    // if there are too few arguments to hold the keys, the action will fail anyway
+    let keys = doc.keys.key_positions(stage.len()).into_iter().flatten();
+    if auth.provider().is_permitted(doc, keys.map(arg)) {
+        Ok(())
+    } else {
+        log_violation(doc.name, auth.provider().whoami().ok(), auth.peer());
+        util::err(errors::AUTH_CODE_PERMS)
+    }
+}
+
+/// Returns the events in the ACL log, from the newest to the oldest
+pub fn log_entries() -> Vec<AclLogEntry> {
+    ACL_LOG_ENTRIES.lock().iter().cloned().collect()
+}
+
+/// Clear the ACL log
+pub fn log_reset() {
+    ACL_LOG_ENTRIES.lock().clear()
+}
+
+/// Write the ACL file, replacing it only once all of `acls` has been written
+fn write_aclfile(path: &str, acls: &str) -> IoResult<()> {
+    let tmp = format!("{path}_");
+    fs::write(&tmp, acls)?;
+    fs::rename(&tmp, path)
+}
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "ACL",
+    summary: "Manage the ACL rules of users and inspect the ACL log",
+    since: "0.8.0",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new(
+            "whoami|cat|log|setuser|getuser|list|save|load",
+            ArgumentType::Token,
+            false,
+        ),
+        ArgumentDoc::new("args ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+action! {
+    /// Handle `ACL` queries
+    /// ## Syntax
+    /// - `ACL WHOAMI`: returns the username of the current user (just like `AUTH WHOAMI`)
+    /// - `ACL CAT <optional category>`: returns the names of all the action categories, or the
+    /// names of the actions in the given category
+    /// - `ACL LOG <optional RESET>`: returns the most recent ACL violations (from the newest to
+    /// the oldest) as arrays of the action, the user (`NIL` if the connection wasn't
+    /// authenticated), the client address and the unix timestamp, or clears the log. Only
+    /// root can do this
+    /// - `ACL SETUSER <username> <rule1> <rule2> ...`: creates or updates a user with the rules
+    /// (`on`, `off`, `nopass`, `>password`, `~keypattern`, `+action`, `-action`, `+@category`
+    /// and `-@category`). Only root can do this
+    /// - `ACL GETUSER <username>`: returns the rules of a user (`NIL` if there's no such user)
+    /// - `ACL LIST`: returns the rules of all users, as `user <username> <rules ...>`
+    /// - `ACL SAVE`: writes the rules of all users that have them to the ACL file, as
+    /// `ACL SETUSER` lines
+    /// - `ACL LOAD`: replaces the rules of all users with the ones in the ACL file
+    fn acl(
+        con: &mut T,
+        auth: &mut AuthProviderHandle<'_, T, Strm>,
+        iter: ActionIter<'_>
+    ) {
+        let mut iter = iter;
+        ensure_length(iter.len(), |len| len != 0)?;
+        let subaction = unsafe { iter.next_uppercase_unchecked() };
+        if subaction.as_ref() != ACL_SETUSER {
+            ensure_length(iter.len(), |len| len < 2)?;
+        }
+        match subaction.as_ref() {
+            ACL_SETUSER => {
+                ensure_length(iter.len(), |len| len != 0)?;
+                let user = unsafe { iter.next_unchecked() };
+                let rules = match iter.map(Rule::parse).collect::<Option<Vec<Rule>>>() {
+                    Some(rules) => rules,
+                    None => return util::err(AuthError::Other(errors::AUTH_ERROR_BAD_ACL_RULE)),
+                };
+                auth.provider().set_user_rules(user, rules)?;
+                con.write_response(groups::OKAY).await?;
+            }
+            ACL_GETUSER => {
+                ensure_length(iter.len(), |len| len == 1)?;
+                match auth.provider().get_user_rules(unsafe { iter.next_unchecked() })? {
+                    Some(rules) => acl_write_strings(con, rules).await?,
+                    None => con.write_response(groups::NIL).await?,
+                }
+            }
+            ACL_LIST => {
+                ensure_boolean_or_aerr(iter.is_empty())?;
+                acl_write_strings(con, auth.provider().list_rules()?).await?;
+            }
+            ACL_SAVE => {
+                ensure_boolean_or_aerr(iter.is_empty())?;
+                let provider = auth.provider();
+                let path = provider.aclfile()?;
+                match write_aclfile(path, &provider.dump_rules()?) {
+                    Ok(()) => con.write_response(groups::OKAY).await?,
+                    Err(e) => {
+                        log::error!("Failed to save the ACL file with: {e}");
+                        con.write_response(groups::SERVER_ERR).await?
+                    }
+                }
+            }
+            ACL_LOAD => {
+                ensure_boolean_or_aerr(iter.is_empty())?;
+                let provider = auth.provider();
+                match fs::read_to_string(provider.aclfile()?) {
+                    Ok(acls) => {
+                        provider.load_rules(&acls)?;
+                        con.write_response(groups::OKAY).await?;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load the ACL file with: {e}");
+                        con.write_response(groups::SERVER_ERR).await?
+                    }
+                }
+            }
+            ACL_WHOAMI => {
+                ensure_boolean_or_aerr(iter.is_empty())?;
+                con.write_response(StringWrapper(auth.provider().whoami()?)).await?;
+            }
+            ACL_CAT => {
+                let names: Vec<&str> = match iter.next() {
+                    Some(category) => COMMAND_DOCS
+                        .iter()
+                        .filter(|doc| doc.group.as_str().as_bytes().eq_ignore_ascii_case(category))
+                        .map(|doc| doc.name)
+                        .collect(),
+                    None => CommandGroup::ALL.iter().map(CommandGroup::as_str).collect(),
+                };
+                let mut writer = unsafe {
+                    // SAFETY: category and action names are always unicode
+                    NonNullArrayWriter::new(con, TSYMBOL_UNICODE_STRING, names.len())
+                }
+                .await?;
+                for name in names {
+                    writer.write_element(name).await?;
+                }
+            }
+            ACL_LOG => {
+                auth.provider().ensure_root()?;
+                match iter.next_uppercase() {
+                    Some(reset) => {
+                        ensure_boolean_or_aerr(reset.as_ref() == ACL_LOG_RESET)?;
+                        log_reset();
+                        con.write_response(groups::OKAY).await?;
+                    }
+                    None => {
+                        let entries = log_entries();
+                        con.write_array_length(entries.len()).await?;
+                        for entry in entries {
+                            con.write_array_length(4).await?;
+                            con.write_response(StringWrapper(entry.action)).await?;
+                            match entry.user {
+                                Some(user) => con.write_response(StringWrapper(user)).await?,
+                                None => con.write_response(groups::NIL).await?,
+                            }
+                            con.write_response(StringWrapper(entry.addr.to_string())).await?;
+                            con.write_response(entry.timestamp).await?;
+                        }
+                    }
+                }
+            }
+            _ => return util::err(groups::UNKNOWN_ACTION),
+        }
+        Ok(())
+    }
+}
+
+action! {
+    /// Write the strings as a non-null array
+    fn acl_write_strings(con: &mut T, strings: Vec<String>) {
+        let mut writer = unsafe {
+            // SAFETY: the rules are always unicode
+            NonNullArrayWriter::new(con, TSYMBOL_UNICODE_STRING, strings.len())
+        }
+        .await?;
+        for string in strings {
+            writer.write_element(string).await?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_acl_log_is_capped() {
+    let addr: SocketAddr = "127.0.0.1:2003".parse().unwrap();
+    log_reset();
+    for i in 0..ACL_LOG_MAX_LEN + 2 {
+        log_violation(format!("ACTION{}", i), None, addr);
+    }
+    let entries = log_entries();
+    assert_eq!(entries.len(), ACL_LOG_MAX_LEN);
+    assert_eq!(entries[0].action, format!("ACTION{}", ACL_LOG_MAX_LEN + 1));
+    assert_eq!(entries[ACL_LOG_MAX_LEN - 1].action, "ACTION2");
+    log_violation("AUTH", Some("testuser".to_owned()), addr);
+    assert_eq!(log_entries()[0].user.as_deref(), Some("testuser"));
+    log_reset();
+    assert!(log_entries().is_empty());
+}
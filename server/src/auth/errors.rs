@@ -38,6 +38,12 @@ pub const AUTH_CODE_PERMS: &[u8] = b"!11\n";
 pub const AUTH_ERROR_ILLEGAL_USERNAME: &[u8] = b"!err-auth-illegal-username\n";
 /// Skyhash respstring: ID is protected/in use
 pub const AUTH_ERROR_FAILED_TO_DELETE_USER: &[u8] = b"!err-auth-deluser-fail\n";
+/// Skyhash respstring: bad ACL rule
+pub const AUTH_ERROR_BAD_ACL_RULE: &[u8] = b"!err-auth-bad-acl-rule\n";
+/// Skyhash respstring: bad line in the ACL file
+pub const AUTH_ERROR_BAD_ACL_FILE: &[u8] = b"!err-auth-bad-acl-file\n";
+/// Skyhash respstring: no ACL file was configured
+pub const AUTH_ERROR_NO_ACLFILE: &[u8] = b"!err-auth-no-aclfile\n";
 
 /// Auth erros
 #[derive(PartialEq, Debug)]
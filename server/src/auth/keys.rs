@@ -39,14 +39,20 @@ pub fn generate_full() -> (String, Authkey) {
     let mut bytes: [u8; RAN_BYTES_SIZE] = [0u8; RAN_BYTES_SIZE];
     openssl::rand::rand_bytes(&mut bytes).unwrap();
     let ret = base64::encode_config(&bytes, base64::BCRYPT);
-    let hash = rcrypt::hash(&ret, rcrypt::DEFAULT_COST).unwrap();
-    let store_in_db = unsafe {
+    let store_in_db = hash_key(ret.as_bytes());
+    (ret, store_in_db)
+}
+
+/// Hash a "human readable key" (or a password set with `ACL SETUSER`) into the "authbytes"
+/// that can be stored
+pub fn hash_key(key: &[u8]) -> Authkey {
+    let hash = rcrypt::hash(key, rcrypt::DEFAULT_COST).unwrap();
+    unsafe {
         let mut array = AuthkeyArray::new();
         // we guarantee that the size is equal to 40
         array.extend_from_slice_unchecked(&hash);
         array.into_array_unchecked()
-    };
-    (ret, store_in_db)
+    }
 }
 
 /// Verify a "human readable key" against the provided "authbytes"
@@ -36,10 +36,12 @@
  * users have access to everything but the ability to create/revoke other users
 */
 
+pub mod acl;
 mod keys;
 pub mod provider;
+pub mod rules;
 use crate::resp::{writer::NonNullArrayWriter, TSYMBOL_UNICODE_STRING};
-pub use provider::{AuthProvider, AuthResult, Authmap};
+pub use provider::{Aclmap, AuthProvider, AuthResult, Authmap};
 pub mod errors;
 pub use errors::AuthError;
 
@@ -56,6 +58,21 @@ const AUTH_DELUSER: &[u8] = b"deluser";
 const AUTH_RESTORE: &[u8] = b"restore";
 const AUTH_LISTUSER: &[u8] = b"listuser";
 const AUTH_WHOAMI: &[u8] = b"whoami";
+const AUTH_COPY: &[u8] = b"copy";
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "AUTH",
+    summary: "Manage users and authenticate",
+    since: "0.7.4",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("subaction", ArgumentType::Token, false),
+        ArgumentDoc::new("args ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
 
 action! {
     /// Handle auth. Should have passed the `auth` token
@@ -63,6 +80,15 @@ action! {
         con: &mut T,
         auth: &mut AuthProviderHandle<'_, T, Strm>,
         iter: ActionIter<'_>
+    ) {
+        let ret = self::_auth(con, auth, iter).await;
+        acl::log_if_denied(&ret, "AUTH", auth);
+        ret
+    }
+    fn _auth(
+        con: &mut T,
+        auth: &mut AuthProviderHandle<'_, T, Strm>,
+        iter: ActionIter<'_>
     ) {
         let mut iter = iter;
         match iter.next_lowercase().unwrap_or_aerr()?.as_ref() {
@@ -88,6 +114,15 @@ action! {
                 con.write_response(groups::OKAY).await?;
                 Ok(())
             }
+            AUTH_COPY => {
+                ensure_boolean_or_aerr(iter.len() == 3)?; // source, destination and password
+                let (src, dst, password) = unsafe {
+                    (iter.next_unchecked(), iter.next_unchecked(), iter.next_unchecked())
+                };
+                auth.provider().copy_user(src, dst, password)?;
+                con.write_response(groups::OKAY).await?;
+                Ok(())
+            }
             AUTH_RESTORE => self::auth_restore(con, auth, &mut iter).await,
             AUTH_LISTUSER => self::auth_listuser(con, auth, &mut iter).await,
             AUTH_WHOAMI => self::auth_whoami(con, auth, &mut iter).await,
@@ -141,6 +176,15 @@ action! {
         con: &mut T,
         auth: &mut AuthProviderHandle<'_, T, Strm>,
         iter: ActionIter<'_>
+    ) {
+        let ret = self::_auth_login_only(con, auth, iter).await;
+        acl::log_if_denied(&ret, "AUTH", auth);
+        ret
+    }
+    fn _auth_login_only(
+        con: &mut T,
+        auth: &mut AuthProviderHandle<'_, T, Strm>,
+        iter: ActionIter<'_>
     ) {
         let mut iter = iter;
         match iter.next_lowercase().unwrap_or_aerr()?.as_ref() {
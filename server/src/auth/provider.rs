@@ -24,9 +24,12 @@
  *
 */
 
+use super::rules::{Rule, UserRules};
 use super::{errors, keys, AuthError};
+use crate::actions::command_docs::CommandDoc;
 use crate::corestore::array::Array;
 use crate::corestore::htable::Coremap;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 // constants
@@ -60,6 +63,8 @@ pub type Authkey = [u8; AUTHKEY_SIZE];
 pub type AuthResult<T> = Result<T, AuthError>;
 /// Authmap
 pub type Authmap = Arc<Coremap<AuthID, Authkey>>;
+/// A map of the ACL rules of the users that have them
+pub type Aclmap = Arc<Coremap<AuthID, UserRules>>;
 
 /// The authn/authz provider
 ///
@@ -69,32 +74,49 @@ pub struct AuthProvider {
     whoami: Option<AuthID>,
     /// a map of users
     authmap: Authmap,
+    /// the ACL rules of the users
+    aclmap: Aclmap,
+    /// the file used by `ACL SAVE` and `ACL LOAD`
+    aclfile: Option<Arc<str>>,
 }
 
 impl AuthProvider {
-    fn _new(authmap: Authmap, whoami: Option<AuthID>, origin: Option<Authkey>) -> Self {
+    fn _new(
+        authmap: Authmap,
+        aclmap: Aclmap,
+        whoami: Option<AuthID>,
+        origin: Option<Authkey>,
+        aclfile: Option<Arc<str>>,
+    ) -> Self {
         Self {
             authmap,
+            aclmap,
             whoami,
             origin,
+            aclfile,
         }
     }
     /// New provider with no origin-key
     pub fn new_disabled() -> Self {
-        Self::_new(Default::default(), None, None)
+        Self::_new(Default::default(), Default::default(), None, None, None)
     }
     /// New provider with zero users
     #[cfg(test)]
     pub fn new_blank(origin: Option<Authkey>) -> Self {
-        Self::_new(Default::default(), None, origin)
+        Self::_new(Default::default(), Default::default(), None, origin, None)
     }
     /// New provider with users from the provided map
     ///
     /// ## Test suite
     /// The testsuite creates users `root` and `testuser`; this **does not** apply to
     /// release mode
-    pub fn new(authmap: Arc<Coremap<AuthID, Authkey>>, origin: Option<Authkey>) -> Self {
-        let slf = Self::_new(authmap, None, origin);
+    pub fn new(
+        authmap: Authmap,
+        aclmap: Aclmap,
+        origin: Option<Authkey>,
+        aclfile: Option<String>,
+    ) -> Self {
+        let slf = Self::_new(authmap, aclmap, None, origin, aclfile.map(Arc::from));
         #[cfg(debug_assertions)]
         {
             // 'root' user in test mode
@@ -157,11 +179,21 @@ impl AuthProvider {
     }
     pub fn login(&mut self, account: &[u8], token: &[u8]) -> AuthResult<()> {
         self.ensure_enabled()?;
-        match self
-            .authmap
+        let (enabled, nopass) = self
+            .aclmap
             .get(account)
-            .map(|token_hash| keys::verify_key(token, token_hash.as_slice()))
-        {
+            .map(|rules| (rules.is_enabled(), rules.is_nopass()))
+            .unwrap_or((true, false));
+        if !enabled {
+            return Err(AuthError::BadCredentials);
+        }
+        match self.authmap.get(account).map(|token_hash| {
+            if nopass {
+                Some(true)
+            } else {
+                keys::verify_key(token, token_hash.as_slice())
+            }
+        }) {
             Some(Some(true)) => {
                 // great, authenticated
                 self.whoami = Some(Self::try_auth_id(account)?);
@@ -221,7 +253,8 @@ impl AuthProvider {
             None => Err(AuthError::Disabled),
         }
     }
-    fn ensure_root(&self) -> AuthResult<()> {
+    /// Fails with [`AuthError::PermissionDenied`] unless the current user is root
+    pub fn ensure_root(&self) -> AuthResult<()> {
         if self.are_you_root()? {
             Ok(())
         } else {
@@ -234,11 +267,37 @@ impl AuthProvider {
             // can't delete root!
             Err(AuthError::Other(errors::AUTH_ERROR_FAILED_TO_DELETE_USER))
         } else if self.authmap.true_if_removed(user) {
+            self.aclmap.true_if_removed(user);
             Ok(())
         } else {
             Err(AuthError::BadCredentials)
         }
     }
+    /// Create `dst` with the ACL rules of `src` and the given password. Fails if there is no
+    /// such user as `src` or if `dst` already exists
+    pub fn copy_user(&self, src: &[u8], dst: &[u8], password: &[u8]) -> AuthResult<()> {
+        self.ensure_root()?;
+        let dst = Self::try_auth_id(dst)?;
+        if src.eq(&USER_ROOT) || dst.eq(&USER_ROOT) {
+            // root doesn't have rules that could be copied (or replaced)
+            return Err(AuthError::Other(errors::AUTH_ERROR_ILLEGAL_USERNAME));
+        }
+        if !self.authmap.contains_key(src) {
+            return Err(AuthError::BadCredentials);
+        }
+        let mut rules = self.aclmap.get_cloned(src).unwrap_or_else(UserRules::full);
+        // the copy has its own password, even if `src` is `nopass`
+        rules.apply(Rule::Password(password.to_vec()));
+        if self
+            .authmap
+            .true_if_insert(dst.clone(), keys::hash_key(password))
+        {
+            self.aclmap.upsert(dst, rules);
+            Ok(())
+        } else {
+            Err(AuthError::AlreadyClaimed)
+        }
+    }
     /// List all the users
     pub fn collect_usernames(&self) -> AuthResult<Vec<String>> {
         self.ensure_root()?;
@@ -248,6 +307,159 @@ impl AuthProvider {
             .map(|kv| String::from_utf8_lossy(kv.key()).to_string())
             .collect())
     }
+    /// Create a user (or update an existing one) and apply the given ACL rules. A user that
+    /// is created here can't log in until the rules enable them and either set a password or
+    /// `nopass`. An existing user without rules starts from `on ~* +@all`
+    pub fn set_user_rules(&self, user: &[u8], rules: Vec<Rule>) -> AuthResult<()> {
+        self.ensure_root()?;
+        let id = Self::try_auth_id(user)?;
+        if id.eq(&USER_ROOT) {
+            // root can always do everything
+            return Err(AuthError::Other(errors::AUTH_ERROR_ILLEGAL_USERNAME));
+        }
+        let mut staged = match self.aclmap.get_cloned(&id) {
+            Some(user_rules) => StagedRules::new(user_rules),
+            None if self.authmap.contains_key(&id) => StagedRules::new(UserRules::full()),
+            None => StagedRules::new(UserRules::default()),
+        };
+        staged.apply(rules);
+        self.commit_rules(id, staged);
+        Ok(())
+    }
+    /// Store the rules (and the password, if any) of a user
+    fn commit_rules(&self, id: AuthID, staged: StagedRules) {
+        let StagedRules { rules, password } = staged;
+        match password {
+            Some(pass) => self.authmap.upsert(id.clone(), keys::hash_key(&pass)),
+            None if !self.authmap.contains_key(&id) => {
+                // nobody knows this key; they'll need a password or `nopass` to log in
+                self.authmap.upsert(id.clone(), keys::generate_full().1)
+            }
+            None => {}
+        }
+        self.aclmap.upsert(id, rules);
+    }
+    /// Returns the path of the ACL file
+    pub fn aclfile(&self) -> AuthResult<&str> {
+        self.ensure_root()?;
+        self.aclfile
+            .as_deref()
+            .ok_or(AuthError::Other(errors::AUTH_ERROR_NO_ACLFILE))
+    }
+    /// Returns the ACL rules of the users that have them as `ACL SETUSER` lines (sorted by
+    /// the username) that can be read back with [`Self::load_rules`]
+    pub fn dump_rules(&self) -> AuthResult<String> {
+        self.ensure_root()?;
+        let mut lines: Vec<String> = self
+            .aclmap
+            .iter()
+            .filter(|kv| self.authmap.contains_key(kv.key()))
+            .map(|kv| {
+                format!(
+                    "ACL SETUSER {} {}\n",
+                    String::from_utf8_lossy(kv.key()),
+                    kv.value().to_rules().join(" ")
+                )
+            })
+            .collect();
+        lines.sort_unstable();
+        Ok(lines.concat())
+    }
+    /// Replace the ACL rules of all users with the `ACL SETUSER` lines in `acls` (empty
+    /// lines and lines starting with `#` are skipped). Every user in `acls` starts with no
+    /// access at all, while users that aren't in `acls` lose their rules. Nothing is changed
+    /// if any of the lines is invalid
+    pub fn load_rules(&self, acls: &str) -> AuthResult<()> {
+        self.ensure_root()?;
+        let mut staged: HashMap<AuthID, StagedRules> = HashMap::new();
+        for line in acls.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut tokens = line.split_ascii_whitespace();
+            let is_setuser = matches!(
+                (tokens.next(), tokens.next()),
+                (Some(acl), Some(setuser))
+                    if acl.eq_ignore_ascii_case("ACL") && setuser.eq_ignore_ascii_case("SETUSER")
+            );
+            let user = match tokens.next() {
+                Some(user) if is_setuser => user,
+                _ => return Err(AuthError::Other(errors::AUTH_ERROR_BAD_ACL_FILE)),
+            };
+            let id = Self::try_auth_id(user.as_bytes())?;
+            if id.eq(&USER_ROOT) {
+                return Err(AuthError::Other(errors::AUTH_ERROR_ILLEGAL_USERNAME));
+            }
+            let rules = match tokens.map(|rule| Rule::parse(rule.as_bytes())).collect() {
+                Some(rules) => rules,
+                None => return Err(AuthError::Other(errors::AUTH_ERROR_BAD_ACL_RULE)),
+            };
+            staged
+                .entry(id)
+                .or_insert_with(|| StagedRules::new(UserRules::default()))
+                .apply(rules);
+        }
+        // first store the new rules and only then drop the stale ones, so that a user who
+        // stays restricted is never briefly left without rules
+        let stale: Vec<AuthID> = self
+            .aclmap
+            .iter()
+            .filter(|kv| !staged.contains_key(kv.key()))
+            .map(|kv| kv.key().clone())
+            .collect();
+        for (id, rules) in staged {
+            self.commit_rules(id, rules);
+        }
+        for id in stale {
+            self.aclmap.true_if_removed(&id);
+        }
+        Ok(())
+    }
+    /// Returns the ACL rules of the given user or `None` if there is no such user
+    pub fn get_user_rules(&self, user: &[u8]) -> AuthResult<Option<Vec<String>>> {
+        self.ensure_root()?;
+        if !self.authmap.contains_key(user) {
+            return Ok(None);
+        }
+        let rules = match self.aclmap.get(user) {
+            Some(rules) => rules.to_rules(),
+            None => UserRules::full().to_rules(),
+        };
+        Ok(Some(rules))
+    }
+    /// Returns the ACL rules of all the users (`user <username> <rules ...>`), sorted by the
+    /// username
+    pub fn list_rules(&self) -> AuthResult<Vec<String>> {
+        self.ensure_root()?;
+        let mut rules: Vec<String> = self
+            .authmap
+            .iter()
+            .map(|kv| {
+                let user_rules = match self.aclmap.get(kv.key()) {
+                    Some(rules) => rules.to_rules(),
+                    None => UserRules::full().to_rules(),
+                };
+                format!(
+                    "user {} {}",
+                    String::from_utf8_lossy(kv.key()),
+                    user_rules.join(" ")
+                )
+            })
+            .collect();
+        rules.sort_unstable();
+        Ok(rules)
+    }
+    /// Returns true if the current user's ACL rules allow the query. Root and the users that
+    /// don't have rules can run anything
+    pub fn is_permitted<'a>(&self, doc: &CommandDoc, keys: impl Iterator<Item = &'a [u8]>) -> bool {
+        match self.whoami.as_ref() {
+            Some(user) if !user.eq(&USER_ROOT) => match self.aclmap.get(user) {
+                Some(rules) => rules.permits(doc, keys),
+                None => true,
+            },
+            _ => true,
+        }
+    }
     /// Return the AuthID of the current user
     pub fn whoami(&self) -> AuthResult<String> {
         self.ensure_enabled()?;
@@ -262,8 +474,33 @@ impl Clone for AuthProvider {
     fn clone(&self) -> Self {
         Self {
             authmap: self.authmap.clone(),
+            aclmap: self.aclmap.clone(),
             whoami: None,
             origin: self.origin,
+            aclfile: self.aclfile.clone(),
+        }
+    }
+}
+
+/// The rules of a user that haven't been stored yet, along with the last password that the
+/// rules set
+struct StagedRules {
+    rules: UserRules,
+    password: Option<Vec<u8>>,
+}
+
+impl StagedRules {
+    const fn new(rules: UserRules) -> Self {
+        Self {
+            rules,
+            password: None,
+        }
+    }
+    fn apply(&mut self, rules: Vec<Rule>) {
+        for rule in rules {
+            if let Some(pass) = self.rules.apply(rule) {
+                self.password = Some(pass);
+            }
         }
     }
 }
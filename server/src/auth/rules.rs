@@ -0,0 +1,321 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # ACL rules
+//!
+//! Users created with `ACL SETUSER` carry a set of rules that decide if they can log in, which
+//! actions they can run and which keys they can access. The rules are kept in their canonical
+//! text form as well (like `on ~user:* +@string -DEL`) which is what gets persisted to disk and
+//! what `ACL GETUSER` returns. Users without rules (root and the users created with
+//! `AUTH ADDUSER`) can run anything
+
+use crate::actions::command_docs::{self, CommandDoc, CommandGroup};
+use crate::util;
+
+const RULE_ON: &[u8] = b"on";
+const RULE_OFF: &[u8] = b"off";
+const RULE_NOPASS: &[u8] = b"nopass";
+/// The category that matches all actions
+const CATEGORY_ALL: &[u8] = b"all";
+
+#[derive(Debug, PartialEq)]
+/// A single ACL rule, as passed to `ACL SETUSER`
+pub enum Rule {
+    /// `on`: the user can log in
+    On,
+    /// `off`: the user can't log in
+    Off,
+    /// `nopass`: the user can log in with any password
+    NoPass,
+    /// `>password`: set the password of the user
+    Password(Vec<u8>),
+    /// `~pattern`: allow access to the keys that match the glob
+    KeyPattern(Vec<u8>),
+    /// `+action`, `-action`, `+@category` or `-@category`
+    Command(CommandRule),
+}
+
+impl Rule {
+    /// Parse a rule. Returns `None` if the rule is invalid or refers to an unknown action or
+    /// category
+    pub fn parse(rule: &[u8]) -> Option<Self> {
+        if rule.eq_ignore_ascii_case(RULE_ON) {
+            return Some(Self::On);
+        }
+        if rule.eq_ignore_ascii_case(RULE_OFF) {
+            return Some(Self::Off);
+        }
+        if rule.eq_ignore_ascii_case(RULE_NOPASS) {
+            return Some(Self::NoPass);
+        }
+        let (prefix, rest) = rule.split_first()?;
+        if rest.is_empty() {
+            return None;
+        }
+        match prefix {
+            b'>' => Some(Self::Password(rest.to_vec())),
+            // the canonical form is space separated, so patterns can't have whitespace
+            b'~' if !rest.iter().any(u8::is_ascii_whitespace) => {
+                Some(Self::KeyPattern(rest.to_vec()))
+            }
+            b'+' => CommandRule::parse(true, rest).map(Self::Command),
+            b'-' => CommandRule::parse(false, rest).map(Self::Command),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// What a command rule applies to
+pub enum CommandTarget {
+    /// An action, by its (uppercase) name
+    Action(&'static str),
+    /// A category (the name of a [`CommandGroup`] or `all`)
+    Category(Option<CommandGroup>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Allow or deny an action or a category of actions
+pub struct CommandRule {
+    allow: bool,
+    target: CommandTarget,
+}
+
+impl CommandRule {
+    fn parse(allow: bool, target: &[u8]) -> Option<Self> {
+        let target = match target.split_first()? {
+            (b'@', category) if category.eq_ignore_ascii_case(CATEGORY_ALL) => {
+                CommandTarget::Category(None)
+            }
+            (b'@', category) => CommandTarget::Category(Some(
+                CommandGroup::ALL
+                    .into_iter()
+                    .find(|group| group.as_str().as_bytes().eq_ignore_ascii_case(category))?,
+            )),
+            _ => CommandTarget::Action(command_docs::find(target)?.name),
+        };
+        Some(Self { allow, target })
+    }
+    fn matches(&self, doc: &CommandDoc) -> bool {
+        match self.target {
+            CommandTarget::Action(name) => name == doc.name,
+            CommandTarget::Category(None) => true,
+            CommandTarget::Category(Some(group)) => group == doc.group,
+        }
+    }
+    fn to_rule(&self) -> String {
+        let sign = if self.allow { '+' } else { '-' };
+        match self.target {
+            CommandTarget::Action(name) => format!("{}{}", sign, name),
+            CommandTarget::Category(None) => format!("{}@all", sign),
+            CommandTarget::Category(Some(group)) => format!("{}@{}", sign, group.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The ACL rules of a user
+pub struct UserRules {
+    enabled: bool,
+    nopass: bool,
+    key_patterns: Vec<Vec<u8>>,
+    /// the command rules, in the order they were added (the last matching rule wins)
+    commands: Vec<CommandRule>,
+    /// the canonical form of the rules
+    repr: Vec<u8>,
+}
+
+impl Default for UserRules {
+    /// The rules of a new user: can't log in, can't run anything and can't access any key
+    fn default() -> Self {
+        Self::from_parts(false, false, Vec::new(), Vec::new())
+    }
+}
+
+impl UserRules {
+    fn from_parts(
+        enabled: bool,
+        nopass: bool,
+        key_patterns: Vec<Vec<u8>>,
+        commands: Vec<CommandRule>,
+    ) -> Self {
+        let mut slf = Self {
+            enabled,
+            nopass,
+            key_patterns,
+            commands,
+            repr: Vec::new(),
+        };
+        slf.repr = slf.to_rules().join(" ").into_bytes();
+        slf
+    }
+    /// The rules of a user that can run anything (`on ~* +@all`)
+    pub fn full() -> Self {
+        let all = CommandRule {
+            allow: true,
+            target: CommandTarget::Category(None),
+        };
+        Self::from_parts(true, false, vec![b"*".to_vec()], vec![all])
+    }
+    /// Parse rules from their canonical form. If anything is off (say, the file was
+    /// tampered with), the user is disabled rather than given more access than intended
+    pub fn from_repr(repr: &[u8]) -> Self {
+        let mut rules = Self::default();
+        for rule in repr.split(|b| *b == b' ').filter(|rule| !rule.is_empty()) {
+            match Rule::parse(rule) {
+                Some(Rule::Password(_)) | None => return Self::default(),
+                Some(rule) => {
+                    rules.apply(rule);
+                }
+            }
+        }
+        rules
+    }
+    /// Apply a rule. If the rule sets a password, the password is returned so that the caller
+    /// can store its hash
+    pub fn apply(&mut self, rule: Rule) -> Option<Vec<u8>> {
+        let mut password = None;
+        match rule {
+            Rule::On => self.enabled = true,
+            Rule::Off => self.enabled = false,
+            Rule::NoPass => self.nopass = true,
+            Rule::Password(pass) => {
+                self.nopass = false;
+                password = Some(pass);
+            }
+            Rule::KeyPattern(pattern) => {
+                if !self.key_patterns.contains(&pattern) {
+                    self.key_patterns.push(pattern);
+                }
+            }
+            Rule::Command(rule) => {
+                // the newer rule replaces an older one for the same target
+                self.commands.retain(|old| old.target != rule.target);
+                self.commands.push(rule);
+            }
+        }
+        self.repr = self.to_rules().join(" ").into_bytes();
+        password
+    }
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    pub const fn is_nopass(&self) -> bool {
+        self.nopass
+    }
+    /// Returns true if the rules allow running the action with the given keys
+    pub fn permits<'a>(&self, doc: &CommandDoc, mut keys: impl Iterator<Item = &'a [u8]>) -> bool {
+        let action_allowed = self
+            .commands
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(doc))
+            .is_some_and(|rule| rule.allow);
+        action_allowed
+            && keys.all(|key| {
+                self.key_patterns
+                    .iter()
+                    .any(|pattern| util::glob_match(pattern, key))
+            })
+    }
+    /// Returns the rules in their canonical form
+    pub fn to_rules(&self) -> Vec<String> {
+        let mut rules = Vec::with_capacity(2 + self.key_patterns.len() + self.commands.len());
+        rules.push(if self.enabled { "on" } else { "off" }.to_owned());
+        if self.nopass {
+            rules.push("nopass".to_owned());
+        }
+        for pattern in &self.key_patterns {
+            rules.push(format!("~{}", String::from_utf8_lossy(pattern)));
+        }
+        rules.extend(self.commands.iter().map(CommandRule::to_rule));
+        rules
+    }
+}
+
+impl AsRef<[u8]> for UserRules {
+    fn as_ref(&self) -> &[u8] {
+        &self.repr
+    }
+}
+
+#[test]
+fn test_rule_parse() {
+    assert_eq!(Rule::parse(b"ON"), Some(Rule::On));
+    assert_eq!(
+        Rule::parse(b">pass"),
+        Some(Rule::Password(b"pass".to_vec()))
+    );
+    assert_eq!(
+        Rule::parse(b"~user:*"),
+        Some(Rule::KeyPattern(b"user:*".to_vec()))
+    );
+    assert!(Rule::parse(b"+get").is_some());
+    assert!(Rule::parse(b"-@List").is_some());
+    assert!(Rule::parse(b"+@all").is_some());
+    assert!(Rule::parse(b"+nosuchaction").is_none());
+    assert!(Rule::parse(b"+@nosuchcategory").is_none());
+    assert!(Rule::parse(b"~a b").is_none());
+    assert!(Rule::parse(b">").is_none());
+    assert!(Rule::parse(b"blah").is_none());
+}
+
+#[test]
+fn test_user_rules_permits() {
+    let get = command_docs::find(b"get").unwrap();
+    let del = command_docs::find(b"del").unwrap();
+    let lget = command_docs::find(b"lget").unwrap();
+    let mut rules = UserRules::default();
+    assert!(!rules.permits(get, [b"user:1".as_ref()].into_iter()));
+    for rule in ["on", "~user:*", "+@string", "+@generic", "-DEL"] {
+        rules.apply(Rule::parse(rule.as_bytes()).unwrap());
+    }
+    assert!(rules.permits(get, [b"user:1".as_ref()].into_iter()));
+    assert!(!rules.permits(get, [b"admin:1".as_ref()].into_iter()));
+    assert!(!rules.permits(del, [b"user:1".as_ref()].into_iter()));
+    assert!(!rules.permits(lget, [b"user:1".as_ref()].into_iter()));
+    assert!(UserRules::full().permits(del, [b"admin:1".as_ref()].into_iter()));
+}
+
+#[test]
+fn test_user_rules_repr() {
+    let mut rules = UserRules::default();
+    for rule in ["on", "nopass", "~user:*", "+get", "-@list", ">pass", "-GET"] {
+        rules.apply(Rule::parse(rule.as_bytes()).unwrap());
+    }
+    assert_eq!(rules.as_ref(), b"on ~user:* -@list -GET");
+    assert_eq!(UserRules::from_repr(rules.as_ref()), rules);
+    assert_eq!(UserRules::full().as_ref(), b"on ~* +@all");
+    // anything unexpected disables the user
+    assert_eq!(
+        UserRules::from_repr(b"on +@all >pass"),
+        UserRules::default()
+    );
+    assert_eq!(
+        UserRules::from_repr(b"on +@all +blah"),
+        UserRules::default()
+    );
+}
@@ -109,3 +109,186 @@ mod authn {
         );
     }
 }
+
+mod acl {
+    use crate::actions::command_docs;
+    use crate::auth::rules::Rule;
+    use crate::auth::{errors, AuthError, AuthProvider};
+
+    const ORIG: &[u8; 40] = b"c4299d190fb9a00626797fcc138c56eae9971664";
+
+    fn rules(rules: &[&str]) -> Vec<Rule> {
+        rules
+            .iter()
+            .map(|rule| Rule::parse(rule.as_bytes()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn set_user_rules_okay_with_nopass_login() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider
+            .set_user_rules(b"guest", rules(&["on", "nopass", "~guest:*", "+get"]))
+            .unwrap();
+        assert_eq!(
+            provider.get_user_rules(b"guest").unwrap().unwrap(),
+            vec!["on", "nopass", "~guest:*", "+GET"]
+        );
+        assert_eq!(provider.get_user_rules(b"nobody").unwrap(), None);
+        assert_eq!(
+            provider.list_rules().unwrap(),
+            vec![
+                "user guest on nopass ~guest:* +GET",
+                "user root on ~* +@all"
+            ]
+        );
+        provider.login(b"guest", b"anything").unwrap();
+        let get = command_docs::find(b"get").unwrap();
+        let set = command_docs::find(b"set").unwrap();
+        assert!(provider.is_permitted(get, [b"guest:1".as_ref()].into_iter()));
+        assert!(!provider.is_permitted(get, [b"other".as_ref()].into_iter()));
+        assert!(!provider.is_permitted(set, [b"guest:1".as_ref()].into_iter()));
+        // not root anymore
+        assert_eq!(
+            provider.set_user_rules(b"guest", rules(&["+@all"])),
+            Err(AuthError::PermissionDenied)
+        );
+    }
+    #[test]
+    fn set_user_rules_off_cant_login() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider
+            .set_user_rules(b"guest", rules(&["nopass"]))
+            .unwrap();
+        assert_eq!(
+            provider.login(b"guest", b"anything").unwrap_err(),
+            AuthError::BadCredentials
+        );
+    }
+    #[test]
+    fn set_user_rules_fail_root() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        assert_eq!(
+            provider.set_user_rules(b"root", rules(&["off"])),
+            Err(AuthError::Other(errors::AUTH_ERROR_ILLEGAL_USERNAME))
+        );
+    }
+    #[test]
+    fn set_user_rules_fail_anonymous() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider.logout().unwrap();
+        assert_eq!(
+            provider.set_user_rules(b"guest", rules(&["on"])),
+            Err(AuthError::Anonymous)
+        );
+    }
+    #[test]
+    fn dump_and_load_rules() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider
+            .set_user_rules(b"guest", rules(&["on", "nopass", "~guest:*", "+get"]))
+            .unwrap();
+        provider.set_user_rules(b"other", rules(&["off"])).unwrap();
+        let acls = provider.dump_rules().unwrap();
+        assert_eq!(
+            acls,
+            "ACL SETUSER guest on nopass ~guest:* +GET\nACL SETUSER other off\n"
+        );
+        // users that aren't in the file lose their rules
+        provider
+            .load_rules(
+                "# comment\n\nacl setuser guest on nopass\nACL SETUSER guest ~* +@all -set\n",
+            )
+            .unwrap();
+        assert_eq!(
+            provider.dump_rules().unwrap(),
+            "ACL SETUSER guest on nopass ~* +@all -SET\n"
+        );
+        // and the saved rules can be loaded back
+        provider.load_rules(&acls).unwrap();
+        assert_eq!(provider.dump_rules().unwrap(), acls);
+    }
+    #[test]
+    fn load_rules_fail_changes_nothing() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider.set_user_rules(b"guest", rules(&["on"])).unwrap();
+        let acls = provider.dump_rules().unwrap();
+        assert_eq!(
+            provider.load_rules("ACL SETUSER other on\nSET x y\n"),
+            Err(AuthError::Other(errors::AUTH_ERROR_BAD_ACL_FILE))
+        );
+        assert_eq!(
+            provider.load_rules("ACL SETUSER other on\nACL SETUSER guest +nosuchaction\n"),
+            Err(AuthError::Other(errors::AUTH_ERROR_BAD_ACL_RULE))
+        );
+        assert_eq!(
+            provider.load_rules("ACL SETUSER root off\n"),
+            Err(AuthError::Other(errors::AUTH_ERROR_ILLEGAL_USERNAME))
+        );
+        assert_eq!(provider.dump_rules().unwrap(), acls);
+        assert_eq!(provider.get_user_rules(b"other").unwrap(), None);
+    }
+    #[test]
+    fn copy_user_okay() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider
+            .set_user_rules(b"guest", rules(&["on", "nopass", "~guest:*", "+get"]))
+            .unwrap();
+        provider.copy_user(b"guest", b"guest2", b"pass").unwrap();
+        // same rules, but with a password of its own
+        assert_eq!(
+            provider.get_user_rules(b"guest2").unwrap().unwrap(),
+            vec!["on", "~guest:*", "+GET"]
+        );
+        assert_eq!(
+            provider.get_user_rules(b"guest").unwrap().unwrap(),
+            vec!["on", "nopass", "~guest:*", "+GET"]
+        );
+    }
+    #[test]
+    fn copy_user_fail() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        provider.set_user_rules(b"guest", rules(&["on"])).unwrap();
+        provider.set_user_rules(b"other", rules(&["off"])).unwrap();
+        assert_eq!(
+            provider.copy_user(b"nobody", b"guest2", b"pass"),
+            Err(AuthError::BadCredentials)
+        );
+        assert_eq!(
+            provider.copy_user(b"guest", b"other", b"pass"),
+            Err(AuthError::AlreadyClaimed)
+        );
+        assert_eq!(
+            provider.copy_user(b"root", b"guest2", b"pass"),
+            Err(AuthError::Other(errors::AUTH_ERROR_ILLEGAL_USERNAME))
+        );
+        // nothing was changed
+        assert_eq!(
+            provider.get_user_rules(b"other").unwrap().unwrap(),
+            vec!["off"]
+        );
+        assert_eq!(provider.get_user_rules(b"guest2").unwrap(), None);
+        provider.logout().unwrap();
+        assert_eq!(
+            provider.copy_user(b"guest", b"guest2", b"pass"),
+            Err(AuthError::Anonymous)
+        );
+    }
+    #[test]
+    fn aclfile_fail_not_configured() {
+        let mut provider = AuthProvider::new_blank(Some(*ORIG));
+        let _ = provider.claim_root(ORIG).unwrap();
+        assert_eq!(
+            provider.aclfile(),
+            Err(AuthError::Other(errors::AUTH_ERROR_NO_ACLFILE))
+        );
+    }
+}
@@ -87,6 +87,48 @@ pub(super) fn parse_cli_args(matches: ArgMatches) -> Configset {
     );
     fcli!(server_mode, matches.value_of("mode"), "--mode");
     fcli!(server_maxcon, matches.value_of("maxcon"), "--maxcon");
+    fcli!(
+        server_membudget,
+        matches.value_of("membudget"),
+        "--membudget"
+    );
+    fcli!(
+        server_max_pipeline_length,
+        matches.value_of("max-pipeline-length"),
+        "--max-pipeline-length"
+    );
+    fcli!(
+        server_max_query_size,
+        matches.value_of("max-query-size"),
+        "--max-query-size"
+    );
+    fcli!(
+        server_buffer_shrink_threshold,
+        matches.value_of("buffer-shrink-threshold"),
+        "--buffer-shrink-threshold"
+    );
+    fcli!(
+        server_client_idle_timeout_secs,
+        matches.value_of("client-idle-timeout-secs"),
+        "--client-idle-timeout-secs"
+    );
+    fcli!(
+        server_protect_large_flush,
+        Flag::<true>::new(matches.is_present("protect-large-flush")),
+        "--protect-large-flush"
+    );
+    fcli!(
+        server_verbose_protocol_errors,
+        Flag::<true>::new(matches.is_present("verbose-protocol-errors")),
+        "--verbose-protocol-errors"
+    );
+    fcli!(
+        server_unixsock,
+        matches.value_of("unixsock-path"),
+        "--unixsock-path",
+        matches.value_of("unixsock-perm"),
+        "--unixsock-perm"
+    );
     // bgsave settings
     fcli!(
         bgsave_settings,
@@ -117,13 +159,19 @@ pub(super) fn parse_cli_args(matches: ArgMatches) -> Configset {
         Flag::<true>::new(matches.is_present("sslonly")),
         "--sslonly",
         matches.value_of("tlspass"),
-        "--tlspassin"
+        "--tlspassin",
+        matches.value_of("tlsminversion"),
+        "--tlsminversion",
+        matches.value_of("tlsciphers"),
+        "--tlsciphers"
     );
     // auth settings
     fcli!(
         auth_settings,
         matches.value_of("authkey"),
-        "--auth-origin-key"
+        "--auth-origin-key",
+        matches.value_of("aclfile"),
+        "--auth-aclfile"
     );
     defset
 }
@@ -48,7 +48,28 @@ pub(super) fn parse_env_config() -> Configset {
     fenv!(server_tcp, SKY_SYSTEM_HOST, SKY_SYSTEM_PORT);
     fenv!(server_noart, SKY_SYSTEM_NOART);
     fenv!(server_maxcon, SKY_SYSTEM_MAXCON);
+    fenv!(server_membudget, SKY_SYSTEM_MEMBUDGET);
+    fenv!(server_max_pipeline_length, SKY_SYSTEM_MAX_PIPELINE_LENGTH);
+    fenv!(server_max_query_size, SKY_SYSTEM_MAX_QUERY_SIZE);
+    fenv!(
+        server_buffer_shrink_threshold,
+        SKY_SYSTEM_BUFFER_SHRINK_THRESHOLD
+    );
+    fenv!(
+        server_client_idle_timeout_secs,
+        SKY_SYSTEM_CLIENT_IDLE_TIMEOUT_SECS
+    );
+    fenv!(server_protect_large_flush, SKY_SYSTEM_PROTECT_LARGE_FLUSH);
+    fenv!(
+        server_verbose_protocol_errors,
+        SKY_SYSTEM_VERBOSE_PROTOCOL_ERRORS
+    );
     fenv!(server_mode, SKY_DEPLOY_MODE);
+    fenv!(
+        server_unixsock,
+        SKY_SYSTEM_UNIXSOCK_PATH,
+        SKY_SYSTEM_UNIXSOCK_PERM
+    );
     // bgsave settings
     fenv!(bgsave_settings, SKY_BGSAVE_ENABLED, SKY_BGSAVE_DURATION);
     // snapshot settings
@@ -65,8 +86,10 @@ pub(super) fn parse_env_config() -> Configset {
         SKY_TLS_CERT,
         SKY_TLS_PORT,
         SKY_TLS_ONLY,
-        SKY_TLS_PASSIN
+        SKY_TLS_PASSIN,
+        SKY_TLS_MIN_VERSION,
+        SKY_TLS_CIPHERS
     );
-    fenv!(auth_settings, SKY_AUTH_ORIGIN_KEY);
+    fenv!(auth_settings, SKY_AUTH_ORIGIN_KEY, SKY_AUTH_ACLFILE);
     defset
 }
@@ -25,7 +25,8 @@
 */
 
 use super::{
-    AuthSettings, ConfigSourceParseResult, Configset, Modeset, OptString, TryFromConfigSource,
+    AuthSettings, ConfigSourceParseResult, Configset, Modeset, OptString, SocketPerm, TlsVersion,
+    TryFromConfigSource,
 };
 use serde::Deserialize;
 use std::net::IpAddr;
@@ -57,8 +58,26 @@ pub struct ConfigKeyServer {
     pub(super) noart: Option<bool>,
     /// The maximum number of clients
     pub(super) maxclient: Option<usize>,
+    /// The memory budget for data in bytes
+    pub(super) membudget: Option<usize>,
+    /// The maximum number of queries in a pipeline
+    pub(super) max_pipeline_length: Option<usize>,
+    /// The maximum number of bytes that a query can take
+    pub(super) max_query_size: Option<usize>,
+    /// The read buffer capacity above which a connection's buffer is shrunk
+    pub(super) buffer_shrink_threshold: Option<usize>,
+    /// The number of seconds after which an idle client is disconnected
+    pub(super) client_idle_timeout_secs: Option<u64>,
+    /// Whether large tables can only be flushed with `FLUSHDB <entity> force`
+    pub(super) protect_large_flush: Option<bool>,
+    /// Whether packet errors include the offset at which the packet was rejected
+    pub(super) verbose_protocol_errors: Option<bool>,
     /// The deployment mode
     pub(super) mode: Option<Modeset>,
+    /// The path of a unix socket to listen on
+    pub(super) unixsock_path: Option<String>,
+    /// The permissions of the unix socket file
+    pub(super) unixsock_perm: Option<SocketPerm>,
 }
 
 /// The BGSAVE section in the config file
@@ -95,6 +114,8 @@ pub struct KeySslOpts {
     pub(super) port: u16,
     pub(super) only: Option<bool>,
     pub(super) passin: Option<String>,
+    pub(super) min_version: Option<TlsVersion>,
+    pub(super) ciphers: Option<Vec<String>>,
 }
 
 /// A custom non-null type for config files
@@ -176,8 +197,39 @@ pub fn from_file(file: ConfigFile) -> Configset {
         "server.port",
     );
     set.server_maxcon(Optional::from(server.maxclient), "server.maxcon");
+    set.server_membudget(Optional::from(server.membudget), "server.membudget");
+    set.server_max_pipeline_length(
+        Optional::from(server.max_pipeline_length),
+        "server.max_pipeline_length",
+    );
+    set.server_max_query_size(
+        Optional::from(server.max_query_size),
+        "server.max_query_size",
+    );
+    set.server_buffer_shrink_threshold(
+        Optional::from(server.buffer_shrink_threshold),
+        "server.buffer_shrink_threshold",
+    );
+    set.server_client_idle_timeout_secs(
+        Optional::from(server.client_idle_timeout_secs),
+        "server.client_idle_timeout_secs",
+    );
+    set.server_protect_large_flush(
+        Optional::from(server.protect_large_flush),
+        "server.protect_large_flush",
+    );
+    set.server_verbose_protocol_errors(
+        Optional::from(server.verbose_protocol_errors),
+        "server.verbose_protocol_errors",
+    );
     set.server_noart(Optional::from(server.noart), "server.noart");
     set.server_mode(Optional::from(server.mode), "server.mode");
+    set.server_unixsock(
+        OptString::from(server.unixsock_path),
+        "server.unixsock_path",
+        Optional::from(server.unixsock_perm),
+        "server.unixsock_perm",
+    );
     // bgsave settings
     if let Some(bgsave) = bgsave {
         let ConfigKeyBGSAVE { enabled, every } = bgsave;
@@ -212,6 +264,8 @@ pub fn from_file(file: ConfigFile) -> Configset {
             port,
             only,
             passin,
+            min_version,
+            ciphers,
         } = tls;
         set.tls_settings(
             NonNull::from(key),
@@ -224,11 +278,23 @@ pub fn from_file(file: ConfigFile) -> Configset {
             "ssl.only",
             OptString::from(passin),
             "ssl.passin",
+            Optional::from(min_version),
+            "ssl.min_version",
+            OptString::from(ciphers.map(|ciphers| ciphers.join(":"))),
+            "ssl.ciphers",
         );
     }
     if let Some(auth) = auth {
-        let AuthSettings { origin_key } = auth;
-        set.auth_settings(Optional::from(origin_key), "auth.origin")
+        let AuthSettings {
+            origin_key,
+            aclfile,
+        } = auth;
+        set.auth_settings(
+            Optional::from(origin_key),
+            "auth.origin",
+            OptString::from(aclfile),
+            "auth.aclfile",
+        )
     }
     set
 }
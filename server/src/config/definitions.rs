@@ -26,7 +26,10 @@
 
 use super::{feedback::WarningStack, DEFAULT_IPV4, DEFAULT_PORT};
 use crate::config::AuthkeyWrapper;
-use crate::dbnet::MAXIMUM_CONNECTION_LIMIT;
+use crate::dbnet::{
+    DEFAULT_BUFFER_SHRINK_THRESHOLD, DEFAULT_MAX_QUERY_SIZE, MAXIMUM_CONNECTION_LIMIT,
+};
+use crate::protocol::DEFAULT_MAX_PIPELINE_LENGTH;
 use core::fmt;
 use core::str::FromStr;
 use serde::{
@@ -83,21 +86,46 @@ pub struct ConfigurationSet {
     pub ports: PortConfig,
     /// The maximum number of connections
     pub maxcon: usize,
+    /// The memory budget for data in bytes (0 is unlimited)
+    pub membudget: usize,
+    /// The maximum number of queries in a pipeline
+    pub max_pipeline_length: usize,
+    /// The maximum number of bytes that a query can take
+    pub max_query_size: usize,
+    /// The read buffer capacity (in bytes) above which a connection's buffer is shrunk
+    pub buffer_shrink_threshold: usize,
+    /// The number of seconds after which an idle client is disconnected (0 to disable)
+    pub client_idle_timeout_secs: u64,
+    /// Whether large tables can only be flushed with `FLUSHDB <entity> force`
+    pub protect_large_flush: bool,
+    /// Whether packet errors include the offset at which the packet was rejected
+    pub verbose_protocol_errors: bool,
     /// The deployment mode
     pub mode: Modeset,
     /// The auth settings
     pub auth: AuthSettings,
+    /// The unix socket to listen on in addition to the TCP ports, if any
+    pub unixsock: Option<UnixSockOpts>,
 }
 
 impl ConfigurationSet {
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         noart: bool,
         bgsave: BGSave,
         snapshot: SnapshotConfig,
         ports: PortConfig,
         maxcon: usize,
+        membudget: usize,
+        max_pipeline_length: usize,
+        max_query_size: usize,
+        buffer_shrink_threshold: usize,
+        client_idle_timeout_secs: u64,
+        protect_large_flush: bool,
+        verbose_protocol_errors: bool,
         mode: Modeset,
         auth: AuthSettings,
+        unixsock: Option<UnixSockOpts>,
     ) -> Self {
         Self {
             noart,
@@ -105,8 +133,16 @@ impl ConfigurationSet {
             snapshot,
             ports,
             maxcon,
+            membudget,
+            max_pipeline_length,
+            max_query_size,
+            buffer_shrink_threshold,
+            client_idle_timeout_secs,
+            protect_large_flush,
+            verbose_protocol_errors,
             mode,
             auth,
+            unixsock,
         }
     }
     /// Create a default `ConfigurationSet` with the following setup defaults:
@@ -116,6 +152,14 @@ impl ConfigurationSet {
     /// - `bgsave_enabled` : true
     /// - `bgsave_duration` : 120
     /// - `ssl` : disabled
+    /// - `membudget` : 0 (unlimited)
+    /// - `max_pipeline_length` : 1024
+    /// - `max_query_size` : 64 MiB
+    /// - `buffer_shrink_threshold` : 1 MiB
+    /// - `client_idle_timeout_secs` : 0 (disabled)
+    /// - `protect_large_flush` : false
+    /// - `verbose_protocol_errors` : false
+    /// - `unixsock` : disabled
     pub const fn default() -> Self {
         Self::new(
             false,
@@ -123,8 +167,16 @@ impl ConfigurationSet {
             SnapshotConfig::default(),
             PortConfig::new_insecure_only(DEFAULT_IPV4, 2003),
             MAXIMUM_CONNECTION_LIMIT,
+            0,
+            DEFAULT_MAX_PIPELINE_LENGTH,
+            DEFAULT_MAX_QUERY_SIZE,
+            DEFAULT_BUFFER_SHRINK_THRESHOLD,
+            0,
+            false,
+            false,
             Modeset::Dev,
             AuthSettings::default(),
+            None,
         )
     }
     /// Returns `false` if `noart` is enabled. Otherwise it returns `true`
@@ -225,6 +277,10 @@ pub struct SslOpts {
     pub chain: String,
     pub port: u16,
     pub passfile: Option<String>,
+    /// the lowest TLS version that clients can use (if `None`, the TLS library's default)
+    pub min_version: Option<TlsVersion>,
+    /// an OpenSSL cipher list (separated by `:`) that restricts the ciphers clients can use
+    pub ciphers: Option<String>,
 }
 
 impl SslOpts {
@@ -234,6 +290,8 @@ impl SslOpts {
             chain,
             port,
             passfile,
+            min_version: None,
+            ciphers: None,
         }
     }
     pub const fn get_port(&self) -> u16 {
@@ -241,6 +299,61 @@ impl SslOpts {
     }
 }
 
+/// The unix domain socket configuration
+#[derive(Debug, PartialEq)]
+pub struct UnixSockOpts {
+    /// the path of the socket file
+    pub path: String,
+    /// the permissions of the socket file (if `None`, the ones given by the umask)
+    pub perm: Option<SocketPerm>,
+}
+
+impl UnixSockOpts {
+    pub const fn new(path: String, perm: Option<SocketPerm>) -> Self {
+        Self { path, perm }
+    }
+}
+
+/// The permission bits of a socket file, written in octal (for example `770`)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SocketPerm(pub u32);
+
+impl FromStr for SocketPerm {
+    type Err = ();
+    fn from_str(st: &str) -> Result<SocketPerm, Self::Err> {
+        match u32::from_str_radix(st, 8) {
+            Ok(mode) if mode <= 0o777 => Ok(SocketPerm(mode)),
+            _ => Err(()),
+        }
+    }
+}
+
+struct SocketPermVisitor;
+
+impl<'de> Visitor<'de> for SocketPermVisitor {
+    type Value = SocketPerm;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expecting a string with octal permission bits")
+    }
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        value
+            .parse()
+            .map_err(|_| E::custom(format!("Bad value `{value}` for socket permissions")))
+    }
+}
+
+impl<'de> Deserialize<'de> for SocketPerm {
+    fn deserialize<D>(deserializer: D) -> Result<SocketPerm, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(SocketPermVisitor)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 /// The snapshot configuration
 ///
@@ -301,6 +414,7 @@ pub struct ConfigType {
     restore: RestoreFile,
     is_custom: bool,
     warnings: Option<WarningStack>,
+    migrate: bool,
 }
 
 impl ConfigType {
@@ -315,6 +429,7 @@ impl ConfigType {
             restore,
             is_custom,
             warnings,
+            migrate: false,
         }
     }
     pub fn print_warnings(&self) {
@@ -328,6 +443,13 @@ impl ConfigType {
     pub fn is_custom(&self) -> bool {
         self.is_custom
     }
+    /// Returns true if we were asked to run an offline migration instead of serving
+    pub fn is_migrate(&self) -> bool {
+        self.migrate
+    }
+    pub fn set_migrate(&mut self, migrate: bool) {
+        self.migrate = migrate;
+    }
     pub fn is_artful(&self) -> bool {
         self.config.is_artful()
     }
@@ -404,19 +526,69 @@ impl<'de> Deserialize<'de> for Modeset {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A TLS protocol version
+pub enum TlsVersion {
+    Tls12,
+    Tls13,
+}
+
+impl FromStr for TlsVersion {
+    type Err = ();
+    fn from_str(st: &str) -> Result<TlsVersion, Self::Err> {
+        match st {
+            "1.2" => Ok(TlsVersion::Tls12),
+            "1.3" => Ok(TlsVersion::Tls13),
+            _ => Err(()),
+        }
+    }
+}
+
+struct TlsVersionVisitor;
+
+impl<'de> Visitor<'de> for TlsVersionVisitor {
+    type Value = TlsVersion;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expecting a string with the TLS version")
+    }
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        value
+            .parse()
+            .map_err(|_| E::custom(format!("Bad value `{value}` for TLS version")))
+    }
+}
+
+impl<'de> Deserialize<'de> for TlsVersion {
+    fn deserialize<D>(deserializer: D) -> Result<TlsVersion, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(TlsVersionVisitor)
+    }
+}
+
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct AuthSettings {
     pub origin_key: Option<AuthkeyWrapper>,
+    /// the file used by `ACL SAVE` and `ACL LOAD`
+    pub aclfile: Option<String>,
 }
 
 impl AuthSettings {
     pub const fn default() -> Self {
-        Self { origin_key: None }
+        Self {
+            origin_key: None,
+            aclfile: None,
+        }
     }
     #[cfg(test)]
-    pub fn new(origin: AuthkeyWrapper) -> Self {
+    pub fn new(origin: AuthkeyWrapper, aclfile: Option<String>) -> Self {
         Self {
             origin_key: Some(origin),
+            aclfile,
         }
     }
 }
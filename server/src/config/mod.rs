@@ -46,7 +46,10 @@ mod tests;
 use self::cfgfile::Config as ConfigFile;
 pub use self::definitions::*;
 use self::feedback::{ConfigError, ErrorStack, WarningStack};
-use crate::dbnet::MAXIMUM_CONNECTION_LIMIT;
+use crate::dbnet::{
+    DEFAULT_BUFFER_SHRINK_THRESHOLD, DEFAULT_MAX_QUERY_SIZE, MAXIMUM_CONNECTION_LIMIT,
+};
+use crate::protocol::DEFAULT_MAX_PIPELINE_LENGTH;
 
 // server defaults
 const DEFAULT_IPV4: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
@@ -409,6 +412,97 @@ impl Configset {
         );
         self.cfg.maxcon = maxcon;
     }
+    pub fn server_membudget(
+        &mut self,
+        nmembudget: impl TryFromConfigSource<usize>,
+        nmembudget_key: StaticStr,
+    ) {
+        let mut membudget = 0;
+        self.try_mutate(
+            nmembudget,
+            &mut membudget,
+            nmembudget_key,
+            "a positive integer (or 0 for no limit)",
+        );
+        self.cfg.membudget = membudget;
+    }
+    pub fn server_max_pipeline_length(
+        &mut self,
+        nmax: impl TryFromConfigSource<usize>,
+        nmax_key: StaticStr,
+    ) {
+        let mut max = DEFAULT_MAX_PIPELINE_LENGTH;
+        self.try_mutate_with_condcheck(
+            nmax,
+            &mut max,
+            nmax_key,
+            "a positive integer greater than zero",
+            |max| *max > 0,
+        );
+        self.cfg.max_pipeline_length = max;
+    }
+    pub fn server_max_query_size(
+        &mut self,
+        nmax: impl TryFromConfigSource<usize>,
+        nmax_key: StaticStr,
+    ) {
+        let mut max = DEFAULT_MAX_QUERY_SIZE;
+        self.try_mutate_with_condcheck(
+            nmax,
+            &mut max,
+            nmax_key,
+            "a positive integer greater than zero",
+            |max| *max > 0,
+        );
+        self.cfg.max_query_size = max;
+    }
+    pub fn server_buffer_shrink_threshold(
+        &mut self,
+        nthreshold: impl TryFromConfigSource<usize>,
+        nthreshold_key: StaticStr,
+    ) {
+        let mut threshold = DEFAULT_BUFFER_SHRINK_THRESHOLD;
+        self.try_mutate_with_condcheck(
+            nthreshold,
+            &mut threshold,
+            nthreshold_key,
+            "a positive integer greater than zero",
+            |threshold| *threshold > 0,
+        );
+        self.cfg.buffer_shrink_threshold = threshold;
+    }
+    pub fn server_client_idle_timeout_secs(
+        &mut self,
+        ntimeout: impl TryFromConfigSource<u64>,
+        ntimeout_key: StaticStr,
+    ) {
+        let mut timeout = 0;
+        self.try_mutate(
+            ntimeout,
+            &mut timeout,
+            ntimeout_key,
+            "a positive integer (or 0 to disable)",
+        );
+        self.cfg.client_idle_timeout_secs = timeout;
+    }
+    pub fn server_protect_large_flush(
+        &mut self,
+        nprotect: impl TryFromConfigSource<bool>,
+        nprotect_key: StaticStr,
+    ) {
+        let mut protect = false;
+        self.try_mutate(nprotect, &mut protect, nprotect_key, "true/false");
+        self.cfg.protect_large_flush = protect;
+    }
+    pub fn server_verbose_protocol_errors(
+        &mut self,
+        nverbose: impl TryFromConfigSource<bool>,
+        nverbose_key: StaticStr,
+    ) {
+        let mut verbose = false;
+        self.try_mutate(nverbose, &mut verbose, nverbose_key, "true/false");
+        self.cfg.verbose_protocol_errors = verbose;
+    }
     pub fn server_mode(&mut self, nmode: impl TryFromConfigSource<Modeset>, nmode_key: StaticStr) {
         let mut modeset = Modeset::Dev;
         self.try_mutate(
@@ -419,6 +513,36 @@ impl Configset {
         );
         self.cfg.mode = modeset;
     }
+    pub fn server_unixsock(
+        &mut self,
+        npath: impl TryFromConfigSource<OptString>,
+        npath_key: StaticStr,
+        nperm: impl TryFromConfigSource<SocketPerm>,
+        nperm_key: StaticStr,
+    ) {
+        if npath.is_present() {
+            let mut path = OptString::new_null();
+            self.try_mutate(npath, &mut path, npath_key, "path to the socket file");
+            let mut perm = None;
+            if nperm.is_present() {
+                let mut mode = SocketPerm(0);
+                self.try_mutate(nperm, &mut mode, nperm_key, "octal permission bits");
+                perm = Some(mode);
+            }
+            if cfg!(unix) {
+                self.cfg.unixsock = path.base.map(|path| UnixSockOpts::new(path, perm));
+            } else {
+                self.wstack.push(format!(
+                    "Specifying `{npath_key}` is pointless since unix sockets are not supported on this platform"
+                ));
+            }
+        } else if nperm.is_present() {
+            self.mutated();
+            self.wstack.push(format!(
+                "Specifying `{nperm_key}` is pointless when the unix socket is disabled"
+            ));
+        }
+    }
 }
 
 // bgsave settings
@@ -526,6 +650,10 @@ impl Configset {
         nonly_key: StaticStr,
         npass: impl TryFromConfigSource<OptString>,
         npass_key: StaticStr,
+        nminver: impl TryFromConfigSource<TlsVersion>,
+        nminver_key: StaticStr,
+        nciphers: impl TryFromConfigSource<OptString>,
+        nciphers_key: StaticStr,
     ) {
         match (nkey.is_present(), ncert.is_present()) {
             (true, true) => {
@@ -552,7 +680,26 @@ impl Configset {
                     "path to TLS cert passphrase",
                 );
 
-                let sslopts = SslOpts::new(key, cert, port, tls_pass.base);
+                // now restrict the protocol versions and ciphers, if asked to
+                let mut min_version = None;
+                if nminver.is_present() {
+                    let mut version = TlsVersion::Tls12;
+                    self.try_mutate(nminver, &mut version, nminver_key, "`1.2` or `1.3`");
+                    min_version = Some(version);
+                }
+                let mut ciphers = OptString::new_null();
+                self.try_mutate(
+                    nciphers,
+                    &mut ciphers,
+                    nciphers_key,
+                    "a list of TLS ciphers",
+                );
+
+                let sslopts = SslOpts {
+                    min_version,
+                    ciphers: ciphers.base,
+                    ..SslOpts::new(key, cert, port, tls_pass.base)
+                };
                 // now check if TLS only
                 if tls_only {
                     let host = self.cfg.ports.get_host();
@@ -587,6 +734,18 @@ impl Configset {
                         "Specifying `{npass_key}` is pointless when TLS is disabled"
                     ));
                 }
+                if nminver.is_present() {
+                    self.mutated();
+                    self.wstack.push(format!(
+                        "Specifying `{nminver_key}` is pointless when TLS is disabled"
+                    ));
+                }
+                if nciphers.is_present() {
+                    self.mutated();
+                    self.wstack.push(format!(
+                        "Specifying `{nciphers_key}` is pointless when TLS is disabled"
+                    ));
+                }
             }
         }
     }
@@ -598,13 +757,23 @@ impl Configset {
         &mut self,
         nauth: impl TryFromConfigSource<AuthkeyWrapper>,
         nauth_key: StaticStr,
+        naclfile: impl TryFromConfigSource<OptString>,
+        naclfile_key: StaticStr,
     ) {
         let mut def = AuthkeyWrapper::empty();
         self.try_mutate(nauth, &mut def, nauth_key, "A 40-byte long ASCII string");
         if def != AuthkeyWrapper::empty() {
+            let mut aclfile = OptString::new_null();
+            self.try_mutate(naclfile, &mut aclfile, naclfile_key, "path to the ACL file");
             self.cfg.auth = AuthSettings {
                 origin_key: Some(def),
+                aclfile: aclfile.base,
             };
+        } else if naclfile.is_present() {
+            self.mutated();
+            self.wstack.push(format!(
+                "Specifying `{naclfile_key}` is pointless when authentication is disabled"
+            ));
         }
     }
 }
@@ -614,6 +783,7 @@ pub fn get_config() -> Result<ConfigType, ConfigError> {
     let cfg_layout = load_yaml!("../cli.yml");
     let matches = App::from_yaml(cfg_layout).get_matches();
     let restore_file = matches.value_of("restore").map(|v| v.to_string());
+    let migrate = matches.is_present("migrate");
 
     // get config from file
     let cfg_from_file = if let Some(file) = matches.value_of("config") {
@@ -637,12 +807,14 @@ pub fn get_config() -> Result<ConfigType, ConfigError> {
     if has_conflict {
         return Err(ConfigError::Conflict);
     }
-    if cfg_degree == 0 {
+    let mut cfg = if cfg_degree == 0 {
         // no configuration, use default
-        Ok(ConfigType::new_default(restore_file))
+        ConfigType::new_default(restore_file)
     } else {
         cfg_from_file
             .unwrap_or_else(|| cfg_from_env.and_then(cfg_from_cli))
-            .into_result(restore_file)
-    }
+            .into_result(restore_file)?
+    };
+    cfg.set_migrate(migrate);
+    Ok(cfg)
 }
@@ -24,7 +24,10 @@
  *
 */
 
-use super::{BGSave, Configset, PortConfig, SnapshotConfig, SnapshotPref, SslOpts, DEFAULT_IPV4};
+use super::{
+    BGSave, Configset, PortConfig, SnapshotConfig, SnapshotPref, SocketPerm, SslOpts, TlsVersion,
+    UnixSockOpts, DEFAULT_IPV4,
+};
 use crate::ROOT_DIR;
 use std::fs;
 
@@ -159,6 +162,189 @@ fn server_maxcon_fail() {
     assert_eq!(cfgset.cfg.maxcon, 50000);
 }
 
+#[test]
+fn server_max_pipeline_length_okay() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_max_pipeline_length(Some("64"), "SKY_SYSTEM_MAX_PIPELINE_LENGTH");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert_eq!(cfgset.cfg.max_pipeline_length, 64);
+}
+
+#[test]
+fn server_max_pipeline_length_fail() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_max_pipeline_length(Some("1024A"), "SKY_SYSTEM_MAX_PIPELINE_LENGTH");
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `SKY_SYSTEM_MAX_PIPELINE_LENGTH`. Expected a positive integer greater than zero"
+    );
+    assert_eq!(cfgset.cfg.max_pipeline_length, 1024);
+}
+
+#[test]
+fn server_max_query_size_okay() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_max_query_size(Some("1048576"), "SKY_SYSTEM_MAX_QUERY_SIZE");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert_eq!(cfgset.cfg.max_query_size, 1048576);
+}
+
+#[test]
+fn server_max_query_size_fail() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_max_query_size(Some("64MiB"), "SKY_SYSTEM_MAX_QUERY_SIZE");
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `SKY_SYSTEM_MAX_QUERY_SIZE`. Expected a positive integer greater than zero"
+    );
+    assert_eq!(cfgset.cfg.max_query_size, 64 * 1024 * 1024);
+}
+
+#[test]
+fn server_buffer_shrink_threshold_okay() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_buffer_shrink_threshold(Some("65536"), "SKY_SYSTEM_BUFFER_SHRINK_THRESHOLD");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert_eq!(cfgset.cfg.buffer_shrink_threshold, 65536);
+}
+
+#[test]
+fn server_buffer_shrink_threshold_fail() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_buffer_shrink_threshold(Some("1MiB"), "SKY_SYSTEM_BUFFER_SHRINK_THRESHOLD");
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `SKY_SYSTEM_BUFFER_SHRINK_THRESHOLD`. Expected a positive integer greater than zero"
+    );
+    assert_eq!(cfgset.cfg.buffer_shrink_threshold, 1024 * 1024);
+}
+
+#[test]
+fn server_client_idle_timeout_secs_okay() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_client_idle_timeout_secs(Some("300"), "SKY_SYSTEM_CLIENT_IDLE_TIMEOUT_SECS");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert_eq!(cfgset.cfg.client_idle_timeout_secs, 300);
+}
+
+#[test]
+fn server_client_idle_timeout_secs_fail() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_client_idle_timeout_secs(Some("5m"), "SKY_SYSTEM_CLIENT_IDLE_TIMEOUT_SECS");
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `SKY_SYSTEM_CLIENT_IDLE_TIMEOUT_SECS`. Expected a positive integer (or 0 to disable)"
+    );
+    assert_eq!(cfgset.cfg.client_idle_timeout_secs, 0);
+}
+
+#[test]
+fn server_protect_large_flush_okay() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_protect_large_flush(Some("true"), "SKY_SYSTEM_PROTECT_LARGE_FLUSH");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert!(cfgset.cfg.protect_large_flush);
+}
+
+#[test]
+fn server_protect_large_flush_fail() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_protect_large_flush(Some("yes"), "SKY_SYSTEM_PROTECT_LARGE_FLUSH");
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `SKY_SYSTEM_PROTECT_LARGE_FLUSH`. Expected true/false"
+    );
+    assert!(!cfgset.cfg.protect_large_flush);
+}
+
+#[test]
+fn server_verbose_protocol_errors_okay() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_verbose_protocol_errors(Some("true"), "SKY_SYSTEM_VERBOSE_PROTOCOL_ERRORS");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert!(cfgset.cfg.verbose_protocol_errors);
+}
+
+#[test]
+fn server_verbose_protocol_errors_fail() {
+    let mut cfgset = Configset::new_env();
+    cfgset.server_verbose_protocol_errors(Some("yes"), "SKY_SYSTEM_VERBOSE_PROTOCOL_ERRORS");
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `SKY_SYSTEM_VERBOSE_PROTOCOL_ERRORS`. Expected true/false"
+    );
+    assert!(!cfgset.cfg.verbose_protocol_errors);
+}
+
+#[cfg(unix)]
+#[test]
+fn server_unixsock_okay() {
+    let mut cfgset = Configset::new_cli();
+    cfgset.server_unixsock(
+        Some("/tmp/skyd.sock"),
+        "--unixsock-path",
+        Some("770"),
+        "--unixsock-perm",
+    );
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert_eq!(
+        cfgset.cfg.unixsock,
+        Some(UnixSockOpts::new(
+            "/tmp/skyd.sock".to_owned(),
+            Some(SocketPerm(0o770))
+        ))
+    );
+}
+
+#[test]
+fn server_unixsock_bad_perm() {
+    let mut cfgset = Configset::new_cli();
+    cfgset.server_unixsock(
+        Some("/tmp/skyd.sock"),
+        "--unixsock-path",
+        Some("800"),
+        "--unixsock-perm",
+    );
+    assert!(cfgset.is_mutated());
+    assert!(!cfgset.is_okay());
+    assert_eq!(
+        cfgset.estack[0],
+        "Bad value for `--unixsock-perm`. Expected octal permission bits"
+    );
+}
+
+#[test]
+fn server_unixsock_perm_without_path() {
+    let mut cfgset = Configset::new_cli();
+    cfgset.server_unixsock(None, "--unixsock-path", Some("770"), "--unixsock-perm");
+    assert!(cfgset.is_mutated());
+    assert!(cfgset.is_okay());
+    assert_eq!(
+        cfgset.wstack[0],
+        "Specifying `--unixsock-perm` is pointless when the unix socket is disabled"
+    );
+    assert_eq!(cfgset.cfg.unixsock, None);
+}
+
 // bgsave settings
 #[test]
 fn bgsave_okay() {
@@ -270,6 +456,10 @@ fn tls_settings_okay() {
         "SKY_TLS_ONLY",
         None,
         "SKY_TLS_PASSIN",
+        None,
+        "SKY_TLS_MIN_VERSION",
+        None,
+        "SKY_TLS_CIPHERS",
     );
     assert!(cfg.is_mutated());
     assert!(cfg.is_okay());
@@ -299,6 +489,10 @@ fn tls_settings_fail() {
         "SKY_TLS_ONLY",
         None,
         "SKY_TLS_PASSIN",
+        None,
+        "SKY_TLS_MIN_VERSION",
+        None,
+        "SKY_TLS_CIPHERS",
     );
     assert!(cfg.is_mutated());
     assert!(!cfg.is_okay());
@@ -314,6 +508,61 @@ fn tls_settings_fail() {
     });
 }
 
+#[test]
+fn tls_settings_min_version_and_ciphers() {
+    let mut cfg = Configset::new_env();
+    cfg.tls_settings(
+        Some("key.pem"),
+        "SKY_TLS_KEY",
+        Some("cert.pem"),
+        "SKY_TLS_CERT",
+        Some("2005"),
+        "SKY_TLS_PORT",
+        Some("false"),
+        "SKY_TLS_ONLY",
+        None,
+        "SKY_TLS_PASSIN",
+        Some("1.3"),
+        "SKY_TLS_MIN_VERSION",
+        Some("TLS_AES_256_GCM_SHA384"),
+        "SKY_TLS_CIPHERS",
+    );
+    assert!(cfg.is_mutated());
+    assert!(cfg.is_okay());
+    assert_eq!(cfg.cfg.ports, {
+        let mut pf = PortConfig::default();
+        pf.upgrade_to_tls(SslOpts {
+            min_version: Some(TlsVersion::Tls13),
+            ciphers: Some("TLS_AES_256_GCM_SHA384".to_owned()),
+            ..SslOpts::new("key.pem".to_owned(), "cert.pem".to_owned(), 2005, None)
+        });
+        pf
+    });
+}
+
+#[test]
+fn tls_settings_fail_with_bad_min_version() {
+    let mut cfg = Configset::new_env();
+    cfg.tls_settings(
+        Some("key.pem"),
+        "SKY_TLS_KEY",
+        Some("cert.pem"),
+        "SKY_TLS_CERT",
+        Some("2005"),
+        "SKY_TLS_PORT",
+        Some("false"),
+        "SKY_TLS_ONLY",
+        None,
+        "SKY_TLS_PASSIN",
+        Some("1.1"),
+        "SKY_TLS_MIN_VERSION",
+        None,
+        "SKY_TLS_CIPHERS",
+    );
+    assert!(cfg.is_mutated());
+    assert!(!cfg.is_okay());
+}
+
 #[test]
 fn tls_settings_fail_with_missing_required_values() {
     let mut cfg = Configset::new_env();
@@ -328,6 +577,10 @@ fn tls_settings_fail_with_missing_required_values() {
         "SKY_TLS_ONLY",
         None,
         "SKY_TLS_PASSIN",
+        None,
+        "SKY_TLS_MIN_VERSION",
+        None,
+        "SKY_TLS_CIPHERS",
     );
     assert!(cfg.is_mutated());
     assert!(!cfg.is_okay());
@@ -345,9 +598,12 @@ mod cfg_file_tests {
     use crate::config::AuthkeyWrapper;
     use crate::config::{
         cfgfile, AuthSettings, BGSave, Configset, ConfigurationSet, Modeset, PortConfig,
-        SnapshotConfig, SnapshotPref, SslOpts, DEFAULT_IPV4, DEFAULT_PORT,
+        SnapshotConfig, SnapshotPref, SslOpts, TlsVersion, DEFAULT_IPV4, DEFAULT_PORT,
+    };
+    use crate::dbnet::{
+        DEFAULT_BUFFER_SHRINK_THRESHOLD, DEFAULT_MAX_QUERY_SIZE, MAXIMUM_CONNECTION_LIMIT,
     };
-    use crate::dbnet::MAXIMUM_CONNECTION_LIMIT;
+    use crate::protocol::DEFAULT_MAX_PIPELINE_LENGTH;
     use std::net::{IpAddr, Ipv6Addr};
 
     fn cfgset_from_toml_str(file: String) -> Result<Configset, toml::de::Error> {
@@ -367,15 +623,20 @@ mod cfg_file_tests {
         expected.snapshot = SnapshotConfig::Enabled(SnapshotPref::new(3600, 4, true));
         expected.ports = PortConfig::new_secure_only(
             crate::config::DEFAULT_IPV4,
-            SslOpts::new(
-                "/path/to/keyfile.pem".to_owned(),
-                "/path/to/chain.pem".to_owned(),
-                2004,
-                Some("/path/to/cert/passphrase.txt".to_owned()),
-            ),
+            SslOpts {
+                min_version: Some(TlsVersion::Tls12),
+                ciphers: Some("ECDHE-RSA-AES128-GCM-SHA256:ECDHE-RSA-AES256-GCM-SHA384".to_owned()),
+                ..SslOpts::new(
+                    "/path/to/keyfile.pem".to_owned(),
+                    "/path/to/chain.pem".to_owned(),
+                    2004,
+                    Some("/path/to/cert/passphrase.txt".to_owned()),
+                )
+            },
         );
         expected.auth.origin_key =
             Some(AuthkeyWrapper::try_new(crate::TEST_AUTH_ORIGIN_KEY).unwrap());
+        expected.auth.aclfile = Some("/path/to/users.acl".to_owned());
         // check
         assert_eq!(cfg_from_file.cfg, expected);
     }
@@ -399,8 +660,16 @@ mod cfg_file_tests {
                 snapshot: SnapshotConfig::default(),
                 ports: PortConfig::default(),
                 maxcon: MAXIMUM_CONNECTION_LIMIT,
+                membudget: 0,
+                max_pipeline_length: DEFAULT_MAX_PIPELINE_LENGTH,
+                max_query_size: DEFAULT_MAX_QUERY_SIZE,
+                buffer_shrink_threshold: DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                client_idle_timeout_secs: 0,
+                protect_large_flush: false,
+                verbose_protocol_errors: false,
                 mode: Modeset::Dev,
                 auth: AuthSettings::default(),
+                unixsock: None,
             }
         );
     }
@@ -420,8 +689,16 @@ mod cfg_file_tests {
                     DEFAULT_PORT
                 ),
                 maxcon: MAXIMUM_CONNECTION_LIMIT,
+                membudget: 0,
+                max_pipeline_length: DEFAULT_MAX_PIPELINE_LENGTH,
+                max_query_size: DEFAULT_MAX_QUERY_SIZE,
+                buffer_shrink_threshold: DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                client_idle_timeout_secs: 0,
+                protect_large_flush: false,
+                verbose_protocol_errors: false,
                 mode: Modeset::Dev,
                 auth: AuthSettings::default(),
+                unixsock: None,
             }
         );
     }
@@ -438,16 +715,33 @@ mod cfg_file_tests {
                 SnapshotConfig::Enabled(SnapshotPref::new(3600, 4, true)),
                 PortConfig::new_secure_only(
                     DEFAULT_IPV4,
-                    SslOpts::new(
-                        "/path/to/keyfile.pem".into(),
-                        "/path/to/chain.pem".into(),
-                        2004,
-                        Some("/path/to/cert/passphrase.txt".to_owned())
-                    )
+                    SslOpts {
+                        min_version: Some(TlsVersion::Tls12),
+                        ciphers: Some(
+                            "ECDHE-RSA-AES128-GCM-SHA256:ECDHE-RSA-AES256-GCM-SHA384".to_owned()
+                        ),
+                        ..SslOpts::new(
+                            "/path/to/keyfile.pem".into(),
+                            "/path/to/chain.pem".into(),
+                            2004,
+                            Some("/path/to/cert/passphrase.txt".to_owned())
+                        )
+                    }
                 ),
                 MAXIMUM_CONNECTION_LIMIT,
+                0,
+                DEFAULT_MAX_PIPELINE_LENGTH,
+                DEFAULT_MAX_QUERY_SIZE,
+                DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                0,
+                false,
+                false,
                 Modeset::Dev,
-                AuthSettings::new(AuthkeyWrapper::try_new(crate::TEST_AUTH_ORIGIN_KEY).unwrap())
+                AuthSettings::new(
+                    AuthkeyWrapper::try_new(crate::TEST_AUTH_ORIGIN_KEY).unwrap(),
+                    Some("/path/to/users.acl".to_owned())
+                ),
+                None
             )
         );
     }
@@ -471,8 +765,16 @@ mod cfg_file_tests {
                 snapshot: SnapshotConfig::default(),
                 ports: PortConfig::default(),
                 maxcon: MAXIMUM_CONNECTION_LIMIT,
+                membudget: 0,
+                max_pipeline_length: DEFAULT_MAX_PIPELINE_LENGTH,
+                max_query_size: DEFAULT_MAX_QUERY_SIZE,
+                buffer_shrink_threshold: DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                client_idle_timeout_secs: 0,
+                protect_large_flush: false,
+                verbose_protocol_errors: false,
                 mode: Modeset::Dev,
                 auth: AuthSettings::default(),
+                unixsock: None,
             }
         );
     }
@@ -493,8 +795,16 @@ mod cfg_file_tests {
                 snapshot: SnapshotConfig::default(),
                 ports: PortConfig::default(),
                 maxcon: MAXIMUM_CONNECTION_LIMIT,
+                membudget: 0,
+                max_pipeline_length: DEFAULT_MAX_PIPELINE_LENGTH,
+                max_query_size: DEFAULT_MAX_QUERY_SIZE,
+                buffer_shrink_threshold: DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                client_idle_timeout_secs: 0,
+                protect_large_flush: false,
+                verbose_protocol_errors: false,
                 mode: Modeset::Dev,
                 auth: AuthSettings::default(),
+                unixsock: None,
             }
         )
     }
@@ -515,8 +825,16 @@ mod cfg_file_tests {
                 snapshot: SnapshotConfig::default(),
                 ports: PortConfig::default(),
                 maxcon: MAXIMUM_CONNECTION_LIMIT,
+                membudget: 0,
+                max_pipeline_length: DEFAULT_MAX_PIPELINE_LENGTH,
+                max_query_size: DEFAULT_MAX_QUERY_SIZE,
+                buffer_shrink_threshold: DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                client_idle_timeout_secs: 0,
+                protect_large_flush: false,
+                verbose_protocol_errors: false,
                 mode: Modeset::Dev,
                 auth: AuthSettings::default(),
+                unixsock: None,
             }
         )
     }
@@ -533,8 +851,16 @@ mod cfg_file_tests {
                 noart: false,
                 ports: PortConfig::default(),
                 maxcon: MAXIMUM_CONNECTION_LIMIT,
+                membudget: 0,
+                max_pipeline_length: DEFAULT_MAX_PIPELINE_LENGTH,
+                max_query_size: DEFAULT_MAX_QUERY_SIZE,
+                buffer_shrink_threshold: DEFAULT_BUFFER_SHRINK_THRESHOLD,
+                client_idle_timeout_secs: 0,
+                protect_large_flush: false,
+                verbose_protocol_errors: false,
                 mode: Modeset::Dev,
                 auth: AuthSettings::default(),
+                unixsock: None,
             }
         );
     }
@@ -268,7 +268,7 @@ impl<const N: usize> Integer64BufferRaw<N> {
         } else if int < Z_16 {
             // lets do 8 at a time
             let v0 = int / Z_8;
-            let v1 = int & Z_8;
+            let v1 = int % Z_8;
             let b0 = v0 / 10000;
             let c0 = v0 % 10000;
             let d1 = (b0 / 100) << 1;
@@ -409,4 +409,8 @@ fn test_int64_buffer() {
         Integer64::init(9348910481349849081_u64).as_ref()
     );
     assert_eq!(u64::MAX.to_string(), Integer64::init(u64::MAX).as_ref());
+    assert_eq!(
+        21474836482_u64.to_string(),
+        Integer64::init(21474836482_u64).as_ref()
+    );
 }
@@ -34,6 +34,7 @@ use crate::corestore::map::{
 use ahash::RandomState;
 use bytes::Bytes;
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::hash::Hash;
 use std::iter::FromIterator;
 use std::ops::Deref;
@@ -80,6 +81,11 @@ impl<K: Eq + Hash, V> Coremap<K, V> {
     pub fn clear(&self) {
         self.inner.clear()
     }
+    /// Clears the inner table, passing every entry to `f` (while holding the lock of its
+    /// shard) before it is dropped
+    pub fn clear_with(&self, f: impl FnMut(&K, &V)) {
+        self.inner.clear_with(f)
+    }
 }
 
 impl<K, V> Coremap<K, V>
@@ -122,6 +128,15 @@ where
     {
         self.inner.get(key)
     }
+    /// Atomically swap the values of two keys if both exist. Returns if the first and the
+    /// second key exist respectively
+    pub fn swap_values<Q>(&self, k1: &Q, k2: &Q) -> (bool, bool)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.swap_values(k1, k2)
+    }
     /// Returns true if the non-existent key was assigned to a value
     pub fn true_if_insert(&self, k: K, v: V) -> bool {
         if let Entry::Vacant(ve) = self.inner.entry(k) {
@@ -149,6 +164,22 @@ where
     pub fn upsert(&self, k: K, v: V) {
         let _ = self.inner.insert(k, v);
     }
+    /// Update or insert, returning the old value (if any)
+    pub fn replace(&self, k: K, v: V) -> Option<V> {
+        self.inner.insert(k, v)
+    }
+    /// Update or insert, returning the old value (if any). If the key didn't exist, `on_insert`
+    /// is passed the key while holding the entry's lock
+    pub fn replace_or_insert(&self, k: K, v: V, on_insert: impl FnOnce(&K)) -> Option<V> {
+        match self.inner.entry(k) {
+            Entry::Occupied(mut oe) => Some(oe.insert(v)),
+            Entry::Vacant(ve) => {
+                on_insert(ve.key());
+                ve.insert(v);
+                None
+            }
+        }
+    }
     /// Returns true if the value was updated
     pub fn true_if_update(&self, k: K, v: V) -> bool {
         if let Entry::Occupied(mut oe) = self.inner.entry(k) {
@@ -172,6 +203,67 @@ where
             None
         }
     }
+    /// Update or insert the value returned by `f`, which is passed the current value (if
+    /// any). `f` is run and the value is written while holding the entry's lock. Nothing
+    /// is written if `f` fails
+    pub fn upsert_with<T, E>(
+        &self,
+        k: K,
+        f: impl FnOnce(Option<&V>) -> Result<(V, T), E>,
+    ) -> Result<T, E> {
+        match self.inner.entry(k) {
+            Entry::Occupied(mut oe) => {
+                let (v, ret) = f(Some(oe.value()))?;
+                oe.insert(v);
+                Ok(ret)
+            }
+            Entry::Vacant(ve) => {
+                let (v, ret) = f(None)?;
+                ve.insert(v);
+                Ok(ret)
+            }
+        }
+    }
+}
+
+/// The outcome of [`Coremap::upsert_if_changed`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UpsertOutcome {
+    /// the key didn't exist and was inserted
+    Inserted,
+    /// the key existed with a different value which was replaced
+    Updated,
+    /// the key already had an equal value, so nothing was written
+    Unchanged,
+}
+
+impl<K: Eq + Hash, V: PartialEq> Coremap<K, V> {
+    /// Update or insert, unless the key already has an equal value. The comparison and the
+    /// write happen while holding the entry's lock. If something is written, `on_write` is
+    /// passed the key and the current value (if any) while holding the entry's lock
+    pub fn upsert_if_changed(
+        &self,
+        k: K,
+        v: V,
+        on_write: impl FnOnce(&K, Option<&V>),
+    ) -> UpsertOutcome {
+        match self.inner.entry(k) {
+            Entry::Occupied(mut oe) => {
+                if *oe.value() == v {
+                    UpsertOutcome::Unchanged
+                } else {
+                    on_write(oe.key(), Some(oe.value()));
+                    oe.insert(v);
+                    UpsertOutcome::Updated
+                }
+            }
+            Entry::Vacant(ve) => {
+                on_write(ve.key(), None);
+                ve.insert(v);
+                UpsertOutcome::Inserted
+            }
+        }
+    }
 }
 
 impl<K: Eq + Hash, V: Clone> Coremap<K, V> {
@@ -194,6 +286,11 @@ impl<K: Eq + Hash + Clone, V> Coremap<K, V> {
             .for_each(|key| v.push(key));
         v
     }
+    /// Returns at most `count` keys starting from `cursor`, along with the cursor to
+    /// continue from (0 once all the keys have been returned)
+    pub fn scan_keys(&self, cursor: u64, count: usize) -> (Vec<K>, u64) {
+        self.inner.scan_keys(cursor, count)
+    }
 }
 
 impl<K: Eq + Hash, V> IntoIterator for Coremap<K, V> {
@@ -290,6 +387,20 @@ impl Data {
 
 impl Eq for Data {}
 
+impl PartialOrd for Data {
+    fn partial_cmp(&self, oth: &Self) -> Option<Ordering> {
+        Some(self.cmp(oth))
+    }
+}
+
+impl Ord for Data {
+    /// Data is ordered bytewise, which is the same as ordering by the unicode code points
+    /// for unicode strings
+    fn cmp(&self, oth: &Self) -> Ordering {
+        self.blob.cmp(&oth.blob)
+    }
+}
+
 impl<T> From<T> for Data
 where
     T: Into<Bytes>,
@@ -130,6 +130,10 @@ impl<'a, K: Hash + Eq, V, S: BuildHasher> OccupiedEntry<'a, K, V, S> {
             hasher,
         }
     }
+    /// Get a ref to the key
+    pub fn key(&self) -> &K {
+        self.elem.0
+    }
     /// Get a ref to the value
     pub fn value(&self) -> &V {
         self.elem.1
@@ -165,6 +169,10 @@ impl<'a, K: Hash + Eq, V, S: BuildHasher> VacantEntry<'a, K, V, S> {
     pub(super) fn new(guard: RwLockWriteGuard<'a, LowMap<K, V>>, key: K, hasher: S) -> Self {
         Self { guard, key, hasher }
     }
+    /// Get a ref to the key
+    pub fn key(&self) -> &K {
+        &self.key
+    }
     /// Insert a value into this bucket
     pub fn insert(mut self, value: V) -> RefMut<'a, K, V> {
         unsafe {
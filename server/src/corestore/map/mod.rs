@@ -287,6 +287,61 @@ where
             // end critical section
         }
     }
+    /// Atomically swap the values of two keys. The swap only happens if both keys exist,
+    /// and the returned tuple tells if the first and the second key exist respectively.
+    ///
+    /// If both keys live in different shards, the shard locks are always acquired in
+    /// ascending order so that two concurrent swaps can never deadlock
+    pub fn swap_values<Q>(&self, k1: &Q, k2: &Q) -> (bool, bool)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (h1, h2) = (
+            make_hash::<K, Q, S>(self.h(), k1),
+            make_hash::<K, Q, S>(self.h(), k2),
+        );
+        let (idx1, idx2) = (
+            self.determine_shard(h1 as usize),
+            self.determine_shard(h2 as usize),
+        );
+        unsafe {
+            // begin critical section
+            if idx1 == idx2 {
+                let lowtable = self.get_wshard_unchecked(idx1);
+                match (lowtable.find(h1, ceq(k1)), lowtable.find(h2, ceq(k2))) {
+                    (Some(b1), Some(b2)) => {
+                        if b1.as_ptr() != b2.as_ptr() {
+                            mem::swap(&mut b1.as_mut().1, &mut b2.as_mut().1);
+                        }
+                        (true, true)
+                    }
+                    (b1, b2) => (b1.is_some(), b2.is_some()),
+                }
+            } else {
+                let (lo, hi) = if idx1 < idx2 {
+                    (idx1, idx2)
+                } else {
+                    (idx2, idx1)
+                };
+                let lotable = self.get_wshard_unchecked(lo);
+                let hitable = self.get_wshard_unchecked(hi);
+                let (t1, t2) = if idx1 < idx2 {
+                    (&lotable, &hitable)
+                } else {
+                    (&hitable, &lotable)
+                };
+                match (t1.find(h1, ceq(k1)), t2.find(h2, ceq(k2))) {
+                    (Some(b1), Some(b2)) => {
+                        mem::swap(&mut b1.as_mut().1, &mut b2.as_mut().1);
+                        (true, true)
+                    }
+                    (b1, b2) => (b1.is_some(), b2.is_some()),
+                }
+            }
+            // end critical section
+        }
+    }
 }
 
 // lt impls
@@ -371,6 +426,22 @@ impl<'a, K: 'a + Hash + Eq, V: 'a, S: BuildHasher + Clone> Skymap<K, V, S> {
     pub fn clear(&self) {
         self.shards().iter().for_each(|shard| shard.write().clear())
     }
+    /// Clear out all the entries in the Skymap, passing every entry to `f` (while holding the
+    /// lock of its shard) before it is dropped
+    pub fn clear_with(&self, mut f: impl FnMut(&K, &V)) {
+        self.shards().iter().for_each(|shard| {
+            let mut lowtable = shard.write();
+            unsafe {
+                // SAFETY: we hold the shard's write lock, so no bucket is moved or freed until the
+                // table is cleared below
+                lowtable.iter().for_each(|bucket| {
+                    let (k, v) = bucket.as_ref();
+                    f(k, v)
+                });
+            }
+            lowtable.clear();
+        })
+    }
 }
 
 // cloned impls
@@ -394,6 +465,74 @@ impl<'a, K, V: Clone, S: BuildHasher> Skymap<K, V, S> {
     }
 }
 
+// scan impls
+impl<K: Clone + Hash, V, S: BuildHasher> Skymap<K, V, S> {
+    /// Clone at most `count` keys, starting from `cursor`. This returns the keys and the
+    /// cursor to continue from, which is 0 once every shard has been scanned. The upper 32
+    /// bits of the cursor hold the shard index and the lower 32 bits hold a position in that
+    /// shard, so each shard is only locked while it is being scanned.
+    ///
+    /// Positions are the lower 32 bits of the hashes of the keys (the upper bits pick the
+    /// shard) and keys are returned in the order of their positions. Unlike bucket offsets,
+    /// positions don't change when a shard is resized, so every key that is present for the
+    /// entire duration of a scan is returned. Keys that share a position are always returned
+    /// together, so if more than `count` keys share one, all of them are returned
+    pub fn scan_keys(&self, cursor: u64, count: usize) -> (Vec<K>, u64) {
+        let mut shard = (cursor >> 32) as usize;
+        let mut position = cursor as u32;
+        let mut keys = Vec::with_capacity(count.min(DEFAULT_CAP));
+        let cursor_at = |shard: usize, position: u32| {
+            if shard < self.shards().len() {
+                ((shard as u64) << 32) | position as u64
+            } else {
+                0
+            }
+        };
+        while shard < self.shards().len() {
+            if keys.len() == count {
+                return (keys, cursor_at(shard, position));
+            }
+            let lowtable = self.shards()[shard].read();
+            let mut entries: Vec<(u32, &K)> = unsafe {
+                // SAFETY: the read lock on the shard is held until the keys have been collected
+                lowtable
+                    .iter()
+                    .map(|bucket| &bucket.as_ref().0)
+                    .map(|key| (make_insert_hash(self.h(), key) as u32, key))
+                    .filter(|(pos, _)| *pos >= position)
+                    .collect()
+            };
+            entries.sort_unstable_by_key(|(pos, _)| *pos);
+            let room = count - keys.len();
+            if entries.len() <= room {
+                keys.extend(entries.into_iter().map(|(_, key)| key.clone()));
+                shard += 1;
+                position = 0;
+                continue;
+            }
+            // the page is full; don't split the keys that share a position
+            let mut cut = room;
+            while cut > 0 && entries[cut].0 == entries[cut - 1].0 {
+                cut -= 1;
+            }
+            if cut == 0 {
+                if !keys.is_empty() {
+                    return (keys, cursor_at(shard, entries[0].0));
+                }
+                // not even the keys at the first position fit, so return all of them
+                let first = entries[0].0;
+                cut = entries.partition_point(|(pos, _)| *pos == first);
+            }
+            keys.extend(entries[..cut].iter().map(|(_, key)| (*key).clone()));
+            return match entries.get(cut) {
+                Some((pos, _)) => (keys, cursor_at(shard, *pos)),
+                None => (keys, cursor_at(shard + 1, 0)),
+            };
+        }
+        (keys, 0)
+    }
+}
+
 // inner impls
 impl<'a, K: 'a, V: 'a, S> Skymap<K, V, S> {
     /// Get a rlock to a certain stripe
@@ -430,6 +569,69 @@ fn test_insert_get() {
     assert_eq!(*_ref, "likes computational dark arts")
 }
 
+#[test]
+fn test_swap_values() {
+    let map = Skymap::default();
+    for i in 0..64 {
+        map.insert(i, i * 10);
+    }
+    // some of these will land in the same shard and some won't
+    for i in 1..64 {
+        assert_eq!(map.swap_values(&0, &i), (true, true));
+        assert_eq!(*map.get(&0).unwrap(), i * 10);
+        assert_eq!(*map.get(&i).unwrap(), 0);
+        assert_eq!(map.swap_values(&i, &0), (true, true));
+    }
+    // swapping a key with itself is a no-op
+    assert_eq!(map.swap_values(&1, &1), (true, true));
+    assert_eq!(*map.get(&1).unwrap(), 10);
+    // nothing changes if either key is missing
+    assert_eq!(map.swap_values(&1, &100), (true, false));
+    assert_eq!(map.swap_values(&100, &1), (false, true));
+    assert_eq!(map.swap_values(&100, &200), (false, false));
+    assert_eq!(*map.get(&1).unwrap(), 10);
+}
+
+#[test]
+fn test_swap_values_concurrent_consistency() {
+    use std::sync::Arc;
+    use std::thread;
+    let map = Arc::new(Skymap::default());
+    map.insert("live", 1);
+    map.insert("staged", 2);
+    let swappers: Vec<_> = (0..4)
+        .map(|_| {
+            let map = map.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    assert_eq!(map.swap_values("live", "staged"), (true, true));
+                }
+            })
+        })
+        .collect();
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let map = map.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    // a value is never lost or duplicated, it is always one of the two
+                    let live = *map.get("live").unwrap();
+                    let staged = *map.get("staged").unwrap();
+                    assert!(live == 1 || live == 2);
+                    assert!(staged == 1 || staged == 2);
+                }
+            })
+        })
+        .collect();
+    swappers
+        .into_iter()
+        .chain(readers)
+        .for_each(|t| t.join().unwrap());
+    // 4000 swaps so we should be back where we started
+    assert_eq!(*map.get("live").unwrap(), 1);
+    assert_eq!(*map.get("staged").unwrap(), 2);
+}
+
 #[test]
 fn test_entry() {
     let map = Skymap::default();
@@ -437,3 +639,78 @@ fn test_entry() {
     assert!(map.entry("hello").is_occupied());
     assert!(map.entry("world").is_vacant());
 }
+
+#[test]
+fn test_scan_keys() {
+    let map: Skymap<usize, ()> = Skymap::default();
+    assert_eq!(map.scan_keys(0, 10), (vec![], 0));
+    for i in 0..1000 {
+        map.insert(i, ());
+    }
+    let mut keys = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let (mut batch, next) = map.scan_keys(cursor, 64);
+        assert!(batch.len() <= 64);
+        keys.append(&mut batch);
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+    keys.sort_unstable();
+    assert_eq!(keys, (0..1000).collect::<Vec<usize>>());
+}
+
+#[cfg(test)]
+fn scan_all<V, S: BuildHasher>(map: &Skymap<usize, V, S>, count: usize) -> Vec<usize> {
+    let mut keys = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let (mut batch, next) = map.scan_keys(cursor, count);
+        assert!(batch.len() <= count);
+        keys.append(&mut batch);
+        if next == 0 {
+            break keys;
+        }
+        cursor = next;
+    }
+}
+
+#[test]
+fn test_scan_keys_large() {
+    let map: Skymap<usize, ()> = Skymap::default();
+    for i in 0..10_000 {
+        map.insert(i, ());
+    }
+    let mut keys = scan_all(&map, 100);
+    // no duplicates, since nothing changed during the scan
+    keys.sort_unstable();
+    assert_eq!(keys, (0..10_000).collect::<Vec<usize>>());
+}
+
+#[test]
+fn test_scan_keys_survives_resizes() {
+    let map: Skymap<usize, ()> = Skymap::default();
+    for i in 0..1000 {
+        map.insert(i, ());
+    }
+    let mut keys = Vec::new();
+    let mut cursor = 0;
+    let mut next_key = 1000;
+    loop {
+        let (mut batch, next) = map.scan_keys(cursor, 100);
+        keys.append(&mut batch);
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+        // grow the map (and resize the shards) between batches
+        for _ in 0..200 {
+            map.insert(next_key, ());
+            next_key += 1;
+        }
+    }
+    // the keys that were there for the entire scan are all returned
+    assert!((0..1000).all(|key| keys.contains(&key)));
+}
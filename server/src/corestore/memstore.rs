@@ -55,7 +55,7 @@
 //! and destructor.
 
 use super::KeyspaceResult;
-use crate::auth::Authmap;
+use crate::auth::{Aclmap, Authmap};
 use crate::corestore::array::Array;
 use crate::corestore::htable::Coremap;
 use crate::corestore::table::Table;
@@ -70,6 +70,7 @@ uninit_array! {
     const DEFAULT_ARRAY: [u8; 64] = [b'd', b'e', b'f', b'a', b'u', b'l', b't'];
     const SYSTEM_ARRAY: [u8; 64] = [b's', b'y', b's', b't', b'e', b'm'];
     const SYSTEM_AUTH_ARRAY: [u8; 64] = [b'a', b'u', b't', b'h'];
+    const SYSTEM_ACL_ARRAY: [u8; 64] = [b'a', b'c', b'l'];
 }
 
 /// typedef for the keyspace/table IDs. We don't need too much fancy here,
@@ -89,6 +90,10 @@ pub const AUTH: ObjectID = unsafe {
     // SAFETY: known init len
     Array::from_const(SYSTEM_AUTH_ARRAY, 4)
 };
+pub const ACL: ObjectID = unsafe {
+    // SAFETY: known init len
+    Array::from_const(SYSTEM_ACL_ARRAY, 3)
+};
 
 #[test]
 fn test_def_macro_sanity() {
@@ -221,11 +226,51 @@ impl Memstore {
             }
             None => match self.system.tables.get(&AUTH).unwrap().data {
                 SystemDataModel::Auth(ref am) => am.clone(),
-                #[allow(unreachable_patterns)]
                 _ => unsafe { impossible!() },
             },
         }
     }
+    pub fn setup_acl(&self) -> Aclmap {
+        match self.system.tables.fresh_entry(ACL) {
+            Some(fresh) => {
+                let r = Aclmap::default();
+                fresh.insert(Wrapper::new(SystemTable::new_acl(r.clone())));
+                // older data directories don't have this table in their preload
+                registry::get_preload_tripswitch().trip();
+                r
+            }
+            None => match self.system.tables.get(&ACL).unwrap().data {
+                SystemDataModel::Acl(ref acl) => acl.clone(),
+                _ => unsafe { impossible!() },
+            },
+        }
+    }
+    /// Returns an estimate of the memory used by the data in all the tables (in bytes)
+    pub fn memory_estimate(&self) -> usize {
+        self.keyspaces
+            .iter()
+            .map(|ks| {
+                ks.value()
+                    .tables
+                    .iter()
+                    .map(|tbl| tbl.value().memory_estimate())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+    /// Remove the expired keys in all the tables. Returns the number of keys removed
+    pub fn remove_expired(&self) -> usize {
+        self.keyspaces
+            .iter()
+            .map(|ks| {
+                ks.value()
+                    .tables
+                    .iter()
+                    .map(|tbl| tbl.value().remove_expired())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
     /// Get an atomic reference to a keyspace
     pub fn get_keyspace_atomic_ref<Q>(&self, keyspace_identifier: &Q) -> Option<Arc<Keyspace>>
     where
@@ -32,7 +32,7 @@ use crate::corestore::{
 use crate::queryengine::parser::{Entity, OwnedEntity};
 use crate::registry;
 use crate::storage;
-use crate::storage::v1::{error::StorageEngineResult, sengine::SnapshotEngine};
+use crate::storage::v1::{error::StorageEngineResult, flush::Autoflush, sengine::SnapshotEngine};
 use crate::util::Unwrappable;
 use core::borrow::Borrow;
 use core::hash::Hash;
@@ -56,6 +56,9 @@ mod tests;
 pub(super) type KeyspaceResult<T> = Result<T, DdlError>;
 
 #[derive(Debug, Clone)]
+/// The container state of a connection. Since the connection holds atomic references to its
+/// keyspace and table, neither can be dropped by another connection while they're in use
+/// (that fails with `still-in-use`), so they never go stale
 struct ConnectionEntityState {
     /// the current table for a connection
     table: Option<(ObjectID, Arc<Table>)>,
@@ -226,6 +229,8 @@ impl Corestore {
         entity: Entity<'_>,
         modelcode: u8,
         volatile: bool,
+        validate_json: bool,
+        ordered: bool,
     ) -> KeyspaceResult<()> {
         let entity = entity.into_owned();
         // first lock the global flush state
@@ -235,7 +240,9 @@ impl Corestore {
             OwnedEntity::Single(tblid) | OwnedEntity::Partial(tblid) => {
                 match &self.estate.ks {
                     Some((_, ks)) => {
-                        let tbl = Table::from_model_code(modelcode, volatile);
+                        let tbl = Table::from_model_code(modelcode, volatile)
+                            .and_then(|tbl| tbl.with_json_validation(validate_json))
+                            .and_then(|tbl| tbl.with_ordered_keys(ordered));
                         if let Some(tbl) = tbl {
                             if ks.create_table(tblid, tbl) {
                                 // we need to re-init tree; so trip
@@ -254,7 +261,9 @@ impl Corestore {
             OwnedEntity::Full(ksid, tblid) => {
                 match self.store.get_keyspace_atomic_ref(&ksid) {
                     Some(kspace) => {
-                        let tbl = Table::from_model_code(modelcode, volatile);
+                        let tbl = Table::from_model_code(modelcode, volatile)
+                            .and_then(|tbl| tbl.with_json_validation(validate_json))
+                            .and_then(|tbl| tbl.with_ordered_keys(ordered));
                         if let Some(tbl) = tbl {
                             if kspace.create_table(tblid, tbl) {
                                 // trip the preload switch
@@ -290,6 +299,46 @@ impl Corestore {
         }
     }
 
+    /// Change the persistence mode of a table. A table that is made persistent is flushed
+    /// right away, while a table that is made volatile is skipped from the next flush cycle
+    pub fn alter_table_persistence(
+        &self,
+        entity: Entity<'_>,
+        volatile: bool,
+    ) -> KeyspaceResult<()> {
+        let (ksid, ks, tblid) = match entity {
+            Entity::Single(tblid) | Entity::Partial(tblid) => match &self.estate.ks {
+                Some((ksid, ks)) => (ksid.clone(), ks.clone(), tblid),
+                None => return Err(DdlError::DefaultNotFound),
+            },
+            Entity::Full(ksid, tblid) => match self.store.get_keyspace_atomic_ref(ksid) {
+                Some(ks) => (unsafe { ObjectID::from_slice(ksid) }, ks, tblid),
+                None => return Err(DdlError::ObjectNotFound),
+            },
+        };
+        let tbl = ks
+            .get_table_atomic_ref(tblid)
+            .ok_or(DdlError::ObjectNotFound)?;
+        // lock the global flush state so that a flush cycle doesn't see the change halfway
+        let flush_lock = registry::lock_flush_state();
+        let was_volatile = tbl.set_volatile(volatile);
+        let ret = if was_volatile && !volatile {
+            let tblid = unsafe { ObjectID::from_slice(tblid) };
+            storage::v1::flush::flush_table_full(&Autoflush, &tblid, &ksid, &ks, &tbl).map_err(
+                |e| {
+                    log::error!("Failed to flush table made persistent: {}", e);
+                    // the table isn't on disk, so it has to stay volatile
+                    tbl.set_volatile(was_volatile);
+                    DdlError::DdlTransactionFailure
+                },
+            )
+        } else {
+            Ok(())
+        };
+        drop(flush_lock);
+        ret
+    }
+
     /// Create a keyspace **without any transactional guarantees**
     ///
     /// **Trip switch handled:** Yes
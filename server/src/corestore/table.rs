@@ -25,15 +25,16 @@
 */
 
 use crate::actions::ActionResult;
-use crate::auth::Authmap;
+use crate::auth::{Aclmap, Authmap};
 use crate::corestore::htable::Coremap;
 use crate::corestore::Data;
 #[cfg(test)]
 use crate::corestore::{memstore::DdlError, KeyspaceResult};
 use crate::dbnet::connection::prelude::Corestore;
-use crate::kvengine::{KVEListmap, KVEStandard, LockedVec};
+use crate::kvengine::{expiry::ExpiryTable, KVEListmap, KVEStandard, LockedVec};
 use crate::protocol::responses::groups;
 use crate::util;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 pub trait DescribeTable {
     type Table;
@@ -81,6 +82,7 @@ impl DescribeTable for KVEList {
 #[derive(Debug)]
 pub enum SystemDataModel {
     Auth(Authmap),
+    Acl(Aclmap),
 }
 
 #[derive(Debug)]
@@ -99,6 +101,9 @@ impl SystemTable {
     pub fn new_auth(authmap: Authmap) -> Self {
         Self::new(SystemDataModel::Auth(authmap))
     }
+    pub fn new_acl(aclmap: Aclmap) -> Self {
+        Self::new(SystemDataModel::Acl(aclmap))
+    }
 }
 
 #[derive(Debug)]
@@ -114,8 +119,8 @@ pub enum DataModel {
 pub struct Table {
     /// a key/value store
     model_store: DataModel,
-    /// is the table volatile
-    volatile: bool,
+    /// is the table volatile (can be changed with `ALTER TABLE`)
+    volatile: AtomicBool,
 }
 
 impl Table {
@@ -123,14 +128,14 @@ impl Table {
     pub const fn from_kve(kve: KVEStandard, volatile: bool) -> Self {
         Self {
             model_store: DataModel::KV(kve),
-            volatile,
+            volatile: AtomicBool::new(volatile),
         }
     }
     #[cfg(test)]
     pub const fn from_kve_listmap(kve: KVEListmap, volatile: bool) -> Self {
         Self {
             model_store: DataModel::KVExtListmap(kve),
-            volatile,
+            volatile: AtomicBool::new(volatile),
         }
     }
     /// Get the key/value store if the table is a key/value store
@@ -143,35 +148,72 @@ impl Table {
             Err(DdlError::WrongModel)
         }
     }
+    /// Returns the number of entries in this table, not counting expired keys
     pub fn count(&self) -> usize {
         match &self.model_store {
-            DataModel::KV(kv) => kv.len(),
-            DataModel::KVExtListmap(kv) => kv.len(),
+            DataModel::KV(kv) => kv.live_len(),
+            DataModel::KVExtListmap(kv) => kv.live_len(),
+        }
+    }
+    /// Returns at most `count` keys from this table, skipping expired keys
+    pub fn get_keys(&self, count: usize) -> Vec<Data> {
+        match &self.model_store {
+            DataModel::KV(kv) => kv.get_live_keys(count),
+            DataModel::KVExtListmap(kv) => kv.get_live_keys(count),
+        }
+    }
+    /// Remove the expired keys in this table. Returns the number of keys removed
+    pub fn remove_expired(&self) -> usize {
+        match &self.model_store {
+            DataModel::KV(kv) => kv.remove_expired(),
+            DataModel::KVExtListmap(kv) => kv.remove_expired(),
+        }
+    }
+    /// Returns a reference to the deadlines of the keys in this table
+    pub fn get_expiry_ref(&self) -> &ExpiryTable {
+        match &self.model_store {
+            DataModel::KV(kv) => kv.get_expiry_ref(),
+            DataModel::KVExtListmap(kv) => kv.get_expiry_ref(),
+        }
+    }
+    /// Set the deadlines of the keys that exist in this table, ignoring the rest
+    pub fn restore_deadlines(&self, deadlines: impl IntoIterator<Item = (Data, u64)>) {
+        match &self.model_store {
+            DataModel::KV(kv) => kv.restore_deadlines(deadlines),
+            DataModel::KVExtListmap(kv) => kv.restore_deadlines(deadlines),
+        }
+    }
+    /// Returns an estimate of the memory used by the data in this table (in bytes)
+    pub fn memory_estimate(&self) -> usize {
+        match &self.model_store {
+            DataModel::KV(kv) => kv.memory_estimate(),
+            DataModel::KVExtListmap(kv) => kv.memory_estimate(),
         }
     }
     /// Returns this table's _description_
-    pub fn describe_self(&self) -> &'static str {
-        match self.get_model_code() {
+    pub fn describe_self(&self) -> String {
+        let data = match self.get_model_code() {
             // pure KV
-            0 if self.is_volatile() => "Keymap { data:(binstr,binstr), volatile:true }",
-            0 if !self.is_volatile() => "Keymap { data:(binstr,binstr), volatile:false }",
-            1 if self.is_volatile() => "Keymap { data:(binstr,str), volatile:true }",
-            1 if !self.is_volatile() => "Keymap { data:(binstr,str), volatile:false }",
-            2 if self.is_volatile() => "Keymap { data:(str,str), volatile:true }",
-            2 if !self.is_volatile() => "Keymap { data:(str,str), volatile:false }",
-            3 if self.is_volatile() => "Keymap { data:(str,binstr), volatile:true }",
-            3 if !self.is_volatile() => "Keymap { data:(str,binstr), volatile:false }",
+            0 => "(binstr,binstr)",
+            1 => "(binstr,str)",
+            2 => "(str,str)",
+            3 => "(str,binstr)",
             // KVext => list
-            4 if self.is_volatile() => "Keymap { data:(binstr,list<binstr>), volatile:true }",
-            4 if !self.is_volatile() => "Keymap { data:(binstr,list<binstr>), volatile:false }",
-            5 if self.is_volatile() => "Keymap { data:(binstr,list<str>), volatile:true }",
-            5 if !self.is_volatile() => "Keymap { data:(binstr,list<str>), volatile:false }",
-            6 if self.is_volatile() => "Keymap { data:(str,list<binstr>), volatile:true }",
-            6 if !self.is_volatile() => "Keymap { data:(str,list<binstr>), volatile:false }",
-            7 if self.is_volatile() => "Keymap { data:(str,list<str>), volatile:true }",
-            7 if !self.is_volatile() => "Keymap { data:(str,list<str>), volatile:false }",
+            4 => "(binstr,list<binstr>)",
+            5 => "(binstr,list<str>)",
+            6 => "(str,list<binstr>)",
+            7 => "(str,list<str>)",
             _ => unsafe { impossible!() },
+        };
+        let volatile = self.is_volatile();
+        let mut properties = String::new();
+        if self.is_json_validated() {
+            properties.push_str(", validate:json");
+        }
+        if self.is_ordered() {
+            properties.push_str(", ordered");
         }
+        format!("Keymap {{ data:{data}, volatile:{volatile}{properties} }}")
     }
     pub fn truncate_table(&self) {
         match self.model_store {
@@ -180,12 +222,50 @@ impl Table {
         }
     }
     /// Returns the storage type as an 8-bit uint
-    pub const fn storage_type(&self) -> u8 {
-        self.volatile as u8
+    pub fn storage_type(&self) -> u8 {
+        self.is_volatile() as u8
+    }
+    /// Returns true if values in this table need to be well-formed JSON
+    pub fn is_json_validated(&self) -> bool {
+        match self.model_store {
+            DataModel::KV(ref kv) => kv.is_json_validated(),
+            DataModel::KVExtListmap(_) => false,
+        }
+    }
+    /// Enable JSON validation for values. Returns `None` if the data model doesn't support
+    /// JSON validation (only key/value tables do)
+    pub fn with_json_validation(mut self, validate_json: bool) -> Option<Self> {
+        match self.model_store {
+            DataModel::KV(ref mut kv) => kv.set_json_validation(validate_json),
+            DataModel::KVExtListmap(_) if validate_json => return None,
+            DataModel::KVExtListmap(_) => {}
+        }
+        Some(self)
+    }
+    /// Returns true if this table keeps an ordered index of its keys
+    pub fn is_ordered(&self) -> bool {
+        match self.model_store {
+            DataModel::KV(ref kv) => kv.is_ordered(),
+            DataModel::KVExtListmap(_) => false,
+        }
+    }
+    /// Enable the ordered index of keys. Returns `None` if the data model doesn't support
+    /// ordered indexes (only key/value tables do)
+    pub fn with_ordered_keys(mut self, ordered: bool) -> Option<Self> {
+        match self.model_store {
+            DataModel::KV(ref mut kv) => kv.set_ordered(ordered),
+            DataModel::KVExtListmap(_) if ordered => return None,
+            DataModel::KVExtListmap(_) => {}
+        }
+        Some(self)
     }
     /// Returns the volatility of the table
-    pub const fn is_volatile(&self) -> bool {
-        self.volatile
+    pub fn is_volatile(&self) -> bool {
+        self.volatile.load(Ordering::Acquire)
+    }
+    /// Change the volatility of the table, returning the previous one
+    pub fn set_volatile(&self, volatile: bool) -> bool {
+        self.volatile.swap(volatile, Ordering::AcqRel)
     }
     /// Create a new KVEBlob Table with the provided settings
     pub fn new_pure_kve_with_data(
@@ -195,7 +275,7 @@ impl Table {
         v_enc: bool,
     ) -> Self {
         Self {
-            volatile,
+            volatile: AtomicBool::new(volatile),
             model_store: DataModel::KV(KVEStandard::new(k_enc, v_enc, data)),
         }
     }
@@ -206,7 +286,7 @@ impl Table {
         payload_enc: bool,
     ) -> Self {
         Self {
-            volatile,
+            volatile: AtomicBool::new(volatile),
             model_store: DataModel::KVExtListmap(KVEListmap::new(k_enc, payload_enc, data)),
         }
     }
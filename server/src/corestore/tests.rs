@@ -167,3 +167,35 @@ mod modelcode_tests {
         assert_eq!(tbl4.get_model_code(), 7);
     }
 }
+
+mod alter_table_persistence_tests {
+    use super::super::{memstore::*, table::Table, Corestore};
+    use crate::kvengine::KVEStandard;
+    use crate::queryengine::parser::Entity;
+    use crate::storage::v1::sengine::SnapshotEngine;
+    use std::{fs, sync::Arc};
+
+    #[test]
+    fn test_stays_volatile_if_flush_fails() {
+        let store = Memstore::new_default();
+        let ksid = unsafe { ObjectID::from_slice("altervolatileks") };
+        store.create_keyspace(ksid.clone());
+        let ks = store.get_keyspace_atomic_ref(&ksid).unwrap();
+        ks.create_table(
+            unsafe { ObjectID::from_slice("mytbl") },
+            Table::from_kve(KVEStandard::default(), true),
+        );
+        let tbl = ks.get_table_atomic_ref("mytbl".as_bytes()).unwrap();
+        let store = Corestore::default_with_store(store, Arc::new(SnapshotEngine::new_disabled()));
+        // a file in place of the keyspace's directory makes the flush fail
+        fs::create_dir_all("data/ks").unwrap();
+        fs::write("data/ks/altervolatileks", b"").unwrap();
+        let ret = store.alter_table_persistence(
+            Entity::Full("altervolatileks".as_bytes(), "mytbl".as_bytes()),
+            false,
+        );
+        fs::remove_file("data/ks/altervolatileks").unwrap();
+        assert_eq!(ret.unwrap_err(), DdlError::DdlTransactionFailure);
+        assert!(tbl.is_volatile());
+    }
+}
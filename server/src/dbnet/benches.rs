@@ -0,0 +1,182 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+/*
+ These benches compare writing the responses of a pipeline through the connection's `BufWriter`
+ (what the server does) against serializing them into a `Vec<u8>` first and writing that out
+ with a single `write_all`. Both also count the writes that reach the underlying stream, which
+ would be syscalls on a socket
+*/
+
+extern crate test;
+use super::connection::{ProtocolConnection, ProtocolConnectionExt, Written};
+use crate::corestore::{buffers::Integer64, Data};
+use crate::resp::writer;
+use bytes::BytesMut;
+use std::{
+    io::Result as IoResult,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use test::Bencher;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufWriter, ReadBuf},
+    runtime::{Builder, Runtime},
+};
+
+const PIPELINE_LEN: usize = 1000;
+
+/// A stream that drops everything written to it, but counts the writes
+#[derive(Default)]
+struct CountingStream {
+    writes: usize,
+}
+
+impl AsyncRead for CountingStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for CountingStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        self.writes += 1;
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+struct BenchConnection {
+    buffer: BytesMut,
+    stream: BufWriter<CountingStream>,
+    written: Written,
+}
+
+impl BenchConnection {
+    fn new() -> Self {
+        Self {
+            buffer: BytesMut::new(),
+            stream: BufWriter::new(CountingStream::default()),
+            written: Written::Nothing,
+        }
+    }
+    fn writes(&self) -> usize {
+        self.stream.get_ref().writes
+    }
+}
+
+impl ProtocolConnection<CountingStream> for BenchConnection {
+    fn get_buffer(&self) -> &BytesMut {
+        &self.buffer
+    }
+    fn get_stream(&self) -> &BufWriter<CountingStream> {
+        &self.stream
+    }
+    fn get_mut_buffer(&mut self) -> &mut BytesMut {
+        &mut self.buffer
+    }
+    fn get_mut_stream(&mut self) -> &mut BufWriter<CountingStream> {
+        &mut self.stream
+    }
+    fn get_mut_both(&mut self) -> (&mut BytesMut, &mut BufWriter<CountingStream>) {
+        (&mut self.buffer, &mut self.stream)
+    }
+    fn get_written(&self) -> Written {
+        self.written
+    }
+    fn get_mut_written(&mut self) -> &mut Written {
+        &mut self.written
+    }
+}
+
+fn runtime() -> Runtime {
+    Builder::new_current_thread().build().unwrap()
+}
+
+/// The current path: every response of the pipeline is written to the connection like `GET`
+/// does, and the stream is flushed once at the end
+async fn write_pipeline_buffered(con: &mut BenchConnection, value: &Data) {
+    con.write_pipeline_query_header(PIPELINE_LEN).await.unwrap();
+    for _ in 0..PIPELINE_LEN {
+        unsafe {
+            // SAFETY: the pipeline header was written above and every value is one whole element
+            // of the pipeline
+            writer::write_raw_mono(con, b'+', value).await.unwrap();
+        }
+    }
+    con.flush_stream().await.unwrap();
+}
+
+/// The batched path: the same responses are serialized into a `Vec<u8>` that is written out
+/// with a single `write_all`
+async fn write_pipeline_batched(con: &mut BenchConnection, value: &Data) {
+    let mut batch = Vec::new();
+    batch.push(b'$');
+    batch.extend_from_slice(&Integer64::from(PIPELINE_LEN));
+    batch.push(b'\n');
+    for _ in 0..PIPELINE_LEN {
+        batch.push(b'+');
+        batch.extend_from_slice(&Integer64::from(value.len()));
+        batch.push(b'\n');
+        batch.extend_from_slice(value);
+    }
+    con.get_mut_stream().write_all(&batch).await.unwrap();
+    con.flush_stream().await.unwrap();
+}
+
+#[bench]
+fn bench_pipeline_1000_responses_buffered(b: &mut Bencher) {
+    let (rt, value) = (runtime(), Data::from("100"));
+    b.iter(|| {
+        let mut con = BenchConnection::new();
+        rt.block_on(write_pipeline_buffered(&mut con, &value));
+        // ~6KB of responses fit in the BufWriter, so they reach the stream in one write
+        assert_eq!(con.writes(), 1);
+    })
+}
+
+#[bench]
+fn bench_pipeline_1000_responses_batched(b: &mut Bencher) {
+    let (rt, value) = (runtime(), Data::from("100"));
+    b.iter(|| {
+        let mut con = BenchConnection::new();
+        rt.block_on(write_pipeline_batched(&mut con, &value));
+        assert_eq!(con.writes(), 1);
+    })
+}
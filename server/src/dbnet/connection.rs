@@ -44,37 +44,77 @@ use crate::{
         tcp::{BufferedSocketStream, Connection},
         Terminator,
     },
-    protocol::{self, responses, ParseError, Query},
-    queryengine,
+    protocol::{self, responses, ParseError, ParseFailure, Query},
+    queryengine, registry,
     resp::Writable,
     IoResult,
 };
-use bytes::{Buf, BytesMut};
+use bytes::{Buf, BufMut, BytesMut};
+use libsky::BUF_CAP;
 use std::{
-    future::Future,
+    future::{self, Future},
     io::{Error as IoError, ErrorKind},
     marker::PhantomData,
+    net::SocketAddr,
     pin::Pin,
     sync::Arc,
+    time::Duration,
 };
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt, BufWriter},
     sync::{mpsc, Semaphore},
+    time,
 };
 
 pub const SIMPLE_QUERY_HEADER: [u8; 1] = [b'*'];
+
+/// What the first response element written since the last
+/// [`ProtocolConnection::reset_written`] was
+///
+/// Actions report many failures by writing the error response code themselves and returning
+/// `Ok(())`, so this is what tells a pipeline stage that failed apart from one that didn't
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Written {
+    /// nothing has been written yet
+    Nothing,
+    /// a response that isn't an error response code
+    Response,
+    /// an error response code
+    Error,
+}
 type QueryWithAdvance = (Query, usize);
 
 pub enum QueryResult {
     Q(QueryWithAdvance),
     E(&'static [u8]),
     Wrongtype,
+    /// the packet was rejected at the given offset; only returned if verbose protocol errors
+    /// are enabled
+    EAt(&'static str, usize),
+    /// the query declared more elements than allowed or outgrew the maximum query size;
+    /// since the rest of the stream can't be parsed, the connection has to be closed
+    TooLarge,
     Disconnected,
 }
 
+impl QueryResult {
+    /// Returns the result for a packet that the parser rejected
+    fn rejected(failure: ParseFailure) -> Self {
+        let wrongtype = failure.error == ParseError::DatatypeParseFailure;
+        match (registry::verbose_protocol_errors(), wrongtype) {
+            (true, true) => Self::EAt("wrongtype", failure.offset),
+            (true, false) => Self::EAt("bad-packet", failure.offset),
+            (false, true) => Self::Wrongtype,
+            (false, false) => Self::E(responses::full_responses::R_PACKET_ERR),
+        }
+    }
+}
+
 pub struct AuthProviderHandle<'a, T, Strm> {
     provider: &'a mut AuthProvider,
     executor: &'a mut ExecutorFn<T, Strm>,
+    /// the address of the client
+    peer: SocketAddr,
     _phantom: PhantomData<(T, Strm)>,
 }
 
@@ -83,10 +123,15 @@ where
     T: ClientConnection<Strm>,
     Strm: Stream,
 {
-    pub fn new(provider: &'a mut AuthProvider, executor: &'a mut ExecutorFn<T, Strm>) -> Self {
+    pub fn new(
+        provider: &'a mut AuthProvider,
+        executor: &'a mut ExecutorFn<T, Strm>,
+        peer: SocketAddr,
+    ) -> Self {
         Self {
             provider,
             executor,
+            peer,
             _phantom: PhantomData,
         }
     }
@@ -96,6 +141,9 @@ where
     pub fn provider(&self) -> &AuthProvider {
         self.provider
     }
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
     pub fn swap_executor_to_anonymous(&mut self) {
         *self.executor = ConnectionHandler::execute_unauth;
     }
@@ -109,7 +157,10 @@ pub mod prelude {
     //!
     //! This module is hollow itself, it only re-exports from `dbnet::con` and `tokio::io`
     pub use super::{AuthProviderHandle, ClientConnection, ProtocolConnectionExt, Stream};
-    pub use crate::actions::{ensure_boolean_or_aerr, ensure_cond_or_err, ensure_length};
+    pub use crate::actions::{
+        command_docs::{ArgumentDoc, ArgumentType, CommandDoc, CommandGroup, KeySpec, MemoryUse},
+        ensure_boolean_or_aerr, ensure_cond_or_err, ensure_length, invalid_json_err,
+    };
     pub use crate::corestore::{
         table::{KVEBlob, KVEList},
         Corestore,
@@ -137,15 +188,29 @@ where
     Strm: AsyncReadExt + AsyncWriteExt + Unpin + Send + Sync,
 {
     /// Try to parse a query from the buffered data
-    fn try_query(&self) -> Result<QueryWithAdvance, ParseError> {
-        protocol::Parser::parse(self.get_buffer())
+    fn try_query(&self) -> Result<QueryWithAdvance, ParseFailure> {
+        protocol::Parser::parse_with_limits(self.get_buffer(), registry::max_pipeline_length())
+    }
+    /// Skip any keepalive frames (`\n` bytes sent by some connection pools to probe the
+    /// connection) at the start of the buffer
+    fn skip_keepalives(&mut self) {
+        let keepalives = self
+            .get_buffer()
+            .iter()
+            .take_while(|byte| **byte == b'\n')
+            .count();
+        self.advance_buffer(keepalives);
     }
     /// Read a query from the remote end
     ///
     /// This function asynchronously waits until all the data required
-    /// for parsing the query is available
+    /// for parsing the query is available. Queries that are already buffered are
+    /// returned without reading from the stream, and keepalive frames are skipped.
+    /// The buffer is never allowed to grow beyond `max_query_size` bytes; if the query
+    /// still isn't complete by then, [`QueryResult::TooLarge`] is returned
     fn read_query<'r, 's>(
         &'r mut self,
+        max_query_size: usize,
     ) -> Pin<Box<dyn Future<Output = Result<QueryResult, IoError>> + Send + 's>>
     where
         'r: 's,
@@ -154,8 +219,38 @@ where
         Box::pin(async move {
             let mv_self = self;
             loop {
+                mv_self.skip_keepalives();
+                if !mv_self.get_buffer().is_empty() {
+                    match mv_self.try_query() {
+                        Ok(query_with_advance) => {
+                            return Ok(QueryResult::Q(query_with_advance));
+                        }
+                        Err(ParseFailure {
+                            error: ParseError::NotEnough,
+                            ..
+                        }) => (),
+                        Err(ParseFailure {
+                            error: ParseError::TooLarge,
+                            ..
+                        }) => return Ok(QueryResult::TooLarge),
+                        Err(failure) => {
+                            log::debug!(
+                                "Rejected packet with {:?} at offset {} near `{}`",
+                                failure.error,
+                                failure.offset,
+                                failure.context(mv_self.get_buffer())
+                            );
+                            return Ok(QueryResult::rejected(failure));
+                        }
+                    }
+                }
                 let (buffer, stream) = mv_self.get_mut_both();
-                match stream.read_buf(buffer).await {
+                if buffer.len() >= max_query_size {
+                    return Ok(QueryResult::TooLarge);
+                }
+                let remaining = max_query_size - buffer.len();
+                let mut limited = (&mut *buffer).limit(remaining);
+                match stream.read_buf(&mut limited).await {
                     Ok(0) => {
                         if buffer.is_empty() {
                             return Ok(QueryResult::Disconnected);
@@ -166,16 +261,6 @@ where
                     Ok(_) => {}
                     Err(e) => return Err(e),
                 }
-                match mv_self.try_query() {
-                    Ok(query_with_advance) => {
-                        return Ok(QueryResult::Q(query_with_advance));
-                    }
-                    Err(ParseError::NotEnough) => (),
-                    Err(ParseError::DatatypeParseFailure) => return Ok(QueryResult::Wrongtype),
-                    Err(ParseError::UnexpectedByte) | Err(ParseError::BadPacket) => {
-                        return Ok(QueryResult::E(responses::full_responses::R_PACKET_ERR));
-                    }
-                }
             }
         })
     }
@@ -193,6 +278,7 @@ where
             let mv_self = self;
             let streamer = streamer;
             let ret: IoResult<()> = {
+                mv_self.note_written(streamer.is_error());
                 streamer.write(&mut mv_self.get_mut_stream()).await?;
                 Ok(())
             };
@@ -303,7 +389,10 @@ where
             ret
         })
     }
+    /// Returns the underlying stream for the response writers (which never write error
+    /// response codes)
     unsafe fn raw_stream(&mut self) -> &mut BufWriter<Strm> {
+        self.note_written(false);
         self.get_mut_stream()
     }
 }
@@ -338,6 +427,26 @@ pub trait ProtocolConnection<Strm> {
     ///
     /// This is to avoid double mutable reference errors
     fn get_mut_both(&mut self) -> (&mut BytesMut, &mut BufWriter<Strm>);
+    /// Returns what has been written since the last reset
+    fn get_written(&self) -> Written;
+    /// Returns a **mutable** reference to what has been written since the last reset
+    fn get_mut_written(&mut self) -> &mut Written;
+    /// Forget about everything that has been written so far
+    fn reset_written(&mut self) {
+        *self.get_mut_written() = Written::Nothing;
+    }
+    /// Record that a response element was written. Only the first element after a reset
+    /// is kept, since that's the one that tells what kind of response it is
+    fn note_written(&mut self, is_error: bool) {
+        let written = self.get_mut_written();
+        if *written == Written::Nothing {
+            *written = if is_error {
+                Written::Error
+            } else {
+                Written::Response
+            };
+        }
+    }
     /// Advance the read buffer by `forward_by` positions
     fn advance_buffer(&mut self, forward_by: usize) {
         self.get_mut_buffer().advance(forward_by)
@@ -346,6 +455,30 @@ pub trait ProtocolConnection<Strm> {
     fn clear_buffer(&mut self) {
         self.get_mut_buffer().clear()
     }
+    /// Replace the read buffer with a fresh one of `BUF_CAP` bytes (keeping any unread data)
+    /// if its capacity has grown beyond `threshold` and only a little unread data is left.
+    /// Returns true if the buffer was replaced
+    ///
+    /// A single large query would otherwise pin its allocation for as long as the
+    /// connection stays open. Note that the capacity doesn't count the bytes that have
+    /// already been advanced past, so right after a large query this might not kick in;
+    /// it will once a later read makes the buffer reclaim the space at the front
+    ///
+    /// ## Safety
+    ///
+    /// The old buffer is deallocated, so no [`UnsafeSlice`](crate::protocol::UnsafeSlice)
+    /// (or anything else pointing into the buffer, like a [`Query`]) can be alive when
+    /// this is called
+    unsafe fn shrink_buffer_if_needed(&mut self, threshold: usize) -> bool {
+        let buffer = self.get_mut_buffer();
+        if buffer.capacity() <= threshold || buffer.len() > BUF_CAP {
+            return false;
+        }
+        let mut shrunk = BytesMut::with_capacity(BUF_CAP);
+        shrunk.extend_from_slice(buffer);
+        *buffer = shrunk;
+        true
+    }
 }
 
 // Give ProtocolConnection implementors a free ProtocolConnectionExt impl
@@ -376,6 +509,12 @@ where
     fn get_mut_both(&mut self) -> (&mut BytesMut, &mut BufWriter<T>) {
         (&mut self.buffer, &mut self.stream)
     }
+    fn get_written(&self) -> Written {
+        self.written
+    }
+    fn get_mut_written(&mut self) -> &mut Written {
+        &mut self.written
+    }
 }
 
 pub(super) type ExecutorFn<T, Strm> =
@@ -390,8 +529,14 @@ pub struct ConnectionHandler<T, Strm> {
     db: Corestore,
     con: T,
     climit: Arc<Semaphore>,
+    /// the maximum size of a query in bytes
+    max_query_size: usize,
+    /// the time after which the connection is closed if no query arrives
+    idle_timeout: Option<Duration>,
     auth: AuthProvider,
     executor: ExecutorFn<T, Strm>,
+    /// the address of the client
+    peer: SocketAddr,
     terminator: Terminator,
     _term_sig_tx: mpsc::Sender<()>,
     _marker: PhantomData<Strm>,
@@ -402,21 +547,29 @@ where
     T: ProtocolConnectionExt<Strm> + Send + Sync,
     Strm: Sync + Send + Unpin + AsyncWriteExt + AsyncReadExt,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: Corestore,
         con: T,
         auth: AuthProvider,
         executor: ExecutorFn<T, Strm>,
+        peer: SocketAddr,
         climit: Arc<Semaphore>,
+        max_query_size: usize,
+        idle_timeout: Option<Duration>,
         terminator: Terminator,
         _term_sig_tx: mpsc::Sender<()>,
     ) -> Self {
+        registry::connection_opened();
         Self {
             db,
             con,
             auth,
             climit,
+            max_query_size,
+            idle_timeout,
             executor,
+            peer,
             terminator,
             _term_sig_tx,
             _marker: PhantomData,
@@ -424,11 +577,25 @@ where
     }
     pub async fn run(&mut self) -> IoResult<()> {
         while !self.terminator.is_termination_signal() {
+            // the timer is restarted for every query, and runs while a query is only
+            // partially received so that half-open connections don't hold on to buffers
+            let idle_timeout = self.idle_timeout;
+            let idle = async move {
+                match idle_timeout {
+                    Some(duration) => time::sleep(duration).await,
+                    None => future::pending().await,
+                }
+            };
             let try_df = tokio::select! {
-                tdf = self.con.read_query() => tdf,
+                tdf = self.con.read_query(self.max_query_size) => tdf,
                 _ = self.terminator.receive_signal() => {
                     return Ok(());
                 }
+                _ = idle => {
+                    // the client has gone silent, so close the connection
+                    self.con.get_mut_stream().flush().await?;
+                    return Ok(());
+                }
             };
             match try_df {
                 Ok(QueryResult::Q((query, advance_by))) => {
@@ -439,6 +606,9 @@ where
                         Err(ActionError::ActionError(e)) => {
                             self.con.close_conn_with_error(e).await?;
                         }
+                        Err(ActionError::DynamicError(e)) => {
+                            self.con.close_conn_with_error(e).await?;
+                        }
                         Err(ActionError::IoError(e)) => {
                             return Err(e);
                         }
@@ -446,12 +616,35 @@ where
                     // this is only when we clear the buffer. since execute_query is not called
                     // at this point, it's totally fine (so invalidating ptrs is totally cool)
                     self.con.advance_buffer(advance_by);
+                    unsafe {
+                        // SAFETY: the query was moved into execute_query and has been dropped by now, so
+                        // nothing points into the buffer anymore
+                        self.con
+                            .shrink_buffer_if_needed(registry::buffer_shrink_threshold());
+                    }
+                }
+                // the rest of the buffer can't be parsed, so the connection has to be closed
+                Ok(QueryResult::E(r)) => {
+                    self.con.close_conn_with_error(r).await?;
+                    return Ok(());
                 }
-                Ok(QueryResult::E(r)) => self.con.close_conn_with_error(r).await?,
                 Ok(QueryResult::Wrongtype) => {
                     self.con
                         .close_conn_with_error(responses::groups::WRONGTYPE_ERR.to_owned())
-                        .await?
+                        .await?;
+                    return Ok(());
+                }
+                Ok(QueryResult::EAt(error, offset)) => {
+                    let error = format!("{error}@{offset}");
+                    let resp = format!("*!{}\n{}\n", error.len(), error).into_bytes();
+                    self.con.close_conn_with_error(resp).await?;
+                    return Ok(());
+                }
+                Ok(QueryResult::TooLarge) => {
+                    self.con
+                        .close_conn_with_error(responses::full_responses::R_QUERY_TOO_LARGE)
+                        .await?;
+                    return Ok(());
                 }
                 Ok(QueryResult::Disconnected) => return Ok(()),
                 #[cfg(windows)]
@@ -471,7 +664,8 @@ where
         Box::pin(async move {
             let con = &mut self.con;
             let db = &mut self.db;
-            let mut auth_provider = AuthProviderHandle::new(&mut self.auth, &mut self.executor);
+            let mut auth_provider =
+                AuthProviderHandle::new(&mut self.auth, &mut self.executor, self.peer);
             match query {
                 Query::Simple(sq) => {
                     con.write_simple_query_header().await?;
@@ -492,7 +686,8 @@ where
         Box::pin(async move {
             let con = &mut self.con;
             let db = &mut self.db;
-            let mut auth_provider = AuthProviderHandle::new(&mut self.auth, &mut self.executor);
+            let mut auth_provider =
+                AuthProviderHandle::new(&mut self.auth, &mut self.executor, self.peer);
             match query {
                 Query::Simple(q) => {
                     con.write_simple_query_header().await?;
@@ -509,6 +704,10 @@ where
 
     /// Execute a query that has already been validated by `Connection::read_query`
     async fn execute_query(&mut self, query: Query) -> ActionResult<()> {
+        registry::queries_executed(match query {
+            Query::Simple(_) => 1,
+            Query::Pipelined(ref pipeline) => pipeline.len(),
+        });
         (self.executor)(self, query).await?;
         self.con.flush_stream().await?;
         Ok(())
@@ -520,6 +719,7 @@ impl<T, Strm> Drop for ConnectionHandler<T, Strm> {
         // Make sure that the permit is returned to the semaphore
         // in the case that there is a panic inside
         self.climit.add_permits(1);
+        registry::connection_closed();
     }
 }
 
@@ -40,26 +40,38 @@
 //!
 
 use self::tcp::Listener;
+#[cfg(unix)]
+use self::unix::UnixSockListener;
 use crate::{
     auth::AuthProvider,
-    config::{PortConfig, SslOpts},
+    config::{PortConfig, SslOpts, UnixSockOpts},
     corestore::Corestore,
     util::error::{Error, SkyResult},
     IoResult,
 };
-use std::{net::IpAddr, sync::Arc};
+use std::{net::IpAddr, sync::Arc, time::Duration};
 use tls::SslListener;
 use tokio::{
     net::TcpListener,
     sync::{broadcast, mpsc, Semaphore},
 };
+#[cfg(feature = "nightly")]
+mod benches;
 pub mod connection;
 #[macro_use]
 mod macros;
 mod tcp;
+#[cfg(test)]
+mod tests;
 mod tls;
+#[cfg(unix)]
+mod unix;
 
 pub const MAXIMUM_CONNECTION_LIMIT: usize = 50000;
+/// The default maximum size of a query in bytes (64 MiB)
+pub const DEFAULT_MAX_QUERY_SIZE: usize = 64 * 1024 * 1024;
+/// The default capacity (in bytes) above which an idle read buffer is shrunk (1 MiB)
+pub const DEFAULT_BUFFER_SHRINK_THRESHOLD: usize = 1024 * 1024;
 
 /// Responsible for gracefully shutting down the server instead of dying randomly
 // Sounds very sci-fi ;)
@@ -93,16 +105,20 @@ impl Terminator {
     }
 }
 
-/// The base TCP listener
-pub struct BaseListener {
+/// The base listener (a TCP listener, unless otherwise specified)
+pub struct BaseListener<L = TcpListener> {
     /// An atomic reference to the coretable
     pub db: Corestore,
     /// The auth provider
     pub auth: AuthProvider,
     /// The incoming connection listener (binding)
-    pub listener: TcpListener,
+    pub listener: L,
     /// The maximum number of connections
     pub climit: Arc<Semaphore>,
+    /// The maximum size of a query in bytes
+    pub max_query_size: usize,
+    /// The time after which an idle client is disconnected, if any
+    pub idle_timeout: Option<Duration>,
     /// The shutdown broadcaster
     pub signal: broadcast::Sender<()>,
     // When all `Sender`s are dropped - the `Receiver` gets a `None` value
@@ -112,27 +128,54 @@ pub struct BaseListener {
 }
 
 impl BaseListener {
+    #[allow(clippy::too_many_arguments)]
     pub async fn init(
         db: &Corestore,
         auth: AuthProvider,
         host: IpAddr,
         port: u16,
         semaphore: Arc<Semaphore>,
+        max_query_size: usize,
+        idle_timeout: Option<Duration>,
         signal: broadcast::Sender<()>,
     ) -> SkyResult<Self> {
-        let (terminate_tx, terminate_rx) = mpsc::channel(1);
         let listener = TcpListener::bind((host, port))
             .await
             .map_err(|e| Error::ioerror_extra(e, format!("binding to port {port}")))?;
-        Ok(Self {
+        Ok(Self::with_listener(
+            db,
+            auth,
+            listener,
+            semaphore,
+            max_query_size,
+            idle_timeout,
+            signal,
+        ))
+    }
+}
+
+impl<L> BaseListener<L> {
+    pub fn with_listener(
+        db: &Corestore,
+        auth: AuthProvider,
+        listener: L,
+        semaphore: Arc<Semaphore>,
+        max_query_size: usize,
+        idle_timeout: Option<Duration>,
+        signal: broadcast::Sender<()>,
+    ) -> Self {
+        let (terminate_tx, terminate_rx) = mpsc::channel(1);
+        Self {
             db: db.clone(),
             auth,
             listener,
             climit: semaphore,
+            max_query_size,
+            idle_timeout,
             signal,
             terminate_tx,
             terminate_rx,
-        })
+        }
     }
     pub async fn release_self(self) {
         let Self {
@@ -157,11 +200,14 @@ impl BaseListener {
 /// - The `Multi` variant holds both an `SslListener` and a `Listener`
 ///     This variant enables listening to both secure and insecure sockets at the same time
 ///     asynchronously
+/// - The `WithUnix` variant holds any of the above along with a `UnixSockListener`
 #[allow(clippy::large_enum_variant)]
 pub enum MultiListener {
     SecureOnly(SslListener),
     InsecureOnly(Listener),
     Multi(Listener, SslListener),
+    #[cfg(unix)]
+    WithUnix(Box<MultiListener>, UnixSockListener),
 }
 
 impl MultiListener {
@@ -171,8 +217,7 @@ impl MultiListener {
     }
     /// Create a new `SecureOnly` listener
     pub fn new_secure_only(base: BaseListener, ssl: SslOpts) -> SkyResult<Self> {
-        let listener =
-            SslListener::new_pem_based_ssl_connection(ssl.key, ssl.chain, base, ssl.passfile)?;
+        let listener = SslListener::new_pem_based_ssl_connection(ssl, base)?;
         Ok(MultiListener::SecureOnly(listener))
     }
     /// Create a new `Multi` listener that has both a secure and an insecure listener
@@ -181,15 +226,15 @@ impl MultiListener {
         tcp_base_listener: BaseListener,
         ssl: SslOpts,
     ) -> SkyResult<Self> {
-        let secure_listener = SslListener::new_pem_based_ssl_connection(
-            ssl.key,
-            ssl.chain,
-            ssl_base_listener,
-            ssl.passfile,
-        )?;
+        let secure_listener = SslListener::new_pem_based_ssl_connection(ssl, ssl_base_listener)?;
         let insecure_listener = Listener::new(tcp_base_listener);
         Ok(MultiListener::Multi(insecure_listener, secure_listener))
     }
+    /// Have the given listener(s) also listen on a unix socket
+    #[cfg(unix)]
+    pub fn with_unix(self, unix_listener: UnixSockListener) -> Self {
+        MultiListener::WithUnix(Box::new(self), unix_listener)
+    }
     /// Start the server
     ///
     /// The running of single and/or parallel listeners is handled by this function by
@@ -210,6 +255,17 @@ impl MultiListener {
                 }
                 Ok(())
             }
+            #[cfg(unix)]
+            MultiListener::WithUnix(listener, unix_listener) => {
+                let (e1, e2) = tokio::join!(Box::pin(listener.run_server()), unix_listener.run());
+                if let Err(e) = e1 {
+                    log::error!("Listener failed with: {}", e);
+                }
+                if let Err(e) = e2 {
+                    log::error!("Unix socket listener failed with: {}", e);
+                }
+                Ok(())
+            }
         }
     }
     /// Signal the ports to shut down and only return after they have shut down
@@ -224,19 +280,33 @@ impl MultiListener {
                 insecure.base.release_self().await;
                 secure.base.release_self().await;
             }
+            #[cfg(unix)]
+            MultiListener::WithUnix(listener, unix_listener) => {
+                Box::pin(listener.finish_with_termsig()).await;
+                unix_listener.release_self().await;
+            }
         }
     }
 }
 
 /// Initialize the database networking
+#[allow(clippy::too_many_arguments)]
 pub async fn connect(
     ports: PortConfig,
+    unixsock: Option<UnixSockOpts>,
     maxcon: usize,
+    max_query_size: usize,
+    client_idle_timeout_secs: u64,
     db: Corestore,
     auth: AuthProvider,
     signal: broadcast::Sender<()>,
 ) -> SkyResult<MultiListener> {
     let climit = Arc::new(Semaphore::new(maxcon));
+    let idle_timeout = if client_idle_timeout_secs == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(client_idle_timeout_secs))
+    };
     let base_listener_init = |host, port| {
         BaseListener::init(
             &db,
@@ -244,6 +314,8 @@ pub async fn connect(
             host,
             port,
             climit.clone(),
+            max_query_size,
+            idle_timeout,
             signal.clone(),
         )
     };
@@ -262,5 +334,26 @@ pub async fn connect(
         }
     };
     log::info!("Server started on {}", description);
+    #[cfg(unix)]
+    let server = match unixsock {
+        Some(opts) => {
+            let listener = unix::bind(&opts)?;
+            let base = BaseListener::with_listener(
+                &db,
+                auth,
+                listener,
+                climit,
+                max_query_size,
+                idle_timeout,
+                signal,
+            );
+            log::info!("Listening on unix socket {}", opts.path);
+            server.with_unix(UnixSockListener::new(base, opts.path))
+        }
+        None => server,
+    };
+    #[cfg(not(unix))]
+    // the config refuses to set this on platforms without unix sockets
+    let _ = unixsock;
     Ok(server)
 }
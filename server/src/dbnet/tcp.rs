@@ -26,7 +26,7 @@
 
 use crate::{
     dbnet::{
-        connection::{ConnectionHandler, ExecutorFn},
+        connection::{ConnectionHandler, ExecutorFn, Written},
         BaseListener, Terminator,
     },
     protocol, IoResult,
@@ -34,7 +34,7 @@ use crate::{
 use bytes::BytesMut;
 use libsky::BUF_CAP;
 pub use protocol::{ParseResult, Query};
-use std::{cell::Cell, time::Duration};
+use std::{cell::Cell, net::SocketAddr, time::Duration};
 use tokio::{
     io::{AsyncWrite, BufWriter},
     net::TcpStream,
@@ -57,6 +57,8 @@ where
     pub stream: BufWriter<T>,
     /// The in-memory read buffer. The size is given by `BUF_CAP`
     pub buffer: BytesMut,
+    /// What has been written for the current query (or pipeline stage)
+    pub written: Written,
 }
 
 impl<T> Connection<T>
@@ -68,6 +70,7 @@ where
         Connection {
             stream: BufWriter::new(stream),
             buffer: BytesMut::with_capacity(BUF_CAP),
+            written: Written::Nothing,
         }
     }
 }
@@ -111,13 +114,12 @@ impl Listener {
             base,
         }
     }
-    /// Accept an incoming connection
-    async fn accept(&mut self) -> IoResult<TcpStream> {
+    /// Accept an incoming connection, returning the stream and the address of the client
+    async fn accept(&mut self) -> IoResult<(TcpStream, SocketAddr)> {
         let backoff = TcpBackoff::new();
         loop {
             match self.base.listener.accept().await {
-                // We don't need the bindaddr
-                Ok((stream, _)) => return Ok(stream),
+                Ok(accepted) => return Ok(accepted),
                 Err(e) => {
                     if backoff.should_disconnect() {
                         // Too many retries, goodbye user
@@ -143,13 +145,16 @@ impl Listener {
              can arise and it will flood the log and might also result
              in a crash
             */
-            let stream = skip_loop_err!(self.accept().await);
+            let (stream, peer) = skip_loop_err!(self.accept().await);
             let mut chandle = ConnectionHandler::new(
                 self.base.db.clone(),
                 Connection::new(stream),
                 self.base.auth.clone(),
                 self.executor_fn,
+                peer,
                 self.base.climit.clone(),
+                self.base.max_query_size,
+                self.base.idle_timeout,
                 Terminator::new(self.base.signal.subscribe()),
                 self.base.terminate_tx.clone(),
             );
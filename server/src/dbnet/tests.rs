@@ -0,0 +1,300 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use super::connection::{ProtocolConnection, ProtocolConnectionExt, QueryResult, Written};
+use crate::config::TlsVersion;
+use crate::corestore::Data;
+use crate::protocol::{responses::groups, Query};
+use crate::resp::writer;
+use bytes::BytesMut;
+use std::{
+    io::{Cursor, Result as IoResult},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, BufWriter, ReadBuf};
+
+/// A connection that reads from an in-memory buffer
+struct MockConnection<S = Cursor<Vec<u8>>> {
+    buffer: BytesMut,
+    stream: BufWriter<S>,
+    written: Written,
+}
+
+impl MockConnection {
+    fn new(data: Vec<u8>) -> Self {
+        Self {
+            buffer: BytesMut::with_capacity(1024),
+            stream: BufWriter::new(Cursor::new(data)),
+            written: Written::Nothing,
+        }
+    }
+}
+
+impl<S> ProtocolConnection<S> for MockConnection<S> {
+    fn get_buffer(&self) -> &BytesMut {
+        &self.buffer
+    }
+    fn get_stream(&self) -> &BufWriter<S> {
+        &self.stream
+    }
+    fn get_mut_buffer(&mut self) -> &mut BytesMut {
+        &mut self.buffer
+    }
+    fn get_mut_stream(&mut self) -> &mut BufWriter<S> {
+        &mut self.stream
+    }
+    fn get_mut_both(&mut self) -> (&mut BytesMut, &mut BufWriter<S>) {
+        (&mut self.buffer, &mut self.stream)
+    }
+    fn get_written(&self) -> Written {
+        self.written
+    }
+    fn get_mut_written(&mut self) -> &mut Written {
+        &mut self.written
+    }
+}
+
+/// A stream that keeps everything written to it, along with the size of every write
+#[derive(Default)]
+struct RecordingStream {
+    data: Vec<u8>,
+    writes: Vec<usize>,
+}
+
+impl AsyncRead for RecordingStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for RecordingStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        self.data.extend_from_slice(buf);
+        self.writes.push(buf.len());
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+const MAX_QUERY_SIZE: usize = 64 * 1024;
+
+#[tokio::test]
+async fn read_query_okay() {
+    let mut con = MockConnection::new(b"*2\n3\nGET1\nx".to_vec());
+    let ret = con.read_query(MAX_QUERY_SIZE).await.unwrap();
+    assert!(matches!(ret, QueryResult::Q((_, 11))));
+}
+
+#[tokio::test]
+async fn read_query_fail_because_too_large() {
+    let payload_len = 1024 * 1024;
+    let mut data = format!("*1\n{payload_len}\n").into_bytes();
+    data.resize(data.len() + payload_len, b'a');
+    let mut con = MockConnection::new(data);
+    let ret = con.read_query(MAX_QUERY_SIZE).await.unwrap();
+    assert!(matches!(ret, QueryResult::TooLarge));
+    // the buffer should never grow beyond the limit
+    assert!(con.get_buffer().len() <= MAX_QUERY_SIZE);
+}
+
+fn simple_query_data(ret: QueryResult) -> Vec<Vec<u8>> {
+    match ret {
+        QueryResult::Q((Query::Simple(sq), _)) => sq
+            .as_slice()
+            .iter()
+            .map(|v| unsafe { v.as_slice().to_owned() })
+            .collect(),
+        _ => panic!("Expected a simple query"),
+    }
+}
+
+#[tokio::test]
+async fn read_query_skip_keepalives() {
+    let mut con = MockConnection::new(b"\n*2\n3\nGET1\nx\n\n*2\n3\nGET1\ny\n".to_vec());
+    let ret = con.read_query(MAX_QUERY_SIZE).await.unwrap();
+    assert!(matches!(ret, QueryResult::Q((_, 11))));
+    assert_eq!(simple_query_data(ret), vec![b"GET".to_vec(), b"x".to_vec()]);
+    con.advance_buffer(11);
+    // the next query is already buffered, so it shouldn't need another read
+    let ret = con.read_query(MAX_QUERY_SIZE).await.unwrap();
+    assert!(matches!(ret, QueryResult::Q((_, 11))));
+    assert_eq!(simple_query_data(ret), vec![b"GET".to_vec(), b"y".to_vec()]);
+    con.advance_buffer(11);
+    // only keepalives are left, so this is a clean disconnect
+    let ret = con.read_query(MAX_QUERY_SIZE).await.unwrap();
+    assert!(matches!(ret, QueryResult::Disconnected));
+}
+
+#[tokio::test]
+async fn read_buffer_shrinks_after_large_query() {
+    const THRESHOLD: usize = 64 * 1024;
+    let payload_len = 256 * 1024;
+    let mut data = format!("*1\n{payload_len}\n").into_bytes();
+    data.resize(data.len() + payload_len, b'a');
+    for _ in 0..3 {
+        data.extend(b"*2\n3\nGET1\nx");
+    }
+    let mut con = MockConnection::new(data);
+    let ret = con.read_query(MAX_QUERY_SIZE * 8).await.unwrap();
+    let advance_by = match ret {
+        QueryResult::Q((_, advance_by)) => advance_by,
+        _ => panic!("Expected a query"),
+    };
+    drop(ret);
+    assert!(con.get_buffer().capacity() > THRESHOLD);
+    con.advance_buffer(advance_by);
+    unsafe {
+        // SAFETY: the large query was dropped just above and nothing else points into the buffer
+        con.shrink_buffer_if_needed(THRESHOLD);
+    }
+    assert!(con.get_buffer().capacity() <= THRESHOLD);
+    // the small queries that were already buffered must survive the shrink
+    for _ in 0..3 {
+        let ret = con.read_query(MAX_QUERY_SIZE).await.unwrap();
+        assert!(matches!(ret, QueryResult::Q((_, 11))));
+        assert_eq!(simple_query_data(ret), vec![b"GET".to_vec(), b"x".to_vec()]);
+        con.advance_buffer(11);
+        unsafe {
+            // SAFETY: `ret` is consumed by `simple_query_data` above, so nothing borrows the buffer
+            con.shrink_buffer_if_needed(THRESHOLD);
+        }
+        assert!(con.get_buffer().capacity() <= THRESHOLD);
+    }
+}
+
+#[tokio::test]
+async fn large_value_is_written_past_the_buffer() {
+    const VALUE_SIZE: usize = 8 * 1024 * 1024;
+    let value = Data::from(
+        (0..VALUE_SIZE)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>(),
+    );
+    let mut con = MockConnection {
+        buffer: BytesMut::new(),
+        stream: BufWriter::new(RecordingStream::default()),
+        written: Written::Nothing,
+    };
+    unsafe {
+        // SAFETY: only the bytes that reach the stream are checked here, so the element doesn't
+        // need a header in front of it
+        writer::write_raw_mono(&mut con, b'+', &value)
+            .await
+            .unwrap();
+    }
+    con.flush_stream().await.unwrap();
+    let mut expected = format!("+{VALUE_SIZE}\n").into_bytes();
+    let header_len = expected.len();
+    expected.extend_from_slice(&value);
+    let stream = con.get_stream().get_ref();
+    // byte-identical to writing the header and then the value straight to the stream
+    assert!(stream.data == expected);
+    // the header is flushed first and the value isn't copied into the buffer
+    assert_eq!(stream.writes, [header_len, VALUE_SIZE]);
+}
+
+#[tokio::test]
+async fn written_tracks_first_response() {
+    let mut con = MockConnection::new(Vec::new());
+    assert_eq!(con.get_written(), Written::Nothing);
+    // only the first element tells what kind of response it is
+    con.write_response(groups::OVERWRITE_ERR).await.unwrap();
+    con.write_response(groups::OKAY).await.unwrap();
+    assert_eq!(con.get_written(), Written::Error);
+    con.reset_written();
+    con.write_flat_array_length(1).await.unwrap();
+    con.write_response(groups::SERVER_ERR).await.unwrap();
+    assert_eq!(con.get_written(), Written::Response);
+    // the "good" response codes aren't errors
+    for code in [
+        groups::OKAY,
+        groups::NIL,
+        groups::UPDATED,
+        groups::UNCHANGED,
+    ] {
+        con.reset_written();
+        con.write_response(code).await.unwrap();
+        assert_eq!(con.get_written(), Written::Response);
+    }
+    con.reset_written();
+    con.write_response(groups::ENCODING_ERROR.to_vec())
+        .await
+        .unwrap();
+    assert_eq!(con.get_written(), Written::Error);
+}
+
+#[test]
+fn acceptor_builder_defaults() {
+    assert!(super::tls::new_acceptor_builder(None, None).is_ok());
+    assert!(super::tls::new_acceptor_builder(
+        Some(TlsVersion::Tls13),
+        Some("ECDHE-RSA-AES128-GCM-SHA256:TLS_AES_128_GCM_SHA256")
+    )
+    .is_ok());
+}
+
+#[test]
+fn acceptor_builder_rejects_unknown_ciphers() {
+    assert!(super::tls::new_acceptor_builder(
+        Some(TlsVersion::Tls12),
+        Some("ECDHE-RSA-AES128-GCM-SHA256:NOT-A-CIPHER")
+    )
+    .is_err());
+    assert!(super::tls::new_acceptor_builder(None, Some("TLS_NOT_A_CIPHER")).is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_created_with_mode() {
+    use crate::config::{SocketPerm, UnixSockOpts};
+    use std::os::unix::fs::PermissionsExt;
+    let path = "unix_socket_created_with_mode.sock";
+    let opts = UnixSockOpts::new(path.to_owned(), Some(SocketPerm(0o600)));
+    let listener = super::unix::bind(&opts).unwrap();
+    let mode = std::fs::metadata(path).unwrap().permissions().mode();
+    // the socket was moved into place, so it should still be reachable at the path
+    let connected = tokio::net::UnixStream::connect(path).await.is_ok();
+    let tmpdir_left = std::path::Path::new(&format!(".{}.{}", path, std::process::id())).exists();
+    drop(listener);
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(mode & 0o777, 0o600);
+    assert!(connected);
+    assert!(!tmpdir_left);
+}
@@ -25,6 +25,7 @@
 */
 
 use crate::{
+    config::{SslOpts, TlsVersion},
     dbnet::{
         connection::{ConnectionHandler, ExecutorFn},
         tcp::{BufferedSocketStream, Connection, TcpBackoff},
@@ -36,9 +37,12 @@ use crate::{
 use openssl::{
     pkey::PKey,
     rsa::Rsa,
-    ssl::{Ssl, SslAcceptor, SslFiletype, SslMethod},
+    ssl::{
+        Ssl, SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod, SslOptions,
+        SslSessionCacheMode, SslVersion,
+    },
 };
-use std::{fs, pin::Pin};
+use std::{fs, net::SocketAddr, pin::Pin};
 use tokio::net::TcpStream;
 use tokio_openssl::SslStream;
 
@@ -51,14 +55,66 @@ pub struct SslListener {
     executor_fn: SslExecutorFn,
 }
 
+/// Create an acceptor builder with Mozilla's intermediate settings, restricted to the given
+/// minimum TLS version and ciphers (`:` separated). Each cipher is checked on its own, so
+/// that unknown ciphers are reported at startup instead of being silently skipped
+pub(super) fn new_acceptor_builder(
+    min_version: Option<TlsVersion>,
+    ciphers: Option<&str>,
+) -> SkyResult<SslAcceptorBuilder> {
+    let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
+    // sessions (whether by ID or by ticket) are cached for as long as the listener runs, so
+    // that reconnecting clients can skip the full handshake
+    builder.set_session_cache_mode(SslSessionCacheMode::SERVER);
+    builder.set_session_id_context(b"skyd")?;
+    let mut enable_tls13 = min_version == Some(TlsVersion::Tls13);
+    if let Some(ciphers) = ciphers {
+        // TLS 1.3 has its own cipher suites (all of which start with `TLS_`)
+        let (tls13_ciphers, ciphers): (Vec<&str>, Vec<&str>) = ciphers
+            .split(':')
+            .filter(|cipher| !cipher.is_empty())
+            .partition(|cipher| cipher.starts_with("TLS_"));
+        for cipher in ciphers.iter() {
+            if builder.set_cipher_list(cipher).is_err() {
+                return Err(Error::OtherError(format!("unknown TLS cipher `{cipher}`")));
+            }
+        }
+        for cipher in tls13_ciphers.iter() {
+            if builder.set_ciphersuites(cipher).is_err() {
+                return Err(Error::OtherError(format!("unknown TLS cipher `{cipher}`")));
+            }
+        }
+        if !ciphers.is_empty() {
+            builder.set_cipher_list(&ciphers.join(":"))?;
+        }
+        if !tls13_ciphers.is_empty() {
+            builder.set_ciphersuites(&tls13_ciphers.join(":"))?;
+            enable_tls13 = true;
+        }
+    }
+    if enable_tls13 {
+        // Mozilla's intermediate settings disable TLS 1.3
+        builder.clear_options(SslOptions::NO_TLSV1_3);
+    }
+    match min_version {
+        Some(TlsVersion::Tls12) => builder.set_min_proto_version(Some(SslVersion::TLS1_2))?,
+        Some(TlsVersion::Tls13) => builder.set_min_proto_version(Some(SslVersion::TLS1_3))?,
+        None => {}
+    }
+    Ok(builder)
+}
+
 impl SslListener {
-    pub fn new_pem_based_ssl_connection(
-        key_file: String,
-        chain_file: String,
-        base: BaseListener,
-        tls_passfile: Option<String>,
-    ) -> SkyResult<Self> {
-        let mut acceptor_builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
+    pub fn new_pem_based_ssl_connection(ssl: SslOpts, base: BaseListener) -> SkyResult<Self> {
+        let SslOpts {
+            key: key_file,
+            chain: chain_file,
+            passfile: tls_passfile,
+            min_version,
+            ciphers,
+            ..
+        } = ssl;
+        let mut acceptor_builder = self::new_acceptor_builder(min_version, ciphers.as_deref())?;
         // cert is the same for both
         acceptor_builder.set_certificate_chain_file(chain_file)?;
         if let Some(tls_passfile) = tls_passfile {
@@ -87,18 +143,17 @@ impl SslListener {
             base,
         })
     }
-    async fn accept(&mut self) -> SkyResult<SslStream<TcpStream>> {
+    async fn accept(&mut self) -> SkyResult<(SslStream<TcpStream>, SocketAddr)> {
         let backoff = TcpBackoff::new();
         loop {
             match self.base.listener.accept().await {
-                // We don't need the bindaddr
                 // We get the encrypted stream which we need to decrypt
                 // by using the acceptor
-                Ok((stream, _)) => {
+                Ok((stream, peer)) => {
                     let ssl = Ssl::new(self.acceptor.context())?;
                     let mut stream = SslStream::new(ssl, stream)?;
                     Pin::new(&mut stream).accept().await?;
-                    return Ok(stream);
+                    return Ok((stream, peer));
                 }
                 Err(e) => {
                     if backoff.should_disconnect() {
@@ -124,13 +179,16 @@ impl SslListener {
              can arise and it will flood the log and might also result
              in a crash
             */
-            let stream = skip_loop_err!(self.accept().await);
+            let (stream, peer) = skip_loop_err!(self.accept().await);
             let mut sslhandle = ConnectionHandler::new(
                 self.base.db.clone(),
                 Connection::new(stream),
                 self.base.auth.clone(),
                 self.executor_fn,
+                peer,
                 self.base.climit.clone(),
+                self.base.max_query_size,
+                self.base.idle_timeout,
                 Terminator::new(self.base.signal.subscribe()),
                 self.base.terminate_tx.clone(),
             );
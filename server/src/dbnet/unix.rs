@@ -0,0 +1,181 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Unix socket listener
+//!
+//! Local clients can connect over a unix domain socket instead of TCP. The socket file is
+//! created on startup (replacing a stale one left behind by an earlier run) and removed on
+//! shutdown
+
+use crate::{
+    config::{SocketPerm, UnixSockOpts},
+    dbnet::{
+        connection::{ConnectionHandler, ExecutorFn},
+        tcp::{BufferedSocketStream, Connection, TcpBackoff},
+        BaseListener, Terminator,
+    },
+    util::error::{Error, SkyResult},
+    IoResult,
+};
+use std::{
+    ffi::OsString,
+    fs::{self, DirBuilder, Permissions},
+    io::{Error as IoError, ErrorKind},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+    path::Path,
+    process,
+};
+use tokio::net::{UnixListener, UnixStream};
+
+impl BufferedSocketStream for UnixStream {}
+type UnixExecutorFn = ExecutorFn<Connection<UnixStream>, UnixStream>;
+
+/// Clients on a unix socket have no IP address, so they are reported with this one
+const UNIX_PEER: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
+
+/// Bind to the socket file with the configured permissions
+///
+/// The background services are already running by the time the listeners are bound, so the
+/// process-wide umask can't be changed here without also affecting the files they create.
+/// Instead, the socket is bound inside a private directory (that only we can enter), given
+/// its permissions there and then moved into place
+pub(super) fn bind(opts: &UnixSockOpts) -> SkyResult<UnixListener> {
+    let path = Path::new(&opts.path);
+    // binding fails if the socket file of an earlier run is still around
+    if let Ok(meta) = fs::symlink_metadata(path) {
+        if meta.file_type().is_socket() {
+            fs::remove_file(path).map_err(|e| {
+                Error::ioerror_extra(e, format!("removing stale unix socket {}", opts.path))
+            })?;
+        }
+    }
+    let listener = match opts.perm {
+        Some(SocketPerm(mode)) => bind_with_perm(path, mode),
+        None => UnixListener::bind(path),
+    };
+    let listener = listener
+        .map_err(|e| Error::ioerror_extra(e, format!("binding to unix socket {}", opts.path)))?;
+    Ok(listener)
+}
+
+/// Bind to `path` such that the socket is never reachable with looser permissions than `mode`,
+/// even for a moment
+fn bind_with_perm(path: &Path, mode: u32) -> IoResult<UnixListener> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| IoError::new(ErrorKind::InvalidInput, "the socket path has no file name"))?;
+    // the directory has to be on the same filesystem for the rename to work
+    let mut tmpdir_name = OsString::from(".");
+    tmpdir_name.push(name);
+    tmpdir_name.push(format!(".{}", process::id()));
+    let tmpdir = path.with_file_name(tmpdir_name);
+    let tmpsock = tmpdir.join(name);
+    DirBuilder::new().mode(0o700).create(&tmpdir)?;
+    let ret = UnixListener::bind(&tmpsock).and_then(|listener| {
+        fs::set_permissions(&tmpsock, Permissions::from_mode(mode & 0o777))?;
+        fs::rename(&tmpsock, path)?;
+        Ok(listener)
+    });
+    if ret.is_err() {
+        let _ = fs::remove_file(&tmpsock);
+    }
+    let _ = fs::remove_dir(&tmpdir);
+    ret
+}
+
+/// A unix socket listener
+pub struct UnixSockListener {
+    pub base: BaseListener<UnixListener>,
+    path: String,
+    executor_fn: UnixExecutorFn,
+}
+
+impl UnixSockListener {
+    pub fn new(base: BaseListener<UnixListener>, path: String) -> Self {
+        Self {
+            executor_fn: if base.auth.is_enabled() {
+                ConnectionHandler::execute_unauth
+            } else {
+                ConnectionHandler::execute_auth
+            },
+            base,
+            path,
+        }
+    }
+    /// Accept an incoming connection
+    async fn accept(&mut self) -> IoResult<UnixStream> {
+        let backoff = TcpBackoff::new();
+        loop {
+            match self.base.listener.accept().await {
+                Ok((stream, _)) => return Ok(stream),
+                Err(e) => {
+                    if backoff.should_disconnect() {
+                        // Too many retries, goodbye user
+                        return Err(e);
+                    }
+                }
+            }
+            // spin to wait for the backoff duration
+            backoff.spin().await;
+        }
+    }
+    /// Run the server
+    pub async fn run(&mut self) -> IoResult<()> {
+        loop {
+            // Take the permit first, but we won't use it right now
+            // that's why we will forget it
+            self.base.climit.acquire().await.unwrap().forget();
+            // SECURITY: Ignore any errors that may arise in the accept loop (see the TCP listener)
+            let stream = skip_loop_err!(self.accept().await);
+            let mut chandle = ConnectionHandler::new(
+                self.base.db.clone(),
+                Connection::new(stream),
+                self.base.auth.clone(),
+                self.executor_fn,
+                UNIX_PEER,
+                self.base.climit.clone(),
+                self.base.max_query_size,
+                self.base.idle_timeout,
+                Terminator::new(self.base.signal.subscribe()),
+                self.base.terminate_tx.clone(),
+            );
+            tokio::spawn(async move {
+                if let Err(e) = chandle.run().await {
+                    log::error!("Error: {}", e);
+                }
+            });
+        }
+    }
+    /// Wait for the connections to shut down and then remove the socket file
+    pub async fn release_self(self) {
+        let Self { base, path, .. } = self;
+        base.release_self().await;
+        if let Err(e) = fs::remove_file(&path) {
+            log::error!("Failed to remove unix socket {}: {}", path, e);
+        }
+    }
+}
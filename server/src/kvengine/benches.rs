@@ -0,0 +1,80 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+extern crate test;
+use super::json;
+use super::{Data, KVEStandard};
+use test::Bencher;
+
+/// Build a ~1KB JSON document that looks like a typical "user profile" value
+fn sample_document() -> String {
+    let mut doc = String::from("{\"users\": [");
+    let mut i = 0;
+    while doc.len() < 1000 {
+        if i != 0 {
+            doc.push_str(", ");
+        }
+        doc.push_str(&format!(
+            "{{\"id\": {i}, \"name\": \"user{i}\", \"score\": {}.5, \"active\": true, \"tags\": [\"a\", \"b\\u00e9\"]}}",
+            i * 7
+        ));
+        i += 1;
+    }
+    doc.push_str("]}");
+    doc
+}
+
+#[bench]
+fn bench_validate_1kb_document(b: &mut Bencher) {
+    let doc = sample_document();
+    b.bytes = doc.len() as u64;
+    b.iter(|| json::validate(doc.as_bytes()).unwrap())
+}
+
+#[bench]
+fn bench_set_1kb_document_no_validation(b: &mut Bencher) {
+    let doc = sample_document();
+    let tbl = KVEStandard::default();
+    b.iter(|| {
+        let val = doc.as_bytes();
+        tbl.check_json(val).unwrap();
+        tbl.upsert(Data::from("doc"), Data::copy_from_slice(val))
+            .unwrap();
+    })
+}
+
+#[bench]
+fn bench_set_1kb_document_with_validation(b: &mut Bencher) {
+    let doc = sample_document();
+    let mut tbl = KVEStandard::default();
+    tbl.set_json_validation(true);
+    b.iter(|| {
+        let val = doc.as_bytes();
+        tbl.check_json(val).unwrap();
+        tbl.upsert(Data::from("doc"), Data::copy_from_slice(val))
+            .unwrap();
+    })
+}
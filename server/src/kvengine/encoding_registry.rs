@@ -0,0 +1,74 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Encoding registry
+//!
+//! The names reported by `OBJECT ENCODING`, for every table model. Every implementation
+//! classifies the value into one of the [`EncodingFlags`] and then asks [`type_name`] for the
+//! name, so adding a new encoding is a matter of adding an entry here
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The data model of the table the value lives in
+pub enum TableModelType {
+    /// A key/value table
+    KV,
+    /// A key/list table
+    KVExtListmap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The shape of a value, as classified by the `OBJECT ENCODING` implementation
+pub enum EncodingFlags {
+    /// A value that is an integer and fits in an `i64`
+    Int,
+    /// A value that is small enough to be stored compactly
+    Compact,
+    /// Everything else
+    Large,
+}
+
+/// Returns the name of the encoding for a value of the given shape in a table with the given model
+pub const fn type_name(table_model: TableModelType, encoding: EncodingFlags) -> &'static str {
+    use {EncodingFlags::*, TableModelType::*};
+    match (table_model, encoding) {
+        (KV, Int) => "int",
+        (KV, Compact) => "embstr",
+        (KV, Large) => "raw",
+        // a list is never an integer
+        (KVExtListmap, Int | Compact) => "listpack",
+        (KVExtListmap, Large) => "quicklist",
+    }
+}
+
+#[test]
+fn test_type_name() {
+    use {EncodingFlags::*, TableModelType::*};
+    assert_eq!(type_name(KV, Int), "int");
+    assert_eq!(type_name(KV, Compact), "embstr");
+    assert_eq!(type_name(KV, Large), "raw");
+    assert_eq!(type_name(KVExtListmap, Compact), "listpack");
+    assert_eq!(type_name(KVExtListmap, Large), "quicklist");
+}
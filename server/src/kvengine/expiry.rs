@@ -0,0 +1,117 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Key expiry
+//!
+//! Deadlines for keys are kept in a side table, as unix timestamps in milliseconds. Expired keys
+//! are removed lazily, when they're accessed, and by the expiry service which periodically
+//! sweeps all the tables. Tables that never had a deadline set don't pay for anything beyond an
+//! atomic load on every access.
+//!
+//! The deadlines of persistent tables are flushed to a file next to the table's file, right
+//! after the table itself (see `storage::v1::flush`)
+
+use crate::corestore::htable::Coremap;
+use crate::corestore::Data;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Returns the current unix timestamp in milliseconds
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|dur| dur.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Default)]
+/// The deadlines of the keys in a table
+pub struct ExpiryTable {
+    deadlines: Coremap<Data, u64>,
+    /// set once any deadline is set, so that tables that never use deadlines can skip the
+    /// lookups
+    armed: AtomicBool,
+}
+
+impl ExpiryTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns true if a deadline was ever set in this table
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::Acquire)
+    }
+    /// Set (or replace) the deadline of a key
+    pub fn set(&self, key: Data, deadline: u64) {
+        self.armed.store(true, Ordering::Release);
+        self.deadlines.upsert(key, deadline)
+    }
+    /// Returns the deadline of a key, if it has one
+    pub fn get(&self, key: &[u8]) -> Option<u64> {
+        if self.is_armed() {
+            self.deadlines.get(key).map(|deadline| *deadline)
+        } else {
+            None
+        }
+    }
+    /// Remove the deadline of a key. Returns true if the key had a deadline
+    pub fn remove(&self, key: &[u8]) -> bool {
+        self.is_armed() && self.deadlines.true_if_removed(key)
+    }
+    /// Returns true if the key has a deadline that has passed as of `now`
+    pub fn is_expired(&self, key: &[u8], now: u64) -> bool {
+        matches!(self.get(key), Some(deadline) if deadline <= now)
+    }
+    /// Returns the keys whose deadlines have passed as of `now`
+    pub fn expired_keys(&self, now: u64) -> Vec<Data> {
+        if !self.is_armed() {
+            return Vec::new();
+        }
+        self.deadlines
+            .iter()
+            .filter(|kv| *kv.value() <= now)
+            .map(|kv| kv.key().clone())
+            .collect()
+    }
+    /// Returns the number of deadlines that have passed as of `now`
+    pub fn count_expired(&self, now: u64) -> usize {
+        if !self.is_armed() {
+            return 0;
+        }
+        self.deadlines
+            .iter()
+            .filter(|kv| *kv.value() <= now)
+            .count()
+    }
+    /// Returns a reference to the inner structure
+    pub fn get_inner_ref(&self) -> &Coremap<Data, u64> {
+        &self.deadlines
+    }
+    /// Remove all the deadlines
+    pub fn clear(&self) {
+        self.deadlines.clear()
+    }
+}
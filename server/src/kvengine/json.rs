@@ -0,0 +1,318 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # JSON validation
+//!
+//! A minimal, allocation-free JSON (RFC 8259) well-formedness checker used by tables created with
+//! the `validate:json` property. This doesn't build any document; it only walks the input once
+//! (plus a UTF-8 check for every string) and keeps the nesting in a fixed-size bit stack.
+//!
+//! ## Surrogates
+//! Escaped UTF-16 surrogates must come in pairs (like `\uD83D\uDE00`); a lone high or low surrogate
+//! is rejected since it can't be represented as UTF-8 by any consumer of the document
+
+use core::fmt;
+
+/// The maximum nesting depth of arrays and objects
+pub const MAX_DEPTH: usize = 1024;
+
+const REASON_EOF: &str = "unexpected end of input";
+const REASON_UNEXPECTED: &str = "unexpected character";
+const REASON_TRAILING: &str = "trailing characters";
+const REASON_TOO_DEEP: &str = "nesting too deep";
+const REASON_BAD_LITERAL: &str = "invalid literal";
+const REASON_BAD_NUMBER: &str = "invalid number";
+const REASON_BAD_ESCAPE: &str = "invalid escape";
+const REASON_LONE_SURROGATE: &str = "lone surrogate";
+const REASON_CONTROL_CHAR: &str = "control character in string";
+const REASON_BAD_UTF8: &str = "invalid utf-8";
+const REASON_EXPECTED_KEY: &str = "expected string key";
+const REASON_EXPECTED_COLON: &str = "expected ':'";
+const REASON_EXPECTED_SEPARATOR: &str = "expected ',' or closing bracket";
+
+#[derive(Debug, PartialEq)]
+/// A validation error with the offset of the offending byte
+pub struct JsonError {
+    pub offset: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.reason, self.offset)
+    }
+}
+
+type JsonResult<T> = Result<T, JsonError>;
+
+/// Returns an error if the input isn't a single well-formed JSON value
+pub fn validate(input: &[u8]) -> JsonResult<()> {
+    Validator::new(input).run()
+}
+
+struct Validator<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    depth: usize,
+    /// a set bit means that the container at that depth is an object
+    stack: [u64; MAX_DEPTH / 64],
+}
+
+impl<'a> Validator<'a> {
+    const fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            depth: 0,
+            stack: [0; MAX_DEPTH / 64],
+        }
+    }
+    const fn err<T>(&self, reason: &'static str) -> JsonResult<T> {
+        Err(JsonError {
+            offset: self.pos,
+            reason,
+        })
+    }
+    fn peek(&self) -> Option<u8> {
+        self.buf.get(self.pos).copied()
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+    fn push(&mut self, is_object: bool) -> JsonResult<()> {
+        if self.depth == MAX_DEPTH {
+            return self.err(REASON_TOO_DEEP);
+        }
+        let (word, bit) = (self.depth / 64, self.depth % 64);
+        if is_object {
+            self.stack[word] |= 1 << bit;
+        } else {
+            self.stack[word] &= !(1 << bit);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+    fn in_object(&self) -> bool {
+        let top = self.depth - 1;
+        self.stack[top / 64] & (1 << (top % 64)) != 0
+    }
+    fn run(mut self) -> JsonResult<()> {
+        loop {
+            // we're expecting a value
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'{') => {
+                    self.push(true)?;
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        self.pos += 1;
+                        self.depth -= 1;
+                    } else {
+                        self.object_key()?;
+                        continue;
+                    }
+                }
+                Some(b'[') => {
+                    self.push(false)?;
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        self.pos += 1;
+                        self.depth -= 1;
+                    } else {
+                        continue;
+                    }
+                }
+                Some(b'"') => self.string()?,
+                Some(b't') => self.literal(b"true")?,
+                Some(b'f') => self.literal(b"false")?,
+                Some(b'n') => self.literal(b"null")?,
+                Some(b'-' | b'0'..=b'9') => self.number()?,
+                Some(_) => return self.err(REASON_UNEXPECTED),
+                None => return self.err(REASON_EOF),
+            }
+            // we just finished a value; close containers till we need another value
+            loop {
+                self.skip_whitespace();
+                if self.depth == 0 {
+                    if self.pos != self.buf.len() {
+                        return self.err(REASON_TRAILING);
+                    }
+                    return Ok(());
+                }
+                match self.peek() {
+                    Some(b',') => {
+                        self.pos += 1;
+                        if self.in_object() {
+                            self.skip_whitespace();
+                            self.object_key()?;
+                        }
+                        break;
+                    }
+                    Some(b'}') if self.in_object() => {
+                        self.pos += 1;
+                        self.depth -= 1;
+                    }
+                    Some(b']') if !self.in_object() => {
+                        self.pos += 1;
+                        self.depth -= 1;
+                    }
+                    Some(_) => return self.err(REASON_EXPECTED_SEPARATOR),
+                    None => return self.err(REASON_EOF),
+                }
+            }
+        }
+    }
+    /// Validate `"key" :` (leading whitespace should have been skipped)
+    fn object_key(&mut self) -> JsonResult<()> {
+        match self.peek() {
+            Some(b'"') => self.string()?,
+            Some(_) => return self.err(REASON_EXPECTED_KEY),
+            None => return self.err(REASON_EOF),
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b':') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => self.err(REASON_EXPECTED_COLON),
+            None => self.err(REASON_EOF),
+        }
+    }
+    fn literal(&mut self, literal: &[u8]) -> JsonResult<()> {
+        if self.buf[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            self.err(REASON_BAD_LITERAL)
+        }
+    }
+    fn digits(&mut self) -> JsonResult<()> {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            self.err(REASON_BAD_NUMBER)
+        } else {
+            Ok(())
+        }
+    }
+    fn number(&mut self) -> JsonResult<()> {
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            // no leading zeroes
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.digits()?,
+            _ => return self.err(REASON_BAD_NUMBER),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.digits()?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.digits()?;
+        }
+        Ok(())
+    }
+    /// Read the 4 hex digits of a `\u` escape
+    fn hex4(&mut self) -> JsonResult<u16> {
+        let mut code = 0u16;
+        for _ in 0..4 {
+            let digit = match self.peek() {
+                Some(b @ b'0'..=b'9') => b - b'0',
+                Some(b @ b'a'..=b'f') => b - b'a' + 10,
+                Some(b @ b'A'..=b'F') => b - b'A' + 10,
+                Some(_) => return self.err(REASON_BAD_ESCAPE),
+                None => return self.err(REASON_EOF),
+            };
+            code = (code << 4) | digit as u16;
+            self.pos += 1;
+        }
+        Ok(code)
+    }
+    fn string(&mut self) -> JsonResult<()> {
+        // skip the opening quote
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let escape_start = self.pos;
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.pos += 1
+                        }
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let code = self.hex4()?;
+                            if (0xDC00..0xE000).contains(&code) {
+                                self.pos = escape_start;
+                                return self.err(REASON_LONE_SURROGATE);
+                            }
+                            if (0xD800..0xDC00).contains(&code) {
+                                // a high surrogate must be followed by a low surrogate
+                                if !self.buf[self.pos..].starts_with(b"\\u") {
+                                    self.pos = escape_start;
+                                    return self.err(REASON_LONE_SURROGATE);
+                                }
+                                self.pos += 2;
+                                if !(0xDC00..0xE000).contains(&self.hex4()?) {
+                                    self.pos = escape_start;
+                                    return self.err(REASON_LONE_SURROGATE);
+                                }
+                            }
+                        }
+                        Some(_) => return self.err(REASON_BAD_ESCAPE),
+                        None => return self.err(REASON_EOF),
+                    }
+                }
+                Some(0x00..=0x1F) => return self.err(REASON_CONTROL_CHAR),
+                Some(_) => self.pos += 1,
+                None => return self.err(REASON_EOF),
+            }
+        }
+        if let Err(e) = core::str::from_utf8(&self.buf[start..self.pos]) {
+            self.pos = start + e.valid_up_to();
+            return self.err(REASON_BAD_UTF8);
+        }
+        // skip the closing quote
+        self.pos += 1;
+        Ok(())
+    }
+}
@@ -26,14 +26,30 @@
 
 #![allow(dead_code)] // TODO(@ohsayan): Clean this up later
 
+#[cfg(feature = "nightly")]
+mod benches;
 pub mod encoding;
+pub mod encoding_registry;
+pub mod expiry;
+pub mod json;
 #[cfg(test)]
 mod tests;
 
 use self::encoding::{ENCODING_LUT, ENCODING_LUT_PAIR};
-use crate::corestore::{booltable::BoolTable, htable::Coremap, map::bref::Ref, Data};
+use self::expiry::ExpiryTable;
+use self::json::JsonError;
+use crate::corestore::{
+    booltable::BoolTable,
+    htable::{Coremap, UpsertOutcome},
+    map::bref::Ref,
+    Data,
+};
 use crate::util::compiler;
+use bytes::BytesMut;
+use core::{mem, ops::Bound, ptr};
 use parking_lot::RwLock;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicIsize, Ordering};
 
 pub type KVEStandard = KVEngine<Data>;
 pub type KVEListmap = KVEngine<LockedVec>;
@@ -46,9 +62,66 @@ type OptionRef<'a, T> = Option<Ref<'a, Data, T>>;
 type EncodingResultRef<'a, T> = EncodingResult<OptionRef<'a, T>>;
 
 const TSYMBOL_LUT: BoolTable<u8> = BoolTable::new(b'+', b'?');
+/// The approximate fixed cost of a single entry (the key and value handles), in bytes
+const ENTRY_OVERHEAD: usize = mem::size_of::<Data>() * 2;
+/// The approximate fixed cost of a single list element (its handle), in bytes
+const LIST_ITEM_OVERHEAD: usize = mem::size_of::<Data>();
+
+/// Returns an estimate of the memory used by the given list elements, in bytes
+pub fn list_items_estimate<'a>(items: impl IntoIterator<Item = &'a Data>) -> usize {
+    items
+        .into_iter()
+        .map(|item| item.len() + LIST_ITEM_OVERHEAD)
+        .sum()
+}
+
+/// The reasons for which [`KVEStandard::incr_by`] and [`KVEStandard::incr_by_float`] can
+/// fail
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IncrError {
+    /// the key isn't correctly encoded for the table
+    Encoding,
+    /// the stored value isn't a base-10 integer (or a finite float)
+    NotANumber,
+    /// the new value doesn't fit in an `i64` (or is infinite)
+    Overflow,
+}
+
+/// The reasons for which [`KVEStandard::append`] can fail
+#[derive(Debug, PartialEq)]
+pub enum AppendError {
+    /// the key or the suffix isn't correctly encoded for the table
+    Encoding,
+    /// the table needs JSON values and the new value isn't well-formed JSON
+    Json(JsonError),
+}
+
+/// Parse a finite float, rejecting `NaN` and infinities
+pub fn parse_finite_float(val: &[u8]) -> Option<f64> {
+    core::str::from_utf8(val)
+        .ok()?
+        .parse::<f64>()
+        .ok()
+        .filter(|val| val.is_finite())
+}
+
+/// Format a float in its shortest form that parses back to the same value, switching to the
+/// `e` notation for very large and very small values
+pub fn format_float(val: f64) -> String {
+    let abs = val.abs();
+    if abs != 0.0 && !(1e-6..1e21).contains(&abs) {
+        format!("{val:e}")
+    } else {
+        format!("{val}")
+    }
+}
 
 pub trait KVEValue {
     fn verify_encoding(&self, e_v: bool) -> EncodingResult<()>;
+    /// An estimate of the memory used by this value, in bytes
+    fn memory_estimate(&self) -> usize;
+    /// Returns true if this value is well-formed JSON
+    fn is_json(&self) -> bool;
 }
 
 impl KVEValue for Data {
@@ -59,6 +132,12 @@ impl KVEValue for Data {
             Err(())
         }
     }
+    fn memory_estimate(&self) -> usize {
+        self.len()
+    }
+    fn is_json(&self) -> bool {
+        json::validate(self).is_ok()
+    }
 }
 
 impl KVEValue for LockedVec {
@@ -70,6 +149,13 @@ impl KVEValue for LockedVec {
             Err(())
         }
     }
+    fn memory_estimate(&self) -> usize {
+        list_items_estimate(self.read().iter())
+    }
+    fn is_json(&self) -> bool {
+        // lists are never validated
+        false
+    }
 }
 
 #[derive(Debug)]
@@ -77,13 +163,33 @@ pub struct KVEngine<T> {
     data: Coremap<Data, T>,
     e_k: bool,
     e_v: bool,
+    /// values need to be well-formed JSON
+    validate_json: bool,
+    /// the deadlines of keys that expire
+    expiry: ExpiryTable,
+    /// the keys in order, if the table keeps an ordered index. This is only updated while
+    /// holding the lock of the key's entry
+    ordered: Option<RwLock<BTreeSet<Data>>>,
+    /// the estimate of the memory used by the keys and values, which is kept up to date by
+    /// every write. This is signed because concurrent writers can account for a value being
+    /// freed before it has been accounted for being allocated
+    memory: AtomicIsize,
 }
 
 // basic method impls
-impl<T> KVEngine<T> {
+impl<T: KVEValue> KVEngine<T> {
     /// Create a new KVEBlob
     pub fn new(e_k: bool, e_v: bool, data: Coremap<Data, T>) -> Self {
-        Self { data, e_k, e_v }
+        let memory = Self::compute_memory_estimate(&data) as isize;
+        Self {
+            data,
+            e_k,
+            e_v,
+            validate_json: false,
+            expiry: ExpiryTable::new(),
+            ordered: None,
+            memory: AtomicIsize::new(memory),
+        }
     }
     /// Create a new empty KVEBlob
     pub fn init(e_k: bool, e_v: bool) -> Self {
@@ -93,14 +199,241 @@ impl<T> KVEngine<T> {
     pub fn len(&self) -> usize {
         self.data.len()
     }
+    /// Number of KV pairs, not counting the keys that have expired but haven't been removed yet
+    pub fn live_len(&self) -> usize {
+        let expired = self.expiry.count_expired(expiry::now_millis());
+        self.data.len().saturating_sub(expired)
+    }
+    /// Returns at most `count` keys, skipping the keys that have expired
+    pub fn get_live_keys(&self, count: usize) -> Vec<Data> {
+        if compiler::likely(!self.expiry.is_armed()) {
+            return self.data.get_keys(count);
+        }
+        let now = expiry::now_millis();
+        self.data
+            .iter()
+            .map(|kv| kv.key().clone())
+            .filter(|key| !self.expiry.is_expired(key, now))
+            .take(count)
+            .collect()
+    }
     /// Delete all the key/value pairs
     pub fn truncate_table(&self) {
-        self.data.clear()
+        // the index is cleared first: a key inserted in between is then at worst left behind
+        // in the index (which range queries skip), but is never missing from it
+        if let Some(ref index) = self.ordered {
+            index.write().clear();
+        }
+        // a deadline is removed along with its key, under the key's shard lock (writers set
+        // deadlines under the same lock). Clearing all the deadlines afterwards would also drop
+        // the deadline of a key set in a shard that was already cleared
+        let mut freed = 0;
+        self.data.clear_with(|key, val| {
+            self.expiry.remove(key);
+            freed += Self::entry_estimate(key, val);
+        });
+        self.account_memory(freed, 0);
     }
     /// Returns a reference to the inner structure
     pub fn get_inner_ref(&self) -> &Coremap<Data, T> {
         &self.data
     }
+    /// Returns a reference to the deadlines of the keys in this table
+    pub fn get_expiry_ref(&self) -> &ExpiryTable {
+        &self.expiry
+    }
+    /// Remove the key (and its value) if its deadline has passed. Returns true if the key
+    /// was removed
+    pub fn expire_if_due<Q: AsRef<[u8]>>(&self, key: Q) -> bool {
+        if compiler::likely(!self.expiry.is_armed()) {
+            return false;
+        }
+        let key = key.as_ref();
+        let now = expiry::now_millis();
+        if !self.expiry.is_expired(key, now) {
+            return false;
+        }
+        // check again while holding the entry's lock, since some other thread may have
+        // reset the deadline or removed the key in the meantime
+        self.data.true_remove_if(key, |_, val| {
+            let remove = self.expiry.is_expired(key, now) && self.expiry.remove(key);
+            if remove {
+                self.index_remove(key);
+                self.account_memory(Self::entry_estimate(key, val), 0);
+            }
+            remove
+        })
+    }
+    /// Returns at most `count` keys starting from `cursor`, skipping the keys that have
+    /// expired, along with the cursor to continue from (0 once all the keys have been returned)
+    pub fn scan_live_keys(&self, cursor: u64, count: usize) -> (Vec<Data>, u64) {
+        let (mut keys, cursor) = self.data.scan_keys(cursor, count);
+        if compiler::unlikely(self.expiry.is_armed()) {
+            let now = expiry::now_millis();
+            keys.retain(|key| !self.expiry.is_expired(key, now));
+        }
+        (keys, cursor)
+    }
+    /// Remove all the keys whose deadlines have passed. Returns the number of keys removed
+    pub fn remove_expired(&self) -> usize {
+        self.expiry
+            .expired_keys(expiry::now_millis())
+            .into_iter()
+            .filter(|key| self.expire_if_due(key))
+            .count()
+    }
+    /// Set the deadline (a unix timestamp in milliseconds) of the given key, or remove it if
+    /// `deadline` is `None`. Returns true if the key exists and, when removing, if it had a
+    /// deadline
+    pub fn set_deadline(&self, key: &[u8], deadline: Option<u64>) -> EncodingResult<bool> {
+        self.check_key_encoding(key)?;
+        self.expire_if_due(key);
+        let _entry = match self.data.mut_entry(Data::copy_from_slice(key)) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        // we hold the entry's lock, so no one can remove the key before the deadline is set
+        match deadline {
+            Some(deadline) => {
+                self.expiry.set(Data::copy_from_slice(key), deadline);
+                Ok(true)
+            }
+            None => Ok(self.expiry.remove(key)),
+        }
+    }
+    /// Set the deadlines of the keys that exist in the table, ignoring the rest. This is used
+    /// to restore the deadlines read from disk
+    pub fn restore_deadlines(&self, deadlines: impl IntoIterator<Item = (Data, u64)>) {
+        for (key, deadline) in deadlines {
+            if self.data.contains_key(key.as_ref()) {
+                self.expiry.set(key, deadline);
+            }
+        }
+    }
+    /// Returns the deadline (a unix timestamp in milliseconds) of the given key. This is `None`
+    /// if the key doesn't exist and `Some(None)` if it exists but has no deadline
+    pub fn get_deadline(&self, key: &[u8]) -> EncodingResult<Option<Option<u64>>> {
+        self.check_key_encoding(key)?;
+        self.expire_if_due(key);
+        Ok(self.data.get(key).map(|_| self.expiry.get(key)))
+    }
+    /// Returns the milliseconds left before the given key expires. This is `None` if the key
+    /// doesn't exist and `Some(None)` if it exists but has no deadline
+    pub fn time_to_live(&self, key: &[u8]) -> EncodingResult<Option<Option<u64>>> {
+        let now = expiry::now_millis();
+        let ttl = self.get_deadline(key)?;
+        Ok(ttl.map(|deadline| deadline.map(|deadline| deadline.saturating_sub(now))))
+    }
+    /// Enable or disable the ordered index of keys. If enabled, the index is built from the
+    /// keys that are already in the table
+    pub fn set_ordered(&mut self, ordered: bool) {
+        self.ordered = if ordered {
+            let index = self.data.iter().map(|kv| kv.key().clone()).collect();
+            Some(RwLock::new(index))
+        } else {
+            None
+        };
+    }
+    /// Returns true if the table keeps an ordered index of its keys
+    pub fn is_ordered(&self) -> bool {
+        self.ordered.is_some()
+    }
+    /// Returns an estimate of the memory used by the keys and values in this engine (in bytes).
+    /// This is not the actual allocation size, but is good enough to be compared against
+    /// a memory budget. The estimate is kept up to date by writes, so this is cheap
+    pub fn memory_estimate(&self) -> usize {
+        self.memory.load(Ordering::Relaxed).max(0) as usize
+    }
+    /// Record that something in this engine that was estimated to use `old` bytes now uses
+    /// `new` bytes. Writes that change values in place (like the ones on lists) have to call
+    /// this themselves
+    pub fn account_memory(&self, old: usize, new: usize) {
+        self.memory
+            .fetch_add(new as isize - old as isize, Ordering::Relaxed);
+    }
+    /// Estimate the memory used by all the entries by walking them. This is only done once,
+    /// when the engine is created with existing data
+    fn compute_memory_estimate(data: &Coremap<Data, T>) -> usize {
+        data.iter()
+            .map(|kv| Self::entry_estimate(kv.key(), kv.value()))
+            .sum()
+    }
+    /// Estimate the memory used by a single entry
+    fn entry_estimate(key: &[u8], val: &T) -> usize {
+        ENTRY_OVERHEAD + key.len() + val.memory_estimate()
+    }
+    /// Returns at most `limit` keys between `start` and `end` (both inclusive) in ascending
+    /// order, skipping the keys that have expired. Nothing is returned if the table doesn't
+    /// keep an ordered index
+    pub fn key_range(&self, start: &[u8], end: &[u8], limit: usize) -> EncodingResult<Vec<Data>> {
+        self.check_key_encoding(start)?;
+        self.check_key_encoding(end)?;
+        let mut keys = Vec::new();
+        let index = match self.ordered {
+            Some(ref index) if start <= end => index,
+            _ => return Ok(keys),
+        };
+        let now = expiry::now_millis();
+        let mut last: Option<Data> = None;
+        while keys.len() < limit {
+            let want = limit - keys.len();
+            // never touch the map while holding the index's lock, since writers acquire the
+            // entry's lock first
+            let batch: Vec<Data> = {
+                let lower = match last {
+                    Some(ref last) => Bound::Excluded(last.as_ref()),
+                    None => Bound::Included(start),
+                };
+                index
+                    .read()
+                    .range::<[u8], _>((lower, Bound::Included(end)))
+                    .take(want)
+                    .cloned()
+                    .collect()
+            };
+            let exhausted = batch.len() < want;
+            last = batch.last().cloned();
+            keys.extend(batch.into_iter().filter(|key| {
+                !self.expiry.is_expired(key, now) && self.data.contains_key(key.as_ref())
+            }));
+            if exhausted {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+    /// Add the key to the ordered index (if any). The caller must hold the entry's lock
+    #[inline(always)]
+    fn index_insert(&self, key: &Data) {
+        if let Some(ref index) = self.ordered {
+            index.write().insert(key.clone());
+        }
+    }
+    /// Remove the key from the ordered index (if any). The caller must hold the entry's lock
+    #[inline(always)]
+    fn index_remove(&self, key: &[u8]) {
+        if let Some(ref index) = self.ordered {
+            index.write().remove(key);
+        }
+    }
+    /// Same as [`Coremap::upsert_with`], but also adds the key to the ordered index (if any)
+    /// if it didn't exist
+    fn upsert_with_indexed<R, E>(
+        &self,
+        key: Data,
+        f: impl FnOnce(Option<&T>) -> Result<(T, R), E>,
+    ) -> Result<R, E> {
+        self.data.upsert_with(key.clone(), |current| {
+            let is_insert = current.is_none();
+            let old = current.map_or(0, |val| Self::entry_estimate(&key, val));
+            let (new, ret) = f(current)?;
+            if is_insert {
+                self.index_insert(&key);
+            }
+            self.account_memory(old, Self::entry_estimate(&key, &new));
+            Ok((new, ret))
+        })
+    }
     /// Check the encoding of the key
     pub fn is_key_ok(&self, key: &[u8]) -> bool {
         self._check_encoding(key, self.e_k)
@@ -155,6 +488,34 @@ impl<T> KVEngine<T> {
     pub fn get_val_encoder(&self) -> SingleEncoder {
         ENCODING_LUT[self.e_v]
     }
+    /// Enable or disable JSON validation for values
+    pub fn set_json_validation(&mut self, validate_json: bool) {
+        self.validate_json = validate_json;
+    }
+    /// Returns true if values need to be well-formed JSON
+    pub fn is_json_validated(&self) -> bool {
+        self.validate_json
+    }
+    /// Check that the value is well-formed JSON if JSON validation is enabled
+    pub fn check_json(&self, val: &[u8]) -> Result<(), JsonError> {
+        if self.validate_json {
+            json::validate(val)
+        } else {
+            Ok(())
+        }
+    }
+    /// Check that all the values are well-formed JSON if JSON validation is enabled. The
+    /// first malformed value is reported
+    pub fn check_json_all<'a>(
+        &self,
+        mut vals: impl Iterator<Item = &'a [u8]>,
+    ) -> Result<(), JsonError> {
+        if self.validate_json {
+            vals.try_for_each(json::validate)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 // dict impls
@@ -166,6 +527,7 @@ impl<T: KVEValue> KVEngine<T> {
     }
     /// Get the value of the given key without any encoding checks
     pub fn get_unchecked<Q: AsRef<[u8]>>(&self, key: Q) -> OptionRef<T> {
+        self.expire_if_due(key.as_ref());
         self.data.get(key.as_ref())
     }
     /// Set the value of the given key
@@ -176,7 +538,16 @@ impl<T: KVEValue> KVEngine<T> {
     }
     /// Same as set, but doesn't check encoding. Caller must check encoding
     pub fn set_unchecked(&self, key: Data, val: T) -> bool {
-        self.data.true_if_insert(key, val)
+        self.expire_if_due(&key);
+        match self.data.fresh_entry(key) {
+            Some(entry) => {
+                self.index_insert(entry.key());
+                self.account_memory(0, Self::entry_estimate(entry.key(), &val));
+                entry.insert(val);
+                true
+            }
+            None => false,
+        }
     }
     /// Check if the provided key exists
     pub fn exists<Q: AsRef<[u8]>>(&self, key: Q) -> EncodingResult<bool> {
@@ -184,6 +555,7 @@ impl<T: KVEValue> KVEngine<T> {
         Ok(self.exists_unchecked(key.as_ref()))
     }
     pub fn exists_unchecked<Q: AsRef<[u8]>>(&self, key: Q) -> bool {
+        self.expire_if_due(key.as_ref());
         self.data.contains_key(key.as_ref())
     }
     /// Update the value of an existing key. Returns `true` if updated
@@ -194,7 +566,21 @@ impl<T: KVEValue> KVEngine<T> {
     }
     /// Update the value of an existing key without encoding checks
     pub fn update_unchecked(&self, key: Data, val: T) -> bool {
-        self.data.true_if_update(key, val)
+        self.update_if_unchecked(key, val, |_| true)
+    }
+    /// Update the value of an existing key if `cond` returns true for its current value,
+    /// without encoding checks. Returns `true` if updated
+    pub fn update_if_unchecked(&self, key: Data, val: T, cond: impl FnOnce(&T) -> bool) -> bool {
+        self.expire_if_due(&key);
+        match self.data.mut_entry(key) {
+            Some(mut entry) if cond(entry.value()) => {
+                let new = Self::entry_estimate(entry.key(), &val);
+                let old = entry.insert(val);
+                self.account_memory(Self::entry_estimate(entry.key(), &old), new);
+                true
+            }
+            _ => false,
+        }
     }
     /// Update or insert an entry
     pub fn upsert(&self, key: Data, val: T) -> EncodingResult<()> {
@@ -203,9 +589,33 @@ impl<T: KVEValue> KVEngine<T> {
         self.upsert_unchecked(key, val);
         Ok(())
     }
-    /// Update or insert an entry without encoding checks
+    /// Update or insert an entry without encoding checks. If the key already exists, its
+    /// deadline (if any) is left as is
     pub fn upsert_unchecked(&self, key: Data, val: T) {
-        self.data.upsert(key, val)
+        self.expire_if_due(&key);
+        self.replace_or_insert(key, val);
+    }
+    /// Update or insert an entry, returning the old value (if any) and accounting for the
+    /// change in memory usage
+    fn replace_or_insert(&self, key: Data, val: T) -> Option<T> {
+        let key_len = key.len();
+        let new = ENTRY_OVERHEAD + key_len + val.memory_estimate();
+        let old = self
+            .data
+            .replace_or_insert(key, val, |key| self.index_insert(key));
+        let freed = old
+            .as_ref()
+            .map_or(0, |old| ENTRY_OVERHEAD + key_len + old.memory_estimate());
+        self.account_memory(freed, new);
+        old
+    }
+    /// Update or insert an entry, returning the old value (if any). If the key already
+    /// exists, its deadline (if any) is left as is
+    pub fn get_and_upsert(&self, key: Data, val: T) -> EncodingResult<Option<T>> {
+        self.check_key_encoding(&key)?;
+        val.verify_encoding(self.e_v)?;
+        self.expire_if_due(&key);
+        Ok(self.replace_or_insert(key, val))
     }
     /// Remove an entry
     pub fn remove<Q: AsRef<[u8]>>(&self, key: Q) -> EncodingResult<bool> {
@@ -214,7 +624,101 @@ impl<T: KVEValue> KVEngine<T> {
     }
     /// Remove an entry without encoding checks
     pub fn remove_unchecked<Q: AsRef<[u8]>>(&self, key: Q) -> bool {
-        self.data.true_if_removed(key.as_ref())
+        self.remove_if_unchecked(key, |_| true)
+    }
+    /// Remove an entry if `cond` returns true for its value, without encoding checks. The
+    /// key's deadline is removed along with it
+    pub fn remove_if_unchecked<Q: AsRef<[u8]>>(
+        &self,
+        key: Q,
+        cond: impl FnOnce(&T) -> bool,
+    ) -> bool {
+        let key = key.as_ref();
+        if self.expire_if_due(key) {
+            // it's gone
+            return false;
+        }
+        self.data.true_remove_if(key, |_, val| {
+            let remove = cond(val);
+            if remove {
+                self.expiry.remove(key);
+                self.index_remove(key);
+                self.account_memory(Self::entry_estimate(key, val), 0);
+            }
+            remove
+        })
+    }
+    /// Set the value of the given key, along with a deadline (a unix timestamp in milliseconds)
+    /// only if the key doesn't exist. Returns `true` if the key was set
+    pub fn set_nx_with_deadline(&self, key: Data, val: T, deadline: u64) -> EncodingResult<bool> {
+        self.check_key_encoding(&key)?;
+        val.verify_encoding(self.e_v)?;
+        self.expire_if_due(&key);
+        match self.data.fresh_entry(key.clone()) {
+            Some(entry) => {
+                // we hold the entry's lock, so no one can see the value without the deadline
+                self.index_insert(&key);
+                self.account_memory(0, Self::entry_estimate(&key, &val));
+                self.expiry.set(key, deadline);
+                entry.insert(val);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    /// Atomically swap the values of two existing keys. Returns if the first and the second
+    /// key exist respectively (nothing is swapped unless both do)
+    pub fn swap_values(&self, k1: &[u8], k2: &[u8]) -> EncodingResult<(bool, bool)> {
+        self.check_key_encoding(k1)?;
+        self.check_key_encoding(k2)?;
+        self.expire_if_due(k1);
+        self.expire_if_due(k2);
+        Ok(self.data.swap_values(k1, k2))
+    }
+    /// Atomically move a key and its value from this table into `dest`. Returns `true` if the
+    /// key was moved and `false` if it doesn't exist here or already exists in `dest`. This
+    /// fails (without moving anything) if the key or the value aren't valid in `dest` (this
+    /// includes JSON validation). The key's deadline (if any) is moved along with it
+    ///
+    /// The entry locks of both tables are always acquired in the order of the tables' addresses
+    /// so that two concurrent moves in opposite directions can never deadlock
+    pub fn move_to(&self, dest: &Self, key: &[u8]) -> EncodingResult<bool> {
+        self.check_key_encoding(key)?;
+        dest.check_key_encoding(key)?;
+        if ptr::eq(self, dest) {
+            // the key is either missing or it already exists in dest
+            return Ok(false);
+        }
+        self.expire_if_due(key);
+        dest.expire_if_due(key);
+        let owned_key = Data::copy_from_slice(key);
+        let (src_entry, dest_entry) = if (self as *const Self) < (dest as *const Self) {
+            let src_entry = self.data.mut_entry(owned_key.clone());
+            (src_entry, dest.data.fresh_entry(owned_key))
+        } else {
+            let dest_entry = dest.data.fresh_entry(owned_key.clone());
+            (self.data.mut_entry(owned_key), dest_entry)
+        };
+        match (src_entry, dest_entry) {
+            (Some(src_entry), Some(dest_entry)) => {
+                src_entry.value().verify_encoding(dest.e_v)?;
+                if dest.validate_json && !src_entry.value().is_json() {
+                    return Err(());
+                }
+                if let Some(deadline) = self.expiry.get(key) {
+                    self.expiry.remove(key);
+                    dest.expiry.set(Data::copy_from_slice(key), deadline);
+                }
+                let moved = Self::entry_estimate(key, src_entry.value());
+                self.account_memory(moved, 0);
+                dest.account_memory(0, moved);
+                self.index_remove(key);
+                dest.index_insert(dest_entry.key());
+                dest_entry.insert(src_entry.remove());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
     }
     /// Pop an entry
     pub fn pop<Q: AsRef<[u8]>>(&self, key: Q) -> EncodingResult<Option<T>> {
@@ -223,23 +727,57 @@ impl<T: KVEValue> KVEngine<T> {
     }
     /// Pop an entry without encoding checks
     pub fn pop_unchecked<Q: AsRef<[u8]>>(&self, key: Q) -> Option<T> {
-        self.data.remove(key.as_ref()).map(|(_, v)| v)
+        let key = key.as_ref();
+        if self.expire_if_due(key) {
+            return None;
+        }
+        self.data
+            .remove_if(key, |_, val| {
+                self.expiry.remove(key);
+                self.index_remove(key);
+                self.account_memory(Self::entry_estimate(key, val), 0);
+                true
+            })
+            .map(|(_, v)| v)
     }
 }
 
-impl<T: Clone> KVEngine<T> {
+impl<T: KVEValue + Clone> KVEngine<T> {
     pub fn get_cloned<Q: AsRef<[u8]>>(&self, key: Q) -> EncodingResult<Option<T>> {
         self.check_key_encoding(key.as_ref())?;
         Ok(self.get_cloned_unchecked(key.as_ref()))
     }
     pub fn get_cloned_unchecked<Q: AsRef<[u8]>>(&self, key: Q) -> Option<T> {
+        self.expire_if_due(key.as_ref());
         self.data.get_cloned(key.as_ref())
     }
+    /// Get the value of the given key and atomically set its deadline (a unix timestamp in
+    /// milliseconds), or remove it if `deadline` is `None`
+    pub fn get_cloned_and_set_deadline(
+        &self,
+        key: &[u8],
+        deadline: Option<u64>,
+    ) -> EncodingResult<Option<T>> {
+        self.check_key_encoding(key)?;
+        self.expire_if_due(key);
+        let entry = match self.data.mut_entry(Data::copy_from_slice(key)) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        // we hold the entry's lock, so the value and the deadline change together
+        match deadline {
+            Some(deadline) => self.expiry.set(Data::copy_from_slice(key), deadline),
+            None => {
+                self.expiry.remove(key);
+            }
+        }
+        Ok(Some(entry.value().clone()))
+    }
 }
 
 impl KVEStandard {
     pub fn take_snapshot_unchecked<Q: AsRef<[u8]>>(&self, key: Q) -> Option<Data> {
-        self.data.get_cloned(key.as_ref())
+        self.get_cloned_unchecked(key)
     }
     /// Returns an encoder that checks each key and each value in turn
     /// Usual usage:
@@ -251,6 +789,82 @@ impl KVEStandard {
     pub fn get_double_encoder(&self) -> DoubleEncoder {
         ENCODING_LUT_PAIR[(self.e_k, self.e_v)]
     }
+    /// Get the length of the value of the given key, without cloning the value
+    pub fn value_len<Q: AsRef<[u8]>>(&self, key: Q) -> EncodingResult<Option<usize>> {
+        Ok(self.get(key)?.map(|val| val.len()))
+    }
+    /// Update or insert an entry unless the key already holds the same bytes (in which case
+    /// nothing is written). If the key already exists, its deadline (if any) is left as is
+    pub fn upsert_if_changed(&self, key: Data, val: Data) -> EncodingResult<UpsertOutcome> {
+        self.check_key_encoding(&key)?;
+        val.verify_encoding(self.e_v)?;
+        self.expire_if_due(&key);
+        let new = Self::entry_estimate(&key, &val);
+        Ok(self.data.upsert_if_changed(key, val, |key, old| match old {
+            Some(old) => self.account_memory(Self::entry_estimate(key, old), new),
+            None => {
+                self.index_insert(key);
+                self.account_memory(0, new);
+            }
+        }))
+    }
+    /// Atomically add `delta` to the base-10 integer stored at the key (a missing key counts
+    /// as 0) and return the new value. If the key already exists, its deadline (if any) is
+    /// left as is
+    pub fn incr_by(&self, key: Data, delta: i64) -> Result<i64, IncrError> {
+        self.check_key_encoding(&key)
+            .map_err(|_| IncrError::Encoding)?;
+        self.expire_if_due(&key);
+        self.upsert_with_indexed(key, |current| {
+            let current = match current {
+                Some(val) => core::str::from_utf8(val)
+                    .ok()
+                    .and_then(|val| val.parse::<i64>().ok())
+                    .ok_or(IncrError::NotANumber)?,
+                None => 0,
+            };
+            let new = current.checked_add(delta).ok_or(IncrError::Overflow)?;
+            Ok((Data::from(new.to_string()), new))
+        })
+    }
+    /// Atomically append `suffix` to the value of the key (creating it if it doesn't exist)
+    /// and return the new length of the value. If the key already exists, its deadline (if
+    /// any) is left as is
+    pub fn append(&self, key: Data, suffix: &[u8]) -> Result<usize, AppendError> {
+        self.check_key_encoding(&key)
+            .map_err(|_| AppendError::Encoding)?;
+        self.check_value_encoding(suffix)
+            .map_err(|_| AppendError::Encoding)?;
+        self.expire_if_due(&key);
+        self.upsert_with_indexed(key, |current| {
+            let current: &[u8] = current.map_or(&[], |val| val.as_ref());
+            let mut new = BytesMut::with_capacity(current.len() + suffix.len());
+            new.extend_from_slice(current);
+            new.extend_from_slice(suffix);
+            self.check_json(&new).map_err(AppendError::Json)?;
+            let len = new.len();
+            Ok((Data::from(new), len))
+        })
+    }
+    /// Atomically add `delta` to the float stored at the key (a missing key counts as 0) and
+    /// return the new value. If the key already exists, its deadline (if any) is left as is
+    pub fn incr_by_float(&self, key: Data, delta: f64) -> Result<f64, IncrError> {
+        self.check_key_encoding(&key)
+            .map_err(|_| IncrError::Encoding)?;
+        self.expire_if_due(&key);
+        self.upsert_with_indexed(key, |current| {
+            let current = match current {
+                Some(val) => parse_finite_float(val).ok_or(IncrError::NotANumber)?,
+                None => 0.0,
+            };
+            let new = current + delta;
+            if new.is_finite() {
+                Ok((Data::from(format_float(new)), new))
+            } else {
+                Err(IncrError::Overflow)
+            }
+        })
+    }
 }
 
 // list impls
@@ -258,7 +872,7 @@ impl KVEListmap {
     #[cfg(test)]
     pub fn add_list(&self, listname: Data) -> EncodingResult<bool> {
         self.check_key_encoding(&listname)?;
-        Ok(self.data.true_if_insert(listname, LockedVec::new(vec![])))
+        Ok(self.set_unchecked(listname, LockedVec::new(vec![])))
     }
     pub fn list_len(&self, listname: &[u8]) -> EncodingResult<Option<usize>> {
         self.check_key_encoding(listname)?;
@@ -280,7 +894,7 @@ impl KVEListmap {
     }
 }
 
-impl<T> Default for KVEngine<T> {
+impl<T: KVEValue> Default for KVEngine<T> {
     fn default() -> Self {
         Self::init(false, false)
     }
@@ -24,7 +24,7 @@
  *
 */
 
-use super::{Data, KVEStandard};
+use super::{format_float, parse_finite_float, AppendError, Data, IncrError, KVEStandard};
 
 #[test]
 fn test_ignore_encoding() {
@@ -102,3 +102,678 @@ fn test_encoder_validate_with_non_unicode() {
     let encoder = tbl.get_double_encoder();
     assert!(!encoder("hello".as_bytes(), b"Hello \xF0\x90\x80World"));
 }
+
+#[test]
+fn test_move_to() {
+    let src = KVEStandard::default();
+    let dest = KVEStandard::default();
+    src.set(Data::from("x"), Data::from("100")).unwrap();
+    src.set(Data::from("y"), Data::from("200")).unwrap();
+    dest.set(Data::from("y"), Data::from("300")).unwrap();
+    assert!(src.move_to(&dest, b"x").unwrap());
+    assert!(!src.exists("x").unwrap());
+    assert_eq!(dest.get_cloned("x").unwrap().unwrap(), Data::from("100"));
+    // missing in the source
+    assert!(!src.move_to(&dest, b"x").unwrap());
+    // already exists in the destination
+    assert!(!src.move_to(&dest, b"y").unwrap());
+    assert_eq!(src.get_cloned("y").unwrap().unwrap(), Data::from("200"));
+    assert_eq!(dest.get_cloned("y").unwrap().unwrap(), Data::from("300"));
+    // moving within the same table does nothing
+    assert!(!src.move_to(&src, b"y").unwrap());
+}
+
+#[test]
+fn test_move_to_bad_encoding() {
+    let bad_unicode = b"Hello \xF0\x90\x80World".to_vec();
+    let src = KVEStandard::default();
+    let dest = KVEStandard::init(true, true);
+    src.set(Data::from("x"), Data::from(bad_unicode)).unwrap();
+    assert!(src.move_to(&dest, b"x").is_err());
+    // nothing was moved
+    assert!(src.exists("x").unwrap());
+    assert!(!dest.exists("x").unwrap());
+}
+
+#[test]
+fn test_memory_estimate() {
+    let tbl = KVEStandard::default();
+    assert_eq!(tbl.memory_estimate(), 0);
+    tbl.set(Data::from("x"), Data::from("100")).unwrap();
+    let one = tbl.memory_estimate();
+    assert!(one >= 4);
+    tbl.set(Data::from("y"), Data::from("a much larger value"))
+        .unwrap();
+    assert!(tbl.memory_estimate() > one);
+    // deletes free memory
+    tbl.remove("y").unwrap();
+    assert_eq!(tbl.memory_estimate(), one);
+}
+
+#[test]
+fn test_incr_by() {
+    let tbl = KVEStandard::default();
+    // missing keys count as 0
+    assert_eq!(tbl.incr_by(Data::from("x"), 1), Ok(1));
+    assert_eq!(tbl.incr_by(Data::from("x"), -3), Ok(-2));
+    assert_eq!(tbl.get_cloned("x").unwrap().unwrap(), Data::from("-2"));
+    tbl.set(Data::from("y"), Data::from("abc")).unwrap();
+    assert_eq!(tbl.incr_by(Data::from("y"), 1), Err(IncrError::NotANumber));
+    let max = i64::MAX.to_string();
+    tbl.set(Data::from("z"), Data::from(max.clone())).unwrap();
+    assert_eq!(tbl.incr_by(Data::from("z"), 1), Err(IncrError::Overflow));
+    // nothing is written if it fails
+    assert_eq!(tbl.get_cloned("z").unwrap().unwrap(), Data::from(max));
+}
+
+#[test]
+fn test_append() {
+    let tbl = KVEStandard::default();
+    // missing keys are created
+    assert_eq!(tbl.append(Data::from("x"), b"hello"), Ok(5));
+    assert_eq!(tbl.append(Data::from("x"), b", world"), Ok(12));
+    assert_eq!(
+        tbl.get_cloned("x").unwrap().unwrap(),
+        Data::from("hello, world")
+    );
+    let unicode = KVEStandard::init(true, true);
+    unicode.set(Data::from("x"), Data::from("hello")).unwrap();
+    assert_eq!(
+        unicode.append(Data::from("x"), b"\xF0\x99"),
+        Err(AppendError::Encoding)
+    );
+    assert_eq!(
+        unicode.get_cloned("x").unwrap().unwrap(),
+        Data::from("hello")
+    );
+}
+
+#[test]
+fn test_float_formatting() {
+    assert_eq!(format_float(10.5), "10.5");
+    assert_eq!(format_float(3.0), "3");
+    assert_eq!(format_float(-0.25), "-0.25");
+    assert_eq!(format_float(0.0), "0");
+    assert_eq!(format_float(5e21), "5e21");
+    assert_eq!(format_float(1.5e-7), "1.5e-7");
+    assert_eq!(parse_finite_float(b"10.5"), Some(10.5));
+    assert_eq!(parse_finite_float(b"-3e2"), Some(-300.0));
+    assert_eq!(parse_finite_float(b"nan"), None);
+    assert_eq!(parse_finite_float(b"inf"), None);
+    assert_eq!(parse_finite_float(b"abc"), None);
+}
+
+#[test]
+fn test_incr_by_float() {
+    let tbl = KVEStandard::default();
+    // missing keys count as 0
+    assert_eq!(tbl.incr_by_float(Data::from("x"), 10.5), Ok(10.5));
+    assert_eq!(tbl.incr_by_float(Data::from("x"), 0.5), Ok(11.0));
+    assert_eq!(tbl.get_cloned("x").unwrap().unwrap(), Data::from("11"));
+    // integers are valid floats
+    tbl.set(Data::from("y"), Data::from("5")).unwrap();
+    assert_eq!(tbl.incr_by_float(Data::from("y"), -0.25), Ok(4.75));
+    tbl.set(Data::from("z"), Data::from("abc")).unwrap();
+    assert_eq!(
+        tbl.incr_by_float(Data::from("z"), 1.0),
+        Err(IncrError::NotANumber)
+    );
+    tbl.set(Data::from("max"), Data::from(f64::MAX.to_string()))
+        .unwrap();
+    assert_eq!(
+        tbl.incr_by_float(Data::from("max"), f64::MAX),
+        Err(IncrError::Overflow)
+    );
+}
+
+mod json_validation {
+    use super::{AppendError, Data, KVEStandard};
+    use crate::kvengine::json::{self, JsonError, MAX_DEPTH};
+
+    fn err(offset: usize, reason: &'static str) -> Result<(), JsonError> {
+        Err(JsonError { offset, reason })
+    }
+
+    #[test]
+    fn test_valid_documents() {
+        let docs: [&[u8]; 10] = [
+            b"null",
+            b" true ",
+            b"-0.5e+10",
+            b"\"\"",
+            b"[]",
+            b"{}",
+            b"[1, \"two\", [3], {\"four\": 4}]",
+            b"{\"a\": {\"b\": [null, false, 1.25E-3]}, \"c\": \"\\\"\\n\\u00e9\"}",
+            "{\"emoji\": \"\\uD83D\\uDE00 ☕\"}".as_bytes(),
+            b"\r\n\t{ \"k\" : [ ] }\n",
+        ];
+        for doc in docs {
+            assert_eq!(
+                json::validate(doc),
+                Ok(()),
+                "{}",
+                String::from_utf8_lossy(doc)
+            );
+        }
+    }
+
+    #[test]
+    fn test_trailing_garbage() {
+        assert_eq!(json::validate(b"{} x"), err(3, "trailing characters"));
+        assert_eq!(json::validate(b"1 2"), err(2, "trailing characters"));
+    }
+
+    #[test]
+    fn test_unterminated() {
+        assert_eq!(json::validate(b""), err(0, "unexpected end of input"));
+        assert_eq!(json::validate(b"\"abc"), err(4, "unexpected end of input"));
+        assert_eq!(json::validate(b"[1, 2"), err(5, "unexpected end of input"));
+        assert_eq!(
+            json::validate(b"{\"a\":"),
+            err(5, "unexpected end of input")
+        );
+    }
+
+    #[test]
+    fn test_bad_escape() {
+        assert_eq!(json::validate(b"\"ab\\x\""), err(4, "invalid escape"));
+        assert_eq!(json::validate(b"\"\\u12G4\""), err(5, "invalid escape"));
+    }
+
+    #[test]
+    fn test_lone_surrogate() {
+        // the offset is that of the escape
+        assert_eq!(json::validate(b"[\"\\uD83D\"]"), err(2, "lone surrogate"));
+        assert_eq!(json::validate(b"\"x\\uDE00\""), err(2, "lone surrogate"));
+        assert_eq!(
+            json::validate(b"\"\\uD83D\\u0041\""),
+            err(1, "lone surrogate")
+        );
+    }
+
+    #[test]
+    fn test_structural_errors() {
+        assert_eq!(json::validate(b"[1,]"), err(3, "unexpected character"));
+        assert_eq!(
+            json::validate(b"[1 2]"),
+            err(3, "expected ',' or closing bracket")
+        );
+        assert_eq!(
+            json::validate(b"[1}"),
+            err(2, "expected ',' or closing bracket")
+        );
+        assert_eq!(json::validate(b"{1: 2}"), err(1, "expected string key"));
+        assert_eq!(json::validate(b"{\"a\" 2}"), err(5, "expected ':'"));
+        assert_eq!(json::validate(b"tru"), err(0, "invalid literal"));
+        assert_eq!(json::validate(b"01"), err(1, "trailing characters"));
+        assert_eq!(json::validate(b"-"), err(1, "invalid number"));
+        assert_eq!(json::validate(b"1."), err(2, "invalid number"));
+        assert_eq!(
+            json::validate(b"\"a\tb\""),
+            err(2, "control character in string")
+        );
+        assert_eq!(json::validate(b"\"ab\xFF\""), err(3, "invalid utf-8"));
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let okay = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert_eq!(json::validate(okay.as_bytes()), Ok(()));
+        let deep = "[".repeat(MAX_DEPTH + 1) + &"]".repeat(MAX_DEPTH + 1);
+        assert_eq!(
+            json::validate(deep.as_bytes()),
+            err(MAX_DEPTH, "nesting too deep")
+        );
+    }
+
+    #[test]
+    fn test_table_validation() {
+        let mut tbl = KVEStandard::default();
+        // no validation by default
+        assert!(tbl.check_json(b"not json").is_ok());
+        tbl.set_json_validation(true);
+        assert!(tbl.is_json_validated());
+        assert!(tbl.check_json(b"{\"a\": 1}").is_ok());
+        assert_eq!(
+            tbl.check_json(b"{\"a\": 1"),
+            err(7, "unexpected end of input")
+        );
+        let vals: [&[u8]; 3] = [b"1", b"[", b"x"];
+        // the first bad value is reported
+        assert_eq!(
+            tbl.check_json_all(vals.iter().copied()),
+            err(1, "unexpected end of input")
+        );
+    }
+
+    #[test]
+    fn test_append_to_json_table() {
+        let mut tbl = KVEStandard::default();
+        tbl.set_json_validation(true);
+        assert_eq!(
+            tbl.append(Data::from("x"), b"[1"),
+            Err(AppendError::Json(JsonError {
+                offset: 2,
+                reason: "unexpected end of input",
+            }))
+        );
+        assert!(!tbl.exists("x").unwrap());
+        // the new value is validated, not just the suffix
+        assert_eq!(tbl.append(Data::from("x"), b"1"), Ok(1));
+        assert_eq!(tbl.append(Data::from("x"), b"2"), Ok(2));
+        assert!(tbl.append(Data::from("x"), b"]").is_err());
+        assert_eq!(tbl.get_cloned("x").unwrap().unwrap(), Data::from("12"));
+    }
+
+    #[test]
+    fn test_move_to_json_table() {
+        let src = KVEStandard::default();
+        let mut dest = KVEStandard::default();
+        dest.set_json_validation(true);
+        src.set(Data::from("x"), Data::from("not json")).unwrap();
+        src.set(Data::from("y"), Data::from("[1, 2]")).unwrap();
+        assert!(src.move_to(&dest, b"x").is_err());
+        assert!(src.exists("x").unwrap());
+        assert!(src.move_to(&dest, b"y").unwrap());
+        assert!(dest.exists("y").unwrap());
+    }
+}
+
+mod expiry {
+    use super::{Data, KVEStandard};
+    use crate::kvengine::expiry::now_millis;
+
+    #[test]
+    fn test_set_nx_with_deadline() {
+        let tbl = KVEStandard::default();
+        let deadline = now_millis() + 60_000;
+        assert!(tbl
+            .set_nx_with_deadline(Data::from("x"), Data::from("100"), deadline)
+            .unwrap());
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), Some(deadline));
+        // exists, so nothing changes
+        assert!(!tbl
+            .set_nx_with_deadline(Data::from("x"), Data::from("200"), deadline + 1)
+            .unwrap());
+        assert_eq!(tbl.get_cloned("x").unwrap().unwrap(), Data::from("100"));
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), Some(deadline));
+    }
+
+    #[test]
+    fn test_expired_keys_are_removed_lazily() {
+        let tbl = KVEStandard::default();
+        // a deadline in the past
+        tbl.set_nx_with_deadline(Data::from("x"), Data::from("100"), now_millis() - 1)
+            .unwrap();
+        // the key is still in the table till someone looks at it
+        assert_eq!(tbl.len(), 1);
+        assert!(!tbl.exists("x").unwrap());
+        assert_eq!(tbl.len(), 0);
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), None);
+        // and an expired key can be set again
+        tbl.set_nx_with_deadline(Data::from("x"), Data::from("100"), now_millis() - 1)
+            .unwrap();
+        assert!(tbl
+            .set_nx_with_deadline(Data::from("x"), Data::from("200"), now_millis() + 60_000)
+            .unwrap());
+        assert_eq!(tbl.get_cloned("x").unwrap().unwrap(), Data::from("200"));
+    }
+
+    #[test]
+    fn test_remove_drops_deadline() {
+        let tbl = KVEStandard::default();
+        tbl.set_nx_with_deadline(Data::from("x"), Data::from("100"), now_millis() + 60_000)
+            .unwrap();
+        assert!(tbl.remove("x").unwrap());
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), None);
+        // a plain set doesn't inherit the old deadline
+        assert!(tbl.set(Data::from("x"), Data::from("200")).unwrap());
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), None);
+    }
+
+    #[test]
+    fn test_move_keeps_deadline() {
+        let src = KVEStandard::default();
+        let dest = KVEStandard::default();
+        let deadline = now_millis() + 60_000;
+        src.set_nx_with_deadline(Data::from("x"), Data::from("100"), deadline)
+            .unwrap();
+        assert!(src.move_to(&dest, b"x").unwrap());
+        assert_eq!(src.get_expiry_ref().get(b"x"), None);
+        assert_eq!(dest.get_expiry_ref().get(b"x"), Some(deadline));
+    }
+
+    #[test]
+    fn test_get_cloned_and_set_deadline() {
+        let tbl = KVEStandard::default();
+        assert_eq!(tbl.get_cloned_and_set_deadline(b"x", None).unwrap(), None);
+        tbl.set(Data::from("x"), Data::from("100")).unwrap();
+        let deadline = now_millis() + 60_000;
+        assert_eq!(
+            tbl.get_cloned_and_set_deadline(b"x", Some(deadline))
+                .unwrap()
+                .unwrap(),
+            Data::from("100")
+        );
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), Some(deadline));
+        // persist
+        assert!(tbl
+            .get_cloned_and_set_deadline(b"x", None)
+            .unwrap()
+            .is_some());
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), None);
+        // a deadline in the past expires the key on the next access
+        assert!(tbl
+            .get_cloned_and_set_deadline(b"x", Some(now_millis() - 1))
+            .unwrap()
+            .is_some());
+        assert!(!tbl.exists("x").unwrap());
+    }
+    #[test]
+    fn test_set_deadline_and_time_to_live() {
+        let tbl = KVEStandard::default();
+        // missing keys
+        assert!(!tbl.set_deadline(b"x", Some(now_millis() + 60_000)).unwrap());
+        assert_eq!(tbl.time_to_live(b"x").unwrap(), None);
+        tbl.set(Data::from("x"), Data::from("100")).unwrap();
+        assert_eq!(tbl.time_to_live(b"x").unwrap(), Some(None));
+        // nothing to persist
+        assert!(!tbl.set_deadline(b"x", None).unwrap());
+        assert!(tbl.set_deadline(b"x", Some(now_millis() + 60_000)).unwrap());
+        let ttl = tbl.time_to_live(b"x").unwrap().unwrap().unwrap();
+        assert!(ttl > 50_000 && ttl <= 60_000);
+        assert!(tbl.set_deadline(b"x", None).unwrap());
+        assert_eq!(tbl.time_to_live(b"x").unwrap(), Some(None));
+        // an expired key is gone
+        assert!(tbl.set_deadline(b"x", Some(now_millis() - 1)).unwrap());
+        assert_eq!(tbl.time_to_live(b"x").unwrap(), None);
+    }
+
+    #[test]
+    fn test_expired_keys_are_not_counted() {
+        let tbl = KVEStandard::default();
+        tbl.set(Data::from("x"), Data::from("100")).unwrap();
+        tbl.set_nx_with_deadline(Data::from("y"), Data::from("200"), now_millis() - 1)
+            .unwrap();
+        tbl.set_nx_with_deadline(Data::from("z"), Data::from("300"), now_millis() + 60_000)
+            .unwrap();
+        assert_eq!(tbl.len(), 3);
+        assert_eq!(tbl.live_len(), 2);
+        let keys = tbl.get_live_keys(10);
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.contains(&Data::from("y")));
+        assert_eq!(tbl.get_live_keys(1).len(), 1);
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let tbl = KVEStandard::default();
+        // nothing to do for tables without deadlines
+        tbl.set(Data::from("x"), Data::from("100")).unwrap();
+        assert_eq!(tbl.remove_expired(), 0);
+        tbl.set_nx_with_deadline(Data::from("y"), Data::from("200"), now_millis() - 1)
+            .unwrap();
+        tbl.set_nx_with_deadline(Data::from("z"), Data::from("300"), now_millis() + 60_000)
+            .unwrap();
+        assert_eq!(tbl.remove_expired(), 1);
+        assert_eq!(tbl.len(), 2);
+        assert_eq!(tbl.get_expiry_ref().get(b"y"), None);
+        assert!(tbl.get_expiry_ref().get(b"z").is_some());
+    }
+
+    #[test]
+    fn test_truncate_drops_deadlines() {
+        let tbl = KVEStandard::default();
+        tbl.set_nx_with_deadline(Data::from("x"), Data::from("100"), now_millis() + 60_000)
+            .unwrap();
+        tbl.set(Data::from("y"), Data::from("200")).unwrap();
+        tbl.truncate_table();
+        assert_eq!(tbl.len(), 0);
+        assert_eq!(tbl.get_expiry_ref().get_inner_ref().len(), 0);
+        // keys set after the truncate don't inherit the old deadlines, and keep their own
+        assert!(tbl.set(Data::from("x"), Data::from("100")).unwrap());
+        assert_eq!(tbl.get_expiry_ref().get(b"x"), None);
+        let deadline = now_millis() + 60_000;
+        assert!(tbl
+            .set_nx_with_deadline(Data::from("y"), Data::from("200"), deadline)
+            .unwrap());
+        assert_eq!(tbl.get_expiry_ref().get(b"y"), Some(deadline));
+    }
+}
+
+mod ordered_index {
+    use super::{Data, KVEStandard};
+    use crate::kvengine::expiry::now_millis;
+
+    fn ordered_table() -> KVEStandard {
+        let mut tbl = KVEStandard::init(true, true);
+        tbl.set_ordered(true);
+        tbl
+    }
+
+    /// Returns the keys in the index, which must be the same as the keys in the table
+    fn indexed_keys(tbl: &KVEStandard) -> Vec<Data> {
+        let index: Vec<Data> = tbl
+            .ordered
+            .as_ref()
+            .unwrap()
+            .read()
+            .iter()
+            .cloned()
+            .collect();
+        let mut keys = tbl.get_inner_ref().get_keys(tbl.len());
+        keys.sort();
+        assert_eq!(index, keys);
+        index
+    }
+
+    #[test]
+    fn test_key_range() {
+        let tbl = ordered_table();
+        for key in ["2022-03", "2022-01", "2021-12", "2022-02", "2023-01"] {
+            tbl.set(Data::from(key), Data::from("1")).unwrap();
+        }
+        assert_eq!(
+            tbl.key_range(b"2022-01", b"2022-12", usize::MAX).unwrap(),
+            vec![
+                Data::from("2022-01"),
+                Data::from("2022-02"),
+                Data::from("2022-03")
+            ]
+        );
+        // both ends are inclusive
+        assert_eq!(
+            tbl.key_range(b"2021-12", b"2022-01", usize::MAX).unwrap(),
+            vec![Data::from("2021-12"), Data::from("2022-01")]
+        );
+        assert_eq!(
+            tbl.key_range(b"2022", b"2023", 2).unwrap(),
+            vec![Data::from("2022-01"), Data::from("2022-02")]
+        );
+        // an empty or a reversed range
+        assert!(tbl.key_range(b"2024", b"2025", 10).unwrap().is_empty());
+        assert!(tbl.key_range(b"2023", b"2022", 10).unwrap().is_empty());
+        // the keys need to be unicode
+        assert!(tbl.key_range(b"Hello \xF0\x90\x80World", b"z", 10).is_err());
+        // tables without an index have nothing to return
+        let tbl = KVEStandard::init(true, true);
+        tbl.set(Data::from("x"), Data::from("1")).unwrap();
+        assert!(!tbl.is_ordered());
+        assert!(tbl.key_range(b"a", b"z", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_index_follows_writes() {
+        let tbl = ordered_table();
+        tbl.set(Data::from("b"), Data::from("1")).unwrap();
+        tbl.set(Data::from("a"), Data::from("1")).unwrap();
+        // already exists
+        tbl.set(Data::from("a"), Data::from("2")).unwrap();
+        tbl.upsert(Data::from("c"), Data::from("1")).unwrap();
+        tbl.upsert(Data::from("c"), Data::from("2")).unwrap();
+        tbl.update(Data::from("d"), Data::from("1")).unwrap();
+        tbl.get_and_upsert(Data::from("e"), Data::from("1"))
+            .unwrap();
+        tbl.upsert_if_changed(Data::from("f"), Data::from("1"))
+            .unwrap();
+        tbl.incr_by(Data::from("g"), 1).unwrap();
+        tbl.incr_by_float(Data::from("h"), 1.5).unwrap();
+        tbl.append(Data::from("i"), b"1").unwrap();
+        // failed writes don't leave anything behind
+        tbl.incr_by(Data::from("j"), i64::MIN).unwrap();
+        tbl.incr_by(Data::from("j"), -1).unwrap_err();
+        tbl.remove("j").unwrap();
+        assert_eq!(
+            indexed_keys(&tbl),
+            ["a", "b", "c", "e", "f", "g", "h", "i"]
+                .into_iter()
+                .map(Data::from)
+                .collect::<Vec<_>>()
+        );
+        tbl.remove("b").unwrap();
+        tbl.remove_if_unchecked("c", |val| val == "1");
+        tbl.pop("e").unwrap();
+        assert_eq!(
+            indexed_keys(&tbl),
+            ["a", "c", "f", "g", "h", "i"]
+                .into_iter()
+                .map(Data::from)
+                .collect::<Vec<_>>()
+        );
+        tbl.truncate_table();
+        assert!(indexed_keys(&tbl).is_empty());
+        tbl.set(Data::from("x"), Data::from("1")).unwrap();
+        assert_eq!(indexed_keys(&tbl), vec![Data::from("x")]);
+    }
+
+    #[test]
+    fn test_index_follows_moves() {
+        let src = ordered_table();
+        let dest = ordered_table();
+        src.set(Data::from("x"), Data::from("1")).unwrap();
+        assert!(src.move_to(&dest, b"x").unwrap());
+        assert!(indexed_keys(&src).is_empty());
+        assert_eq!(indexed_keys(&dest), vec![Data::from("x")]);
+        // an unordered destination
+        let unordered = KVEStandard::init(true, true);
+        assert!(dest.move_to(&unordered, b"x").unwrap());
+        assert!(indexed_keys(&dest).is_empty());
+    }
+
+    #[test]
+    fn test_index_follows_expiry() {
+        let tbl = ordered_table();
+        tbl.set_nx_with_deadline(Data::from("x"), Data::from("1"), now_millis() - 1)
+            .unwrap();
+        tbl.set_nx_with_deadline(Data::from("y"), Data::from("1"), now_millis() + 60_000)
+            .unwrap();
+        // expired keys are skipped even before they're removed
+        assert_eq!(indexed_keys(&tbl).len(), 2);
+        assert_eq!(
+            tbl.key_range(b"a", b"z", 10).unwrap(),
+            vec![Data::from("y")]
+        );
+        assert_eq!(tbl.key_range(b"a", b"z", 1).unwrap(), vec![Data::from("y")]);
+        assert_eq!(tbl.remove_expired(), 1);
+        assert_eq!(indexed_keys(&tbl), vec![Data::from("y")]);
+    }
+
+    #[test]
+    fn test_index_is_built_from_existing_keys() {
+        let mut tbl = KVEStandard::init(true, true);
+        tbl.set(Data::from("b"), Data::from("1")).unwrap();
+        tbl.set(Data::from("a"), Data::from("1")).unwrap();
+        tbl.set_ordered(true);
+        assert_eq!(
+            tbl.key_range(b"a", b"z", 10).unwrap(),
+            vec![Data::from("a"), Data::from("b")]
+        );
+    }
+}
+
+mod memory_accounting {
+    use super::{Data, KVEStandard};
+    use crate::corestore::htable::Coremap;
+    use crate::kvengine::{expiry::now_millis, KVEListmap, KVEValue, KVEngine, LockedVec};
+
+    /// Check that the estimate kept up to date by the writes is the same as the one we get by
+    /// walking all the entries
+    fn assert_accounted<T: KVEValue>(tbl: &KVEngine<T>) {
+        assert_eq!(
+            tbl.memory_estimate(),
+            KVEngine::<T>::compute_memory_estimate(&tbl.data)
+        );
+    }
+
+    #[test]
+    fn test_writes_are_accounted() {
+        let tbl = KVEStandard::default();
+        tbl.set(Data::from("x"), Data::from("100")).unwrap();
+        tbl.update(Data::from("x"), Data::from("a much larger value"))
+            .unwrap();
+        tbl.upsert(Data::from("y"), Data::from("1")).unwrap();
+        tbl.upsert(Data::from("y"), Data::from("12")).unwrap();
+        tbl.get_and_upsert(Data::from("y"), Data::from("123"))
+            .unwrap();
+        tbl.upsert_if_changed(Data::from("z"), Data::from("1"))
+            .unwrap();
+        tbl.upsert_if_changed(Data::from("z"), Data::from("1234"))
+            .unwrap();
+        tbl.incr_by(Data::from("n"), 100).unwrap();
+        tbl.incr_by_float(Data::from("n"), 0.5).unwrap();
+        tbl.append(Data::from("a"), b"hello").unwrap();
+        tbl.append(Data::from("a"), b" world").unwrap();
+        assert_accounted(&tbl);
+        assert!(tbl.memory_estimate() > 0);
+        tbl.remove("x").unwrap();
+        tbl.pop("y").unwrap();
+        assert!(!tbl.update_if_unchecked(Data::from("z"), Data::from("2"), |val| val.is_empty()));
+        assert!(tbl.update_if_unchecked(Data::from("z"), Data::from("2"), |val| !val.is_empty()));
+        assert_accounted(&tbl);
+        tbl.truncate_table();
+        assert_eq!(tbl.memory_estimate(), 0);
+        assert_accounted(&tbl);
+    }
+
+    #[test]
+    fn test_moves_and_expiry_are_accounted() {
+        let (src, dest) = (KVEStandard::default(), KVEStandard::default());
+        src.set(Data::from("x"), Data::from("100")).unwrap();
+        let one = src.memory_estimate();
+        assert!(src.move_to(&dest, b"x").unwrap());
+        assert_eq!(src.memory_estimate(), 0);
+        assert_eq!(dest.memory_estimate(), one);
+        dest.set_nx_with_deadline(Data::from("y"), Data::from("100"), now_millis() - 1)
+            .unwrap();
+        assert_eq!(dest.memory_estimate(), one * 2);
+        assert_eq!(dest.remove_expired(), 1);
+        assert_eq!(dest.memory_estimate(), one);
+        assert_accounted(&src);
+        assert_accounted(&dest);
+    }
+
+    #[test]
+    fn test_existing_data_is_accounted() {
+        let data = Coremap::new();
+        data.upsert(Data::from("x"), Data::from("100"));
+        data.upsert(Data::from("y"), Data::from("a much larger value"));
+        let tbl = KVEStandard::new(false, false, data);
+        assert!(tbl.memory_estimate() > 0);
+        assert_accounted(&tbl);
+    }
+
+    #[test]
+    fn test_lists_are_accounted() {
+        let tbl = KVEListmap::default();
+        tbl.add_list(Data::from("empty")).unwrap();
+        tbl.set(
+            Data::from("mylist"),
+            LockedVec::new(vec![Data::from("a"), Data::from("bc")]),
+        )
+        .unwrap();
+        assert_accounted(&tbl);
+        tbl.remove("mylist").unwrap();
+        assert_accounted(&tbl);
+    }
+}
@@ -99,11 +99,23 @@ fn main() {
         .enable_all()
         .build()
         .unwrap();
-    let (cfg, restore_file) = check_args_and_get_cfg();
+    let (cfg, restore_file, migrate) = check_args_and_get_cfg();
     // check if any other process is using the data directory and lock it if not (else error)
     // important: create the pid_file just here and nowhere else because check_args can also
     // involve passing --help or wrong arguments which can falsely create a PID file
     let pid_file = run_pre_startup_tasks();
+    if migrate {
+        // offline migration; we don't serve anything
+        drop(runtime);
+        let okay = services::run_migration();
+        let okay = services::pre_shutdown_cleanup(pid_file, None) && okay;
+        {
+            // remove this file in debug builds for harness to pick it up
+            #[cfg(debug_assertions)]
+            std::fs::remove_file(PID_FILE_PATH).unwrap();
+        }
+        process::exit(!okay as i32);
+    }
     let db = runtime.block_on(async move { arbiter::run(cfg, restore_file).await });
     // Make sure all background workers terminate
     drop(runtime);
@@ -129,7 +141,7 @@ use self::config::ConfigurationSet;
 
 /// This function checks the command line arguments and either returns a config object
 /// or prints an error to `stderr` and terminates the server
-fn check_args_and_get_cfg() -> (ConfigurationSet, Option<String>) {
+fn check_args_and_get_cfg() -> (ConfigurationSet, Option<String>, bool) {
     match config::get_config() {
         Ok(cfg) => {
             if cfg.is_artful() {
@@ -144,7 +156,9 @@ fn check_args_and_get_cfg() -> (ConfigurationSet, Option<String>) {
             }
             // print warnings if any
             cfg.print_warnings();
-            cfg.finish()
+            let migrate = cfg.is_migrate();
+            let (cfg, restore_file) = cfg.finish();
+            (cfg, restore_file, migrate)
         }
         Err(e) => {
             log::error!("{}", e);
@@ -25,7 +25,7 @@
 */
 
 use crate::corestore::heap_array::HeapArray;
-use core::{fmt, marker::PhantomData, mem::transmute, slice};
+use core::{fmt, marker::PhantomData, slice};
 #[cfg(feature = "nightly")]
 mod benches;
 #[cfg(test)]
@@ -39,6 +39,8 @@ pub mod responses;
 pub const PROTOCOL_VERSION: f32 = 2.0;
 /// The Skyhash protocol version string (Skyhash-x.y)
 pub const PROTOCOL_VERSIONSTRING: &str = "Skyhash-2.0";
+/// The default maximum number of queries in a pipeline
+pub const DEFAULT_MAX_PIPELINE_LENGTH: usize = 1024;
 
 #[derive(PartialEq)]
 /// As its name says, an [`UnsafeSlice`] is a terribly unsafe slice. It's guarantess are
@@ -86,7 +88,6 @@ pub enum ParseError {
     /// Didn't get the number of expected bytes
     NotEnough = 0u8,
     /// The packet simply contains invalid data
-    #[allow(dead_code)] // HACK(@ohsayan): rustc can't "guess" the transmutation
     BadPacket = 1u8,
     /// The query contains an unexpected byte
     UnexpectedByte = 2u8,
@@ -94,11 +95,30 @@ pub enum ParseError {
     ///
     /// This can happen not just for elements but can also happen for their sizes ([`Self::parse_into_u64`])
     DatatypeParseFailure = 3u8,
+    /// The query has more elements (or a pipeline has more queries) than allowed
+    TooLarge = 4u8,
 }
 
 /// A generic result to indicate parsing errors thorugh the [`ParseError`] enum
 pub type ParseResult<T> = Result<T, ParseError>;
 
+#[derive(Debug, PartialEq)]
+/// A [`ParseError`] along with the offset (in the buffer) at which parsing failed
+pub struct ParseFailure {
+    pub error: ParseError,
+    pub offset: usize,
+}
+
+impl ParseFailure {
+    /// Returns (at most) 16 bytes of `buf` around the offset, escaped so that they can be
+    /// logged
+    pub fn context(&self, buf: &[u8]) -> String {
+        let start = self.offset.saturating_sub(8).min(buf.len());
+        let end = (start + 16).min(buf.len());
+        buf[start..end].escape_ascii().to_string()
+    }
+}
+
 #[derive(Debug)]
 pub enum Query {
     Simple(SimpleQuery),
@@ -168,16 +188,25 @@ struct OwnedPipelinedQuery {
 pub struct Parser<'a> {
     end: *const u8,
     cursor: *const u8,
+    /// the maximum number of queries in a pipeline
+    max_pipeline_length: usize,
     _lt: PhantomData<&'a ()>,
 }
 
 impl<'a> Parser<'a> {
+    #[cfg(test)]
     /// Initialize a new parser
     pub fn new(slice: &[u8]) -> Self {
+        Self::with_limits(slice, usize::MAX)
+    }
+    /// Initialize a new parser that rejects pipelines with more than `max_pipeline_length`
+    /// queries
+    pub fn with_limits(slice: &[u8], max_pipeline_length: usize) -> Self {
         unsafe {
             Self {
                 end: slice.as_ptr().add(slice.len()),
                 cursor: slice.as_ptr(),
+                max_pipeline_length,
                 _lt: PhantomData,
             }
         }
@@ -275,13 +304,16 @@ impl<'a> Parser<'a> {
             if has_lf && len != 0 {
                 self.incr_cursor(); // skip LF
                 Ok(UnsafeSlice::new(start_ptr, len))
+            } else if has_lf {
+                // an empty line
+                Err(ParseError::BadPacket)
             } else {
-                // just some silly hackery
-                Err(transmute(has_lf))
+                Err(ParseError::NotEnough)
             }
         }
     }
-    /// Attempt to read an `usize` from the buffer
+    /// Attempt to read an `usize` from the buffer. On failure, the cursor is moved back to
+    /// the offending byte
     fn read_usize(&mut self) -> ParseResult<usize> {
         let line = self.read_line_pedantic()?;
         let bytes = unsafe {
@@ -289,22 +321,42 @@ impl<'a> Parser<'a> {
             line.as_slice()
         };
         let mut ret = 0usize;
-        for byte in bytes {
-            if byte.is_ascii_digit() {
-                ret = match ret.checked_mul(10) {
-                    Some(r) => r,
-                    None => return Err(ParseError::DatatypeParseFailure),
-                };
-                ret = match ret.checked_add((byte & 0x0F) as _) {
-                    Some(r) => r,
-                    None => return Err(ParseError::DatatypeParseFailure),
-                };
+        for (i, byte) in bytes.iter().enumerate() {
+            let digit = if byte.is_ascii_digit() {
+                ret.checked_mul(10)
+                    .and_then(|r| r.checked_add((byte & 0x0F) as _))
             } else {
-                return Err(ParseError::DatatypeParseFailure);
+                None
+            };
+            match digit {
+                Some(r) => ret = r,
+                None => {
+                    self.cursor = bytes[i..].as_ptr();
+                    return Err(ParseError::DatatypeParseFailure);
+                }
             }
         }
         Ok(ret)
     }
+    /// Attempt to read the number of elements (or queries) that follow, rejecting empty
+    /// queries (and pipelines) and pipelines with more than `max_pipeline_length` queries.
+    ///
+    /// Every element (and query) takes at least two bytes, so if the rest of the buffer can't
+    /// hold `count` of them, we wait for more data **before** anything is allocated for them.
+    /// This way, the number of elements in a simple query is only capped by the maximum
+    /// query size
+    fn read_element_count(&mut self, is_pipeline: bool) -> ParseResult<usize> {
+        let count = self.read_usize()?;
+        if count == 0 {
+            Err(ParseError::BadPacket)
+        } else if is_pipeline && count > self.max_pipeline_length {
+            Err(ParseError::TooLarge)
+        } else if count > self.remaining() / 2 {
+            Err(ParseError::NotEnough)
+        } else {
+            Ok(count)
+        }
+    }
 }
 
 // query impls
@@ -322,7 +374,7 @@ impl<'a> Parser<'a> {
     /// ...
     /// ```
     fn _next_simple_query(&mut self) -> ParseResult<HeapArray<UnsafeSlice>> {
-        let element_count = self.read_usize()?;
+        let element_count = self.read_element_count(false)?;
         unsafe {
             let mut data = HeapArray::new_writer(element_count);
             for i in 0..element_count {
@@ -378,7 +430,7 @@ impl<'a> Parser<'a> {
     /// x    -> Q2E2 itself
     /// ```
     fn next_pipeline(&mut self) -> ParseResult<PipelinedQuery> {
-        let query_count = self.read_usize()?;
+        let query_count = self.read_element_count(true)?;
         unsafe {
             let mut queries = HeapArray::new_writer(query_count);
             for i in 0..query_count {
@@ -393,17 +445,18 @@ impl<'a> Parser<'a> {
     fn _parse(&mut self) -> ParseResult<Query> {
         if self.not_exhausted() {
             unsafe {
-                let first_byte = self.get_byte_at_cursor();
-                self.incr_cursor();
-                let data = match first_byte {
+                let data = match self.get_byte_at_cursor() {
                     b'*' => {
                         // a simple query
+                        self.incr_cursor();
                         Query::Simple(self.next_simple_query()?)
                     }
                     b'$' => {
                         // a pipelined query
+                        self.incr_cursor();
                         Query::Pipelined(self.next_pipeline()?)
                     }
+                    // leave the cursor at the unexpected byte
                     _ => return Err(ParseError::UnexpectedByte),
                 };
                 Ok(data)
@@ -412,10 +465,23 @@ impl<'a> Parser<'a> {
             Err(ParseError::NotEnough)
         }
     }
+    #[cfg(test)]
+    /// Parse a query without any limits
     pub fn parse(buf: &[u8]) -> ParseResult<(Query, usize)> {
-        let mut slf = Self::new(buf);
-        let body = slf._parse()?;
-        let consumed = slf.cursor_ptr() as usize - buf.as_ptr() as usize;
-        Ok((body, consumed))
+        Self::parse_with_limits(buf, usize::MAX).map_err(|failure| failure.error)
+    }
+    /// Parse a query, rejecting pipelines with more than `max_pipeline_length` queries. On
+    /// failure, the offset at which parsing failed is returned along with the error
+    pub fn parse_with_limits(
+        buf: &[u8],
+        max_pipeline_length: usize,
+    ) -> Result<(Query, usize), ParseFailure> {
+        let mut slf = Self::with_limits(buf, max_pipeline_length);
+        let ret = slf._parse();
+        let offset = slf.cursor_ptr() as usize - buf.as_ptr() as usize;
+        match ret {
+            Ok(body) => Ok((body, offset)),
+            Err(error) => Err(ParseFailure { error, offset }),
+        }
     }
 }
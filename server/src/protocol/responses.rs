@@ -76,6 +76,10 @@ pub mod groups {
     pub const STILL_IN_USE: &[u8] = eresp!("still-in-use");
     /// This is a protected object and hence cannot be accessed
     pub const PROTECTED_OBJECT: &[u8] = eresp!("err-protected-object");
+    /// The table is too large to be flushed without `force`
+    pub const PROTECTED: &[u8] = eresp!("err-protected-flush");
+    /// The result of an arithmetic operation doesn't fit in the integer type
+    pub const OVERFLOW_ERR: &[u8] = eresp!("err-overflow");
     /// The action was applied against the wrong model
     pub const WRONG_MODEL: &[u8] = eresp!("wrong-model");
     /// The container already exists
@@ -108,6 +112,28 @@ pub mod groups {
     pub const LISTMAP_BAD_INDEX: &[u8] = eresp!("bad-list-index");
     /// The list is empty
     pub const LISTMAP_LIST_IS_EMPTY: &[u8] = eresp!("list-is-empty");
+    /// The first key of a key pair was not found
+    pub const FIRST_KEY_NOT_FOUND: &[u8] = eresp!("first-key-not-found");
+    /// The second key of a key pair was not found
+    pub const SECOND_KEY_NOT_FOUND: &[u8] = eresp!("second-key-not-found");
+    /// Both keys of a key pair were not found
+    pub const BOTH_KEYS_NOT_FOUND: &[u8] = eresp!("both-keys-not-found");
+    /// The expiry time is not a positive integer (or is too far out)
+    pub const INVALID_EXPIRE_TIME: &[u8] = eresp!("err-invalid-expire-time");
+    /// An existing key was overwritten with a different value
+    pub const UPDATED: &[u8] = eresp!("updated");
+    /// The key already had the same value, so nothing was written
+    pub const UNCHANGED: &[u8] = eresp!("unchanged");
+    /// The table doesn't keep an ordered index of its keys
+    pub const UNORDERED_TABLE: &[u8] = eresp!("err-unordered-table");
+    /// The destination table is in another keyspace
+    pub const CROSS_KEYSPACE: &[u8] = eresp!("err-cross-keyspace");
+
+    /// Returns true if `group` is a response code that reports a failure, which is every
+    /// response code other than `OKAY`, `NIL`, `UPDATED` and `UNCHANGED`
+    pub fn is_error(group: &[u8]) -> bool {
+        group.first() == Some(&b'!') && ![OKAY, NIL, UPDATED, UNCHANGED].contains(&group)
+    }
 }
 
 pub mod full_responses {
@@ -150,4 +176,6 @@ pub mod full_responses {
     pub const R_SNAPSHOT_ILLEGAL_NAME: &[u8] = "*!25\nerr-invalid-snapshot-name\n".as_bytes();
     /// Access after termination signal (other error)
     pub const R_ERR_ACCESS_AFTER_TERMSIG: &[u8] = "*!24\nerr-access-after-termsig\n".as_bytes();
+    /// The query has too many elements or the pipeline has too many queries (other error)
+    pub const R_QUERY_TOO_LARGE: &[u8] = "*!19\nerr-query-too-large\n".as_bytes();
 }
@@ -25,7 +25,7 @@
 */
 
 use super::{Parser, PipelinedQuery, Query, SimpleQuery};
-use crate::protocol::ParseError;
+use crate::protocol::{ParseError, ParseFailure};
 use std::iter::Map;
 use std::vec::IntoIter as VecIntoIter;
 
@@ -641,3 +641,117 @@ fn pipelined_query_fail_because_not_enough() {
         assert_eq!(ret, ParseError::NotEnough)
     }
 }
+
+#[test]
+fn pipelined_query_fail_because_too_large() {
+    let ret = Parser::parse_with_limits(b"$1025\n", 1024)
+        .unwrap_err()
+        .error;
+    assert_eq!(ret, ParseError::TooLarge);
+    // at the limit, we just wait for more data
+    let ret = Parser::parse_with_limits(b"$1024\n", 1024)
+        .unwrap_err()
+        .error;
+    assert_eq!(ret, ParseError::NotEnough);
+}
+
+#[test]
+fn simple_query_okay_above_pipeline_limit() {
+    // the pipeline limit doesn't apply to the elements of a simple query
+    let mut buf = b"*2001\n3\nDEL".to_vec();
+    for i in 0..2000 {
+        let key = i.to_string();
+        buf.extend(format!("{}\n{key}", key.len()).into_bytes());
+    }
+    let (ret, skip) = Parser::parse_with_limits(&buf, 1024).unwrap();
+    assert_eq!(skip, buf.len());
+    let data = simple_query(ret).into_owned().data;
+    assert_eq!(data.len(), 2001);
+    assert_eq!(data[2000], b"1999".to_vec());
+}
+
+#[test]
+fn simple_query_fail_because_count_doesnt_fit() {
+    // a count that the buffer can't possibly hold makes us wait for more data (which is
+    // capped by the maximum query size) instead of allocating for it
+    let ret = Parser::parse_with_limits(b"*18446744073709551615\n3\nGET1\nx", 1024)
+        .unwrap_err()
+        .error;
+    assert_eq!(ret, ParseError::NotEnough);
+    let ret = Parser::parse_with_limits(b"$18446744073709551615\n2\n3\nGET1\nx", usize::MAX)
+        .unwrap_err()
+        .error;
+    assert_eq!(ret, ParseError::NotEnough);
+}
+
+#[test]
+fn parse_failure_offset_unexpected_byte() {
+    let ret = Parser::parse_with_limits(b"#2\n3\nGET1\nx", 1024).unwrap_err();
+    assert_eq!(
+        ret,
+        ParseFailure {
+            error: ParseError::UnexpectedByte,
+            offset: 0
+        }
+    );
+}
+
+#[test]
+fn parse_failure_offset_datatype() {
+    // the element length has a bad byte
+    let buf = b"*2\n3\nGET1x\nx";
+    let ret = Parser::parse_with_limits(buf, 1024).unwrap_err();
+    assert_eq!(
+        ret,
+        ParseFailure {
+            error: ParseError::DatatypeParseFailure,
+            offset: 9
+        }
+    );
+    assert_eq!(buf[ret.offset], b'x');
+}
+
+#[test]
+fn parse_failure_offset_bad_packet() {
+    // an empty element length
+    let ret = Parser::parse_with_limits(b"*2\n3\nGET\n", 1024).unwrap_err();
+    assert_eq!(
+        ret,
+        ParseFailure {
+            error: ParseError::BadPacket,
+            offset: 8
+        }
+    );
+}
+
+#[test]
+fn parse_failure_context() {
+    let buf = b"*1\n17\nsome\x00long\nelement$";
+    let failure = ParseFailure {
+        error: ParseError::UnexpectedByte,
+        offset: 12,
+    };
+    assert_eq!(failure.context(buf), "7\\nsome\\x00long\\nelem");
+    let failure = ParseFailure {
+        error: ParseError::UnexpectedByte,
+        offset: 0,
+    };
+    assert_eq!(failure.context(b"#\n"), "#\\n");
+}
+
+#[test]
+fn simple_query_fail_because_empty() {
+    let ret = Parser::parse(b"*0\n").unwrap_err();
+    assert_eq!(ret, ParseError::BadPacket);
+}
+
+#[test]
+fn pipelined_query_fail_because_empty() {
+    let ret = Parser::parse(b"$0\n").unwrap_err();
+    assert_eq!(ret, ParseError::BadPacket);
+    // an empty query within the pipeline
+    let ret = Parser::parse(b"$1\n0\n").unwrap_err();
+    assert_eq!(ret, ParseError::BadPacket);
+    let ret = Parser::parse(b"$2\n2\n3\nGET1\nx0\n").unwrap_err();
+    assert_eq!(ret, ParseError::BadPacket);
+}
@@ -35,8 +35,57 @@ use core::str;
 pub const TABLE: &[u8] = "TABLE".as_bytes();
 pub const KEYSPACE: &[u8] = "KEYSPACE".as_bytes();
 const VOLATILE: &[u8] = "volatile".as_bytes();
+const PERSISTENT: &[u8] = "persistent".as_bytes();
+const VALIDATE_JSON: &[u8] = "validate:json".as_bytes();
+const ORDERED: &[u8] = "ordered".as_bytes();
 const FORCE_REMOVE: &[u8] = "force".as_bytes();
 
+pub const ALTER_DOCS: CommandDoc = CommandDoc {
+    name: "ALTER",
+    summary: "Change the persistence mode of a table",
+    since: "0.8.0",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("table", ArgumentType::Token, false),
+        ArgumentDoc::new("entity", ArgumentType::Entity, false),
+        ArgumentDoc::new("volatile|persistent", ArgumentType::Token, false),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+pub const CREATE_DOCS: CommandDoc = CommandDoc {
+    name: "CREATE",
+    summary: "Create a keyspace or a table",
+    since: "0.7.0",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("keyspace|table", ArgumentType::Token, false),
+        ArgumentDoc::new("entity", ArgumentType::Entity, false),
+        ArgumentDoc::new("model", ArgumentType::String, true),
+        ArgumentDoc::new("properties ...", ArgumentType::String, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
+pub const DROP_DOCS: CommandDoc = CommandDoc {
+    name: "DROP",
+    summary: "Drop a keyspace or a table",
+    since: "0.7.0",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[
+        ArgumentDoc::new("keyspace|table", ArgumentType::Token, false),
+        ArgumentDoc::new("entity", ArgumentType::Entity, false),
+        ArgumentDoc::new("force", ArgumentType::Token, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action! {
     /// Handle `create table <tableid> <model>(args)` and `create keyspace <ksid>`
     /// like queries
@@ -74,21 +123,53 @@ action! {
         Ok(())
     }
 
+    /// Handle `alter table <tableid> volatile|persistent` like queries
+    fn alter(handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
+        ensure_length(act.len(), |size| size == 3)?;
+        let mut alter_what = unsafe { act.next().unsafe_unwrap() }.to_vec();
+        alter_what.make_ascii_uppercase();
+        if alter_what != TABLE {
+            return util::err(responses::groups::UNKNOWN_DDL_QUERY);
+        }
+        let (entity, mode) = unsafe { (act.next_unchecked(), act.next_unchecked()) };
+        let entity = parser::Entity::from_slice(entity)?;
+        let volatile = match mode {
+            VOLATILE => true,
+            PERSISTENT => false,
+            _ => return util::err(responses::groups::UNKNOWN_PROPERTY),
+        };
+        if registry::state_okay() {
+            handle.alter_table_persistence(entity, volatile)?;
+            con.write_response(responses::groups::OKAY).await?;
+        } else {
+            conwrite!(con, responses::groups::SERVER_ERR)?;
+        }
+        Ok(())
+    }
+
     /// We should have `<tableid> <model>(args) properties`
     fn create_table(handle: &Corestore, con: &'a mut T, mut act: ActionIter<'a>) {
-        ensure_length(act.len(), |size| size > 1 && size < 4)?;
+        ensure_length(act.len(), |size| size > 1 && size < 6)?;
         let table_name = unsafe { act.next().unsafe_unwrap() };
         let model_name = unsafe { act.next().unsafe_unwrap() };
         let (table_entity, model_code) = parser::parse_table_args(table_name, model_name)?;
-        let is_volatile = match act.next() {
-            Some(maybe_volatile) => {
-                ensure_cond_or_err(maybe_volatile.eq(VOLATILE), responses::groups::UNKNOWN_PROPERTY)?;
-                true
+        let (mut is_volatile, mut validate_json, mut ordered) = (false, false, false);
+        for property in act {
+            match property {
+                VOLATILE if !is_volatile => is_volatile = true,
+                VALIDATE_JSON if !validate_json => validate_json = true,
+                ORDERED if !ordered => ordered = true,
+                _ => return util::err(responses::groups::UNKNOWN_PROPERTY),
             }
-            None => false,
-        };
+        }
         if registry::state_okay() {
-            handle.create_table(table_entity, model_code, is_volatile)?;
+            handle.create_table(
+                table_entity,
+                model_code,
+                is_volatile,
+                validate_json,
+                ordered,
+            )?;
             con.write_response(responses::groups::OKAY).await?;
         } else {
             conwrite!(con, responses::groups::SERVER_ERR)?;
@@ -30,6 +30,21 @@ use crate::dbnet::connection::prelude::*;
 use crate::resp::writer::TypedArrayWriter;
 
 const KEYSPACES: &[u8] = "KEYSPACES".as_bytes();
+
+pub const DOCS: CommandDoc = CommandDoc {
+    name: "INSPECT",
+    summary: "Inspect keyspaces and tables",
+    since: "0.7.0",
+    group: CommandGroup::Server,
+    complexity: "O(n)",
+    arguments: &[
+        ArgumentDoc::new("keyspaces|keyspace|table", ArgumentType::Token, false),
+        ArgumentDoc::new("entity", ArgumentType::Entity, true),
+    ],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action! {
     /// Runs an inspect query:
     /// - `INSPECT KEYSPACES` is run by this function itself
@@ -106,12 +121,12 @@ action! {
         match act.next() {
             Some(entity) => {
                 let entity = handle_entity!(con, entity);
-                conwrite!(con, get_tbl!(entity, handle, con).describe_self())?;
+                conwrite!(con, StringWrapper(get_tbl!(entity, handle, con).describe_self()))?;
             },
             None => {
                 // inspect the current table
                 let tbl = handle.get_table_result()?;
-                con.write_response(tbl.describe_self()).await?;
+                con.write_response(StringWrapper(tbl.describe_self())).await?;
             },
         }
         Ok(())
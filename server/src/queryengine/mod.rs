@@ -26,15 +26,17 @@
 
 //! # The Query Engine
 
-use crate::actions::{ActionError, ActionResult};
+use crate::actions::{command_docs, ActionError, ActionResult};
 use crate::auth;
 use crate::corestore::Corestore;
 use crate::dbnet::connection::prelude::*;
+use crate::dbnet::connection::Written;
 use crate::protocol::{iter::AnyArrayIter, responses, PipelinedQuery, SimpleQuery, UnsafeSlice};
 use crate::queryengine::parser::Entity;
 use crate::{actions, admin};
-mod ddl;
-mod inspect;
+use std::time::Instant;
+pub mod ddl;
+pub mod inspect;
 pub mod parser;
 #[cfg(test)]
 mod tests;
@@ -42,6 +44,8 @@ mod tests;
 pub type ActionIter<'a> = AnyArrayIter<'a>;
 
 const ACTION_AUTH: &[u8] = b"auth";
+/// when this is the first query of a pipeline, the pipeline stops at the first error
+const PIPELINE_ABORT_ON_ERR: &[u8] = b"ABORT_ON_ERR";
 
 macro_rules! gen_constants_and_matches {
     (
@@ -61,7 +65,12 @@ macro_rules! gen_constants_and_matches {
         let first = $buf.next_uppercase().unwrap_or_custom_aerr(groups::PACKET_ERR)?;
         match first.as_ref() {
             $(
-                tags::$action => $fns($db, $con, $buf).await?,
+                tags::$action => {
+                    let start = Instant::now();
+                    let ret = $fns($db, $con, $buf).await;
+                    admin::latency::record(stringify!($action), start.elapsed());
+                    ret?
+                }
             )*
             $(
                 tags::$action2 => $fns2.await?,
@@ -89,7 +98,14 @@ action! {
         };
         match iter.next_lowercase().unwrap_or_custom_aerr(groups::PACKET_ERR)?.as_ref() {
             ACTION_AUTH => auth::auth_login_only(con, auth, iter).await,
-            _ => util::err(auth::errors::AUTH_CODE_BAD_CREDENTIALS),
+            action => {
+                auth::acl::log_violation(
+                    String::from_utf8_lossy(action).to_uppercase(),
+                    None,
+                    auth.peer(),
+                );
+                util::err(auth::errors::AUTH_CODE_BAD_CREDENTIALS)
+            }
         }
     }
     //// Execute a simple query
@@ -109,6 +125,17 @@ async fn execute_stage<'a, T: 'a + ClientConnection<Strm>, Strm: Stream>(
     auth: &mut AuthProviderHandle<'_, T, Strm>,
     buf: &[UnsafeSlice],
 ) -> ActionResult<()> {
+    let doc = buf.first().and_then(|action| {
+        command_docs::find(unsafe {
+            // SAFETY: the buffer is alive until the query has been run
+            action.as_slice()
+        })
+    });
+    // unknown actions are rejected anyway
+    if let Some(doc) = doc {
+        auth::acl::ensure_permitted(auth, doc, buf)?;
+        actions::ensure_memory_okay(doc, buf)?;
+    }
     let mut iter = unsafe {
         // UNSAFE(@ohsayan): The presence of the connection guarantees that this
         // won't suddenly become invalid
@@ -121,6 +148,7 @@ async fn execute_stage<'a, T: 'a + ClientConnection<Strm>, Strm: Stream>(
             SET => actions::set::set,
             UPDATE => actions::update::update,
             DEL => actions::del::del,
+            MDEL => actions::del::del,
             HEYA => actions::heya::heya,
             EXISTS => actions::exists::exists,
             MSET => actions::mset::mset,
@@ -136,25 +164,75 @@ async fn execute_stage<'a, T: 'a + ClientConnection<Strm>, Strm: Stream>(
             MKSNAP => admin::mksnap::mksnap,
             LSKEYS => actions::lskeys::lskeys,
             POP => actions::pop::pop,
+            GETDEL => actions::pop::pop,
             CREATE => ddl::create,
+            ALTER => ddl::alter,
             DROP => ddl::ddl_drop,
             USE => self::entity_swap,
             INSPECT => inspect::inspect,
             MPOP => actions::mpop::mpop,
             LSET => actions::lists::lset,
             LGET => actions::lists::lget::lget,
+            LLEN => actions::lists::llen::llen,
             LMOD => actions::lists::lmod::lmod,
+            LRANGE => actions::lists::lrange::lrange,
+            LPOS => actions::lists::lpos::lpos,
+            LSLIST => actions::lists::lslist::lslist,
+            MLLEN => actions::lists::mllen::mllen,
             WHEREAMI => actions::whereami::whereami,
             SYS => admin::sys::sys,
+            SWAPKEYS => actions::swapkeys::swapkeys,
+            LSWAP => actions::lists::lswap::lswap,
+            MOVE => actions::move_key::move_key,
+            LATENCY => admin::latency::latency,
+            DEBUG => admin::debug::debug,
+            MEMORY => admin::memory::memory,
+            OBJECT => actions::object::object,
+            SETNX => actions::setnx::setnx,
+            SETNXEX => actions::setnxex::setnxex,
+            GETEX => actions::getex::getex,
+            EXPIRE => actions::ttl::expire,
+            PEXPIRE => actions::ttl::pexpire,
+            EXPIREAT => actions::ttl::expireat,
+            PEXPIREAT => actions::ttl::pexpireat,
+            PERSIST => actions::ttl::persist,
+            TTL => actions::ttl::ttl,
+            PTTL => actions::ttl::pttl,
+            EXPIRETIME => actions::ttl::expiretime,
+            PEXPIRETIME => actions::ttl::pexpiretime,
+            SCAN => actions::scan::scan,
+            APPEND => actions::append::append,
+            STRLEN => actions::strlen::strlen,
+            GETSET => actions::getset::getset,
+            INCR => actions::incr::incr,
+            DECR => actions::incr::decr,
+            INCRBY => actions::incr::incrby,
+            DECRBY => actions::incr::decrby,
+            INCRBYFLOAT => actions::incr::incrbyfloat,
+            COMMAND => admin::command::command,
+            CSET => actions::cset::cset,
+            KEYRANGE => actions::keyrange::keyrange,
             {
                 // actions that need other arguments
-                AUTH => auth::auth(con, auth, iter)
+                AUTH => auth::auth(con, auth, iter),
+                ACL => auth::acl::acl(con, auth, iter)
             }
         );
     }
     Ok(())
 }
 
+pub const USE_DOCS: CommandDoc = CommandDoc {
+    name: "USE",
+    summary: "Switch to a keyspace or a table",
+    since: "0.7.0",
+    group: CommandGroup::Server,
+    complexity: "O(1)",
+    arguments: &[ArgumentDoc::new("entity", ArgumentType::Entity, false)],
+    keys: KeySpec::NONE,
+    memory: MemoryUse::None,
+};
+
 action! {
     /// Handle `use <entity>` like queries
     fn entity_swap(handle: &mut Corestore, con: &mut T, mut act: ActionIter<'a>) {
@@ -170,34 +248,66 @@ action! {
 }
 
 /// Execute a stage **completely**. This means that action errors are never propagated
-/// over the try operator
+/// over the try operator. Returns `false` if the stage failed, that is if its response is
+/// an error response code (whether the action wrote it or returned an action error)
 async fn execute_stage_pedantic<'a, T: ClientConnection<Strm> + 'a, Strm: Stream + 'a>(
     handle: &mut Corestore,
     con: &mut T,
     auth: &mut AuthProviderHandle<'_, T, Strm>,
     stage: &[UnsafeSlice],
-) -> crate::IoResult<()> {
+) -> crate::IoResult<bool> {
+    con.reset_written();
     let ret = async {
         self::execute_stage(handle, con, auth, stage).await?;
         Ok(())
     };
     match ret.await {
-        Ok(()) => Ok(()),
-        Err(ActionError::ActionError(e)) => con.write_response(e).await,
+        Ok(()) => Ok(con.get_written() != Written::Error),
+        Err(ActionError::ActionError(e)) => con.write_response(e).await.map(|_| false),
+        Err(ActionError::DynamicError(e)) => con.write_response(e).await.map(|_| false),
         Err(ActionError::IoError(ioe)) => Err(ioe),
     }
 }
 
+/// Returns true if the stage is just the `ABORT_ON_ERR` token
+fn is_abort_on_err(stage: &[UnsafeSlice]) -> bool {
+    match stage {
+        [token] => unsafe {
+            // SAFETY: the stage points into the connection's buffer, which is only advanced after
+            // the whole pipeline has been run
+            token.as_slice()
+        }
+        .eq_ignore_ascii_case(PIPELINE_ABORT_ON_ERR),
+        _ => false,
+    }
+}
+
 action! {
     /// Execute a basic pipelined query
+    ///
+    /// If the first query is `ABORT_ON_ERR` (to which we respond with `OKAY`), then once a
+    /// query responds with an error response code, the remaining queries are not run and get
+    /// a `NIL` each (so that the number of responses still matches the number of queries)
     fn execute_pipeline(
         handle: &mut Corestore,
         con: &mut T,
         auth: &mut AuthProviderHandle<'_, T, Strm>,
         pipeline: PipelinedQuery
     ) {
-        for stage in pipeline.into_inner().iter() {
-            self::execute_stage_pedantic(handle, con, auth, stage).await?;
+        let pipeline = pipeline.into_inner();
+        let mut stages = pipeline.iter();
+        let abort_on_err = pipeline.first().is_some_and(|stage| is_abort_on_err(stage));
+        if abort_on_err {
+            stages.next();
+            con.write_response(groups::OKAY).await?;
+        }
+        while let Some(stage) = stages.next() {
+            let okay = self::execute_stage_pedantic(handle, con, auth, stage).await?;
+            if abort_on_err && !okay {
+                for _ in stages.by_ref() {
+                    con.write_response(groups::NIL).await?;
+                }
+            }
         }
         Ok(())
     }
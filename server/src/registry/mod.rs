@@ -30,12 +30,19 @@
 //!
 
 use crate::corestore::lock::{QLGuard, QuickLock};
+use crate::dbnet::DEFAULT_BUFFER_SHRINK_THRESHOLD;
+use crate::protocol::DEFAULT_MAX_PIPELINE_LENGTH;
 use core::sync::atomic::AtomicBool;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::AtomicUsize;
 use core::sync::atomic::Ordering;
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 const ORD_ACQ: Ordering = Ordering::Acquire;
 const ORD_REL: Ordering = Ordering::Release;
 const ORD_SEQ: Ordering = Ordering::SeqCst;
+const ORD_RLX: Ordering = Ordering::Relaxed;
 
 /// A digital _trip switch_ that can be tripped and untripped in a thread
 /// friendly, consistent manner. It is slightly expensive on processors
@@ -80,6 +87,45 @@ static FLUSH_STATE: QuickLock<()> = QuickLock::new(());
 /// The preload trip switch
 static PRELOAD_TRIPSWITCH: Trip = Trip::new_untripped();
 static CLEANUP_TRIPSWITCH: Trip = Trip::new_untripped();
+/// The memory budget in bytes (0 is unlimited)
+static MEMORY_BUDGET: AtomicUsize = AtomicUsize::new(0);
+/// The last estimate of the memory used by data
+static MEMORY_ESTIMATE: AtomicUsize = AtomicUsize::new(0);
+/// The memory limit trip switch (tripped when the estimate exceeds the budget)
+static MEMORY_LIMIT_TRIPSWITCH: Trip = Trip::new_untripped();
+/// The number of open client connections
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+/// The number of queries executed since startup (a pipeline counts as one query per stage)
+static QUERIES: AtomicU64 = AtomicU64::new(0);
+/// The maximum number of queries in a pipeline
+static MAX_PIPELINE_LENGTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_PIPELINE_LENGTH);
+/// The read buffer capacity above which a connection's buffer is shrunk
+static BUFFER_SHRINK_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_BUFFER_SHRINK_THRESHOLD);
+/// Whether large tables can only be flushed with `FLUSHDB <entity> force`
+static PROTECT_LARGE_FLUSH: AtomicBool = AtomicBool::new(false);
+/// Whether packet errors include the offset at which the packet was rejected
+static VERBOSE_PROTOCOL_ERRORS: AtomicBool = AtomicBool::new(false);
+/// The time at which the server started
+static STARTUP: OnceLock<Startup> = OnceLock::new();
+
+/// The time at which the server started, both as a monotonic instant (for the uptime) and as
+/// a wall clock timestamp
+struct Startup {
+    instant: Instant,
+    unix_secs: u64,
+}
+
+impl Startup {
+    fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            unix_secs: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+}
 
 /// Check the global system state
 pub fn state_okay() -> bool {
@@ -111,3 +157,172 @@ pub fn get_preload_tripswitch() -> &'static Trip {
 pub fn get_cleanup_tripswitch() -> &'static Trip {
     &CLEANUP_TRIPSWITCH
 }
+
+/// Set the memory budget (in bytes). A budget of `0` means that memory usage is unlimited
+pub fn set_memory_budget(budget: usize) {
+    MEMORY_BUDGET.store(budget, ORD_REL)
+}
+
+/// Get the memory budget (in bytes)
+pub fn memory_budget() -> usize {
+    MEMORY_BUDGET.load(ORD_ACQ)
+}
+
+/// Set the maximum number of queries in a pipeline
+pub fn set_max_pipeline_length(max: usize) {
+    MAX_PIPELINE_LENGTH.store(max, ORD_RLX)
+}
+
+/// Get the maximum number of queries in a pipeline
+pub fn max_pipeline_length() -> usize {
+    MAX_PIPELINE_LENGTH.load(ORD_RLX)
+}
+
+/// Set the read buffer capacity above which a connection's buffer is shrunk
+pub fn set_buffer_shrink_threshold(threshold: usize) {
+    BUFFER_SHRINK_THRESHOLD.store(threshold, ORD_RLX)
+}
+
+/// Get the read buffer capacity above which a connection's buffer is shrunk
+pub fn buffer_shrink_threshold() -> usize {
+    BUFFER_SHRINK_THRESHOLD.load(ORD_RLX)
+}
+
+/// Set whether large tables can only be flushed with `FLUSHDB <entity> force`
+pub fn set_protect_large_flush(protect: bool) {
+    PROTECT_LARGE_FLUSH.store(protect, ORD_RLX)
+}
+
+/// Check if large tables can only be flushed with `FLUSHDB <entity> force`
+pub fn protect_large_flush() -> bool {
+    PROTECT_LARGE_FLUSH.load(ORD_RLX)
+}
+
+/// Set whether packet errors include the offset at which the packet was rejected
+pub fn set_verbose_protocol_errors(verbose: bool) {
+    VERBOSE_PROTOCOL_ERRORS.store(verbose, ORD_RLX)
+}
+
+/// Check if packet errors include the offset at which the packet was rejected
+pub fn verbose_protocol_errors() -> bool {
+    VERBOSE_PROTOCOL_ERRORS.load(ORD_RLX)
+}
+
+/// Get the last memory estimate (in bytes)
+pub fn memory_estimate() -> usize {
+    MEMORY_ESTIMATE.load(ORD_ACQ)
+}
+
+/// Record the server's startup time. This should be called as early as possible during boot;
+/// calling it again has no effect
+pub fn record_startup_time() {
+    let _ = STARTUP.get_or_init(Startup::now);
+}
+
+/// Get the number of seconds since the server started
+pub fn uptime() -> u64 {
+    STARTUP
+        .get_or_init(Startup::now)
+        .instant
+        .elapsed()
+        .as_secs()
+}
+
+/// Get the unix timestamp (in seconds) at which the server started
+pub fn startup_time() -> u64 {
+    STARTUP.get_or_init(Startup::now).unix_secs
+}
+
+/// Record that a client connection was opened
+pub fn connection_opened() {
+    // these are only counters; they don't synchronize anything
+    CONNECTIONS.fetch_add(1, ORD_RLX);
+}
+
+/// Record that a client connection was closed
+pub fn connection_closed() {
+    CONNECTIONS.fetch_sub(1, ORD_RLX);
+}
+
+/// Get the number of open client connections
+pub fn connections() -> usize {
+    CONNECTIONS.load(ORD_RLX)
+}
+
+/// Record that `count` queries were executed
+pub fn queries_executed(count: usize) {
+    QUERIES.fetch_add(count as u64, ORD_RLX);
+}
+
+/// Get the number of queries executed since startup
+pub fn queries() -> u64 {
+    QUERIES.load(ORD_RLX)
+}
+
+/// Returns the low watermark for the given budget. Once the limit has been hit, writes are only
+/// re-admitted after the estimate drops to (or below) this value so that we don't flap at the
+/// boundary
+pub const fn memory_low_watermark(budget: usize) -> usize {
+    budget - budget / 10
+}
+
+/// Returns true if writes should be refused, given the current state of the memory limit switch,
+/// a fresh estimate and the budget
+const fn memory_limit_hit(tripped: bool, estimate: usize, budget: usize) -> bool {
+    if budget == 0 {
+        false
+    } else if estimate > budget {
+        true
+    } else if estimate <= memory_low_watermark(budget) {
+        false
+    } else {
+        // between the low watermark and the budget; don't flap
+        tripped
+    }
+}
+
+/// Record a new memory estimate and trip (or untrip) the memory limit switch as needed
+pub fn update_memory_estimate(estimate: usize) {
+    MEMORY_ESTIMATE.store(estimate, ORD_REL);
+    let tripped = MEMORY_LIMIT_TRIPSWITCH.is_tripped();
+    if memory_limit_hit(tripped, estimate, memory_budget()) {
+        MEMORY_LIMIT_TRIPSWITCH.trip();
+    } else {
+        MEMORY_LIMIT_TRIPSWITCH.untrip();
+    }
+}
+
+/// Check if writes that grow memory usage are admitted. This is always true if the memory budget
+/// is unlimited
+pub fn memory_okay() -> bool {
+    !MEMORY_LIMIT_TRIPSWITCH.is_tripped()
+}
+
+#[test]
+fn test_uptime_monotonic() {
+    record_startup_time();
+    let started = startup_time();
+    let first = uptime();
+    let second = uptime();
+    assert!(second >= first);
+    // recording again doesn't reset the clock
+    record_startup_time();
+    assert_eq!(startup_time(), started);
+    assert!(uptime() >= second);
+}
+
+#[test]
+fn test_memory_limit_hysteresis() {
+    // unlimited
+    assert!(!memory_limit_hit(false, usize::MAX, 0));
+    // below the budget
+    assert!(!memory_limit_hit(false, 950, 1000));
+    // over the budget
+    assert!(memory_limit_hit(false, 1001, 1000));
+    // still refusing between the low watermark and the budget
+    assert!(memory_limit_hit(true, 950, 1000));
+    assert!(memory_limit_hit(true, 1000, 1000));
+    // readmit at the low watermark
+    assert!(!memory_limit_hit(true, 900, 1000));
+    assert!(!memory_limit_hit(true, 10, 1000));
+}
@@ -30,6 +30,7 @@
 //!
 use crate::corestore::buffers::Integer64;
 use crate::corestore::memstore::ObjectID;
+use crate::protocol::responses::groups;
 use crate::util::FutureResult;
 use bytes::Bytes;
 use std::io::Error as IoError;
@@ -58,6 +59,10 @@ pub trait Writable {
     use explicit declarations for asynchoronous functions
     */
     fn write<'s>(self, con: &'s mut impl IsConnection) -> FutureIoResult<'s>;
+    /// Returns true if this is a response code that reports a failure
+    fn is_error(&self) -> bool {
+        false
+    }
 }
 
 pub trait IsConnection: std::marker::Sync + std::marker::Send {
@@ -111,6 +116,9 @@ impl Writable for Vec<u8> {
     fn write<'s>(self, con: &'s mut impl IsConnection) -> FutureIoResult<'s> {
         Box::pin(async move { con.write_lowlevel(&self).await })
     }
+    fn is_error(&self) -> bool {
+        groups::is_error(self)
+    }
 }
 
 impl<const N: usize> Writable for [u8; N] {
@@ -123,6 +131,9 @@ impl Writable for &'static [u8] {
     fn write<'s>(self, con: &'s mut impl IsConnection) -> FutureIoResult<'s> {
         Box::pin(async move { con.write_lowlevel(self).await })
     }
+    fn is_error(&self) -> bool {
+        groups::is_error(self)
+    }
 }
 
 impl Writable for &'static str {
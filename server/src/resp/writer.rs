@@ -52,6 +52,23 @@ where
     Ok(())
 }
 
+/// Write a typed array (with a custom tsymbol) of the provided elements
+pub async unsafe fn write_typed_array<T, Strm>(
+    con: &mut T,
+    tsymbol: u8,
+    payload: &[Data],
+) -> IoResult<()>
+where
+    T: ProtocolConnectionExt<Strm>,
+    Strm: AsyncReadExt + AsyncWriteExt + Unpin + Send + Sync,
+{
+    let mut writer = TypedArrayWriter::new(con, tsymbol, payload.len()).await?;
+    for element in payload {
+        writer.write_element(element).await?;
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 /// A writer for a flat array, which is a multi-typed non-recursive array
 pub struct FlatArrayWriter<'a, T, Strm> {
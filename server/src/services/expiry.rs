@@ -0,0 +1,55 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::{corestore::Corestore, dbnet::Terminator};
+use tokio::time::{self, Duration};
+
+/// The interval (in milliseconds) after which the tables are swept for expired keys
+const EXPIRY_SWEEP_INTERVAL: u64 = 100;
+
+/// The expiry service periodically removes expired keys from all the tables, so that their
+/// memory is reclaimed even if they're never accessed again
+pub async fn expiry_service(handle: Corestore, mut terminator: Terminator) {
+    let duration = Duration::from_millis(EXPIRY_SWEEP_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = time::sleep_until(time::Instant::now() + duration) => {
+                let cloned_handle = handle.clone();
+                // walking all the tables is blocking work
+                let removed = tokio::task::spawn_blocking(move || {
+                    cloned_handle.get_store().remove_expired()
+                }).await.expect("Something caused the background service to panic");
+                if removed != 0 {
+                    log::debug!("Expiry service removed {removed} expired key(s)");
+                }
+            }
+            _ = terminator.receive_signal() => {
+                break;
+            }
+        }
+    }
+    log::info!("Expiry service has exited");
+}
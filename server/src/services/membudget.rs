@@ -0,0 +1,68 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+use crate::{corestore::Corestore, dbnet::Terminator, registry};
+use tokio::time::{self, Duration};
+
+/// The interval (in milliseconds) after which the memory estimate is refreshed
+const MEMBUDGET_REFRESH_INTERVAL: u64 = 100;
+
+/// The membudget service periodically refreshes the memory estimate in the registry so that
+/// writes can be refused once the memory budget is exhausted
+///
+/// Every table keeps its own estimate up to date as it is written to, so a refresh only adds
+/// up the estimates of the tables (and never walks their entries). The estimate is refreshed
+/// even if the memory budget is unlimited (`0`), so that it can still be reported
+pub async fn membudget_service(handle: Corestore, budget: usize, mut terminator: Terminator) {
+    registry::set_memory_budget(budget);
+    let duration = Duration::from_millis(MEMBUDGET_REFRESH_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = time::sleep_until(time::Instant::now() + duration) => {
+                refresh_memory_estimate(&handle)
+            }
+            _ = terminator.receive_signal() => {
+                break;
+            }
+        }
+    }
+    log::info!("Memory budget service has exited");
+}
+
+/// Add up the memory estimates of all the tables and update the registry
+pub fn refresh_memory_estimate(handle: &Corestore) {
+    let estimate = handle.get_store().memory_estimate();
+    let was_okay = registry::memory_okay();
+    registry::update_memory_estimate(estimate);
+    match (was_okay, registry::memory_okay()) {
+        (true, false) => log::warn!(
+            "Memory budget exhausted (estimate: {estimate}B, budget: {}B). Refusing writes",
+            registry::memory_budget()
+        ),
+        (false, true) => log::info!("Memory usage below low watermark. Accepting writes"),
+        _ => {}
+    }
+}
@@ -25,6 +25,8 @@
 */
 
 pub mod bgsave;
+pub mod expiry;
+pub mod membudget;
 pub mod snapshot;
 use crate::corestore::memstore::Memstore;
 use crate::diskstore::flock::FileLock;
@@ -41,6 +43,29 @@ pub fn restore_data(src: Option<String>) -> IoResult<()> {
     Ok(())
 }
 
+/// Run an offline migration of the data directory, logging a summary for each table.
+/// Returns true if the migration succeeded
+pub fn run_migration() -> bool {
+    log::info!("Migrating data to the current storage format");
+    match storage::v1::migrate::migrate_full() {
+        Ok(summary) if summary.is_empty() => {
+            log::info!("Nothing to migrate");
+            true
+        }
+        Ok(summary) => {
+            for table in summary {
+                log::info!("Migrated {}", table);
+            }
+            log::info!("Migration complete");
+            true
+        }
+        Err(e) => {
+            log::error!("Migration failed: {}", e);
+            false
+        }
+    }
+}
+
 pub fn pre_shutdown_cleanup(mut pid_file: FileLock, mr: Option<&Memstore>) -> bool {
     if let Err(e) = pid_file.unlock() {
         log::error!("Shutdown failure: Failed to unlock pid file: {}", e);
@@ -64,6 +64,16 @@ pub const BYTEMARK_MODEL_KV_STR_LIST_STR: u8 = 7;
 pub const BYTEMARK_STORAGE_PERSISTENT: u8 = 0;
 /// Volatile storage bytemark
 pub const BYTEMARK_STORAGE_VOLATILE: u8 = 1;
+/// The bits of the storage bytemark that hold the storage type. The other bits are used for
+/// table properties
+pub const BYTEMARK_STORAGE_TYPE_MASK: u8 = 0b01;
+
+// table property bym (these are set in the storage bytemark)
+/// Values need to be well-formed JSON
+pub const BYTEMARK_PROPERTY_VALIDATE_JSON: u8 = 0b10;
+/// The table keeps an ordered index of its keys
+pub const BYTEMARK_PROPERTY_ORDERED: u8 = 0b100;
 
 // system bym
 pub const SYSTEM_TABLE_AUTH: u8 = 0;
+pub const SYSTEM_TABLE_ACL: u8 = 1;
@@ -36,11 +36,12 @@ use crate::corestore::{
     memstore::{Keyspace, Memstore, ObjectID, SystemKeyspace},
     table::{DataModel, SystemDataModel, SystemTable, Table},
 };
+use crate::kvengine::expiry::ExpiryTable;
 use crate::registry;
 use crate::util::Wrapper;
 use crate::IoResult;
 use core::ops::Deref;
-use std::io::Write;
+use std::io::{ErrorKind, Write};
 use std::sync::Arc;
 
 pub trait StorageTarget {
@@ -83,6 +84,16 @@ pub trait StorageTarget {
         p.push('_');
         p
     }
+    /// Returns the path to the file with the deadlines of a table's keys. **temporary file**
+    /// ($ROOT/{keyspace}/{table}.ttl_)
+    fn deadlines_target(&self, keyspace: &str, table: &str) -> String {
+        let mut p = self.keyspace_target(keyspace);
+        p.push('/');
+        p.push_str(table);
+        p.push_str(interface::DEADLINES_FILE_SUFFIX);
+        p.push('_');
+        p
+    }
 }
 
 /// The autoflush target (BGSAVE target)
@@ -176,6 +187,8 @@ pub trait FlushableTable {
     fn write_table_to<W: Write>(&self, writer: &mut W) -> IoResult<()>;
     /// Returns the model code bytemark
     fn model_code(&self) -> u8;
+    /// Returns the deadlines of the keys in this table, if the table supports them
+    fn get_expiry_ref(&self) -> Option<&ExpiryTable>;
 }
 
 impl FlushableTable for Table {
@@ -191,11 +204,21 @@ impl FlushableTable for Table {
         }
     }
     fn storage_code(&self) -> u8 {
-        self.storage_type()
+        let mut code = self.storage_type();
+        if self.is_json_validated() {
+            code |= bytemarks::BYTEMARK_PROPERTY_VALIDATE_JSON;
+        }
+        if self.is_ordered() {
+            code |= bytemarks::BYTEMARK_PROPERTY_ORDERED;
+        }
+        code
     }
     fn model_code(&self) -> u8 {
         self.get_model_code()
     }
+    fn get_expiry_ref(&self) -> Option<&ExpiryTable> {
+        Some(self.get_expiry_ref())
+    }
 }
 
 impl FlushableTable for SystemTable {
@@ -205,6 +228,7 @@ impl FlushableTable for SystemTable {
     fn write_table_to<W: Write>(&self, writer: &mut W) -> IoResult<()> {
         match self.get_model_ref() {
             SystemDataModel::Auth(amap) => super::se::raw_serialize_map(amap.as_ref(), writer),
+            SystemDataModel::Acl(acl) => super::se::raw_serialize_map(acl.as_ref(), writer),
         }
     }
     fn storage_code(&self) -> u8 {
@@ -213,8 +237,12 @@ impl FlushableTable for SystemTable {
     fn model_code(&self) -> u8 {
         match self.get_model_ref() {
             SystemDataModel::Auth(_) => bytemarks::SYSTEM_TABLE_AUTH,
+            SystemDataModel::Acl(_) => bytemarks::SYSTEM_TABLE_ACL,
         }
     }
+    fn get_expiry_ref(&self) -> Option<&ExpiryTable> {
+        None
+    }
 }
 
 /// Flush the entire **preload + keyspaces + their partmaps**
@@ -257,6 +285,23 @@ where
     self::oneshot::flush_keyspace(target, ksid, keyspace)
 }
 
+/// Flushes a single **table + partmap**. The table is written before the partmap, so that a
+/// table that was just made persistent is never listed without its file
+pub fn flush_table_full<T: StorageTarget>(
+    target: &T,
+    tableid: &ObjectID,
+    ksid: &ObjectID,
+    keyspace: &Keyspace,
+    table: &Table,
+) -> IoResult<()> {
+    // the keyspace may have been created after the tree was last initialized
+    unsafe {
+        try_dir_ignore_existing!(target.keyspace_target(ksid.as_str()))?;
+    }
+    self::oneshot::flush_table(target, tableid, ksid, table)?;
+    self::oneshot::flush_partmap(target, ksid, keyspace)
+}
+
 pub mod oneshot {
     //! # Irresponsible flushing
     //!
@@ -282,7 +327,33 @@ pub mod oneshot {
             let mut file = File::create(&path)?;
             super::interface::serialize_into_slow_buffer(&mut file, table)?;
             file.sync_all()?;
-            fs::rename(&path, &path[..path.len() - 1])
+            fs::rename(&path, &path[..path.len() - 1])?;
+            // the deadlines are flushed after the data, so every key in the table's file that
+            // still has a deadline finds it in the deadlines file
+            self::flush_deadlines(target, tableid, ksid, table)
+        }
+    }
+
+    /// Flushes the deadlines of a table's keys to the expected location, or removes the
+    /// deadlines file if no deadline was ever set in the table
+    pub fn flush_deadlines<T: StorageTarget, U: FlushableTable>(
+        target: &T,
+        tableid: &ObjectID,
+        ksid: &ObjectID,
+        table: &U,
+    ) -> IoResult<()> {
+        let path = unsafe { target.deadlines_target(ksid.as_str(), tableid.as_str()) };
+        match table.get_expiry_ref() {
+            Some(expiry) if expiry.is_armed() && expiry.get_inner_ref().len() != 0 => {
+                let mut file = File::create(&path)?;
+                super::interface::serialize_deadlines_into_slow_buffer(&mut file, expiry)?;
+                file.sync_all()?;
+                fs::rename(&path, &path[..path.len() - 1])
+            }
+            _ => match fs::remove_file(&path[..path.len() - 1]) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
         }
     }
 
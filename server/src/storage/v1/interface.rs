@@ -27,6 +27,7 @@
 //! Interfaces with the file system
 
 use crate::corestore::memstore::Memstore;
+use crate::kvengine::expiry::ExpiryTable;
 use crate::registry;
 use crate::storage::v1::flush::FlushableKeyspace;
 use crate::storage::v1::flush::FlushableTable;
@@ -35,13 +36,42 @@ use crate::IoResult;
 use core::ops::Deref;
 use std::collections::HashSet;
 use std::fs;
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
 
 pub const DIR_KSROOT: &str = "data/ks";
 pub const DIR_SNAPROOT: &str = "data/snaps";
 pub const DIR_RSNAPROOT: &str = "data/rsnap";
 pub const DIR_BACKUPS: &str = "data/backups";
 pub const DIR_ROOT: &str = "data";
+/// The suffix of the file (next to a table's file) that holds the deadlines of the table's keys
+pub const DEADLINES_FILE_SUFFIX: &str = ".ttl";
+/// The format version of the table files that we currently write
+pub const TABLE_FORMAT_VERSION_CURRENT: u8 = 2;
+/// Table files from v2 onwards start with this magic followed by a byte with the format
+/// version. v1 table files have no header and start with the number of entries (8B) instead,
+/// which can never be large enough to be mistaken for the magic
+const TABLE_HEADER_MAGIC: [u8; 7] = *b"SKYTBL\0";
+/// The size of the header of a table file
+const TABLE_HEADER_SIZE: usize = TABLE_HEADER_MAGIC.len() + 1;
+
+/// Returns the format version of a table file along with the data that follows its header
+pub fn split_table_header(file: &[u8]) -> (u8, &[u8]) {
+    if file.len() >= TABLE_HEADER_SIZE && file[..TABLE_HEADER_MAGIC.len()] == TABLE_HEADER_MAGIC {
+        (file[TABLE_HEADER_MAGIC.len()], &file[TABLE_HEADER_SIZE..])
+    } else {
+        (1, file)
+    }
+}
+
+/// Returns the format version of the table file at `path`, reading nothing but its header
+pub fn read_table_version(path: impl AsRef<Path>) -> IoResult<u8> {
+    let mut header = Vec::with_capacity(TABLE_HEADER_SIZE);
+    fs::File::open(path)?
+        .take(TABLE_HEADER_SIZE as u64)
+        .read_to_end(&mut header)?;
+    Ok(self::split_table_header(&header).0)
+}
 
 /// Creates the directories for the keyspaces
 pub fn create_tree<T: StorageTarget>(target: &T, memroot: &Memstore) -> IoResult<()> {
@@ -109,7 +139,11 @@ pub fn cleanup_tree(memroot: &Memstore) -> IoResult<()> {
                 .value()
                 .tables
                 .iter()
-                .map(|v| unsafe { v.key().as_str() }.to_owned())
+                .flat_map(|v| {
+                    let tbl = unsafe { v.key().as_str() };
+                    // keep the deadlines along with the data
+                    [tbl.to_owned(), concat_str!(tbl, DEADLINES_FILE_SUFFIX)]
+                })
                 .collect();
             for old_file in dir_tbls.difference(&our_tbls) {
                 let fpath = concat_path!(&ks_path, old_file);
@@ -124,7 +158,11 @@ pub fn cleanup_tree(memroot: &Memstore) -> IoResult<()> {
                 .value()
                 .tables
                 .iter()
-                .map(|v| unsafe { v.key().as_str() }.to_owned())
+                .flat_map(|v| {
+                    let tbl = unsafe { v.key().as_str() };
+                    // keep the deadlines along with the data
+                    [tbl.to_owned(), concat_str!(tbl, DEADLINES_FILE_SUFFIX)]
+                })
                 .collect();
             for old_file in dir_tbls.difference(&our_tbls) {
                 if old_file != "PARTMAP" {
@@ -145,11 +183,36 @@ pub fn serialize_into_slow_buffer<T: Write, U: FlushableTable>(
     writable_item: &U,
 ) -> IoResult<()> {
     let mut buffer = BufWriter::new(buffer);
+    buffer.write_all(&TABLE_HEADER_MAGIC)?;
+    buffer.write_all(&[TABLE_FORMAT_VERSION_CURRENT])?;
     writable_item.write_table_to(&mut buffer)?;
     buffer.flush()?;
     Ok(())
 }
 
+#[cfg(test)]
+/// The v1 writer for table files (without a header), kept to test migrations
+pub fn serialize_into_slow_buffer_v1<T: Write, U: FlushableTable>(
+    buffer: &mut T,
+    writable_item: &U,
+) -> IoResult<()> {
+    let mut buffer = BufWriter::new(buffer);
+    writable_item.write_table_to(&mut buffer)?;
+    buffer.flush()?;
+    Ok(())
+}
+
+/// Same as [`serialize_into_slow_buffer`], but for the deadlines of a table's keys
+pub fn serialize_deadlines_into_slow_buffer<T: Write>(
+    buffer: &mut T,
+    expiry: &ExpiryTable,
+) -> IoResult<()> {
+    let mut buffer = BufWriter::new(buffer);
+    super::se::raw_serialize_deadlines(expiry.get_inner_ref(), &mut buffer)?;
+    buffer.flush()?;
+    Ok(())
+}
+
 pub fn serialize_partmap_into_slow_buffer<T, U, Tbl, K>(buffer: &mut T, ks: &K) -> IoResult<()>
 where
     T: Write,
@@ -0,0 +1,207 @@
+/*
+ * Created on Tue Apr 12 2022
+ *
+ * This file is a part of Skytable
+ * Skytable (formerly known as TerrabaseDB or Skybase) is a free and open-source
+ * NoSQL database written by Sayan Nandan ("the Author") with the
+ * vision to provide flexibility in data modelling without compromising
+ * on performance, queryability or scalability.
+ *
+ * Copyright (c) 2022, Sayan Nandan <ohsayan@outlook.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ *
+*/
+
+//! # Offline migrations
+//!
+//! Older formats are always read lazily on startup, so a normal boot never needs anything in
+//! here. Running the server with `--migrate` instead reads every container with the legacy
+//! readers, rewrites it with the current writers and verifies it by reading it back, so that
+//! an upgrade (and its duration) can be dealt with before the server starts serving.
+
+use super::{
+    error::{ErrorContext, StorageEngineError, StorageEngineResult},
+    flush::{self, Autoflush, FlushableTable},
+    interface::{self, DIR_KSROOT, TABLE_FORMAT_VERSION_CURRENT},
+    preload,
+    unflush::{self, UnflushableTable},
+};
+use crate::corestore::{
+    memstore::{ObjectID, SYSTEM},
+    table::{SystemTable, Table},
+};
+use core::fmt;
+use std::{fs, io::ErrorKind, path::Path, time::Instant};
+
+/// The outcome of migrating a single table
+#[derive(Debug, PartialEq)]
+pub struct MigrationSummary {
+    pub ksid: String,
+    pub tblid: String,
+    pub version_before: u8,
+    pub version_after: u8,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub duration_us: u128,
+}
+
+impl fmt::Display for MigrationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{ks}:{tbl} v{vb} -> v{va} ({bb} -> {ba} bytes) in {us}us",
+            ks = self.ksid,
+            tbl = self.tblid,
+            vb = self.version_before,
+            va = self.version_after,
+            bb = self.bytes_before,
+            ba = self.bytes_after,
+            us = self.duration_us
+        )
+    }
+}
+
+/// Returns the size of the file at `path` or zero if it doesn't exist (volatile tables)
+fn file_size(path: impl AsRef<Path>) -> u64 {
+    fs::metadata(path).map(|md| md.len()).unwrap_or(0)
+}
+
+/// Returns the format version of the `PRELOAD`
+pub fn preload_version() -> StorageEngineResult<u8> {
+    let preload_raw =
+        fs::read(concat_path!(DIR_KSROOT, "PRELOAD")).map_err_context("reading PRELOAD")?;
+    preload::read_format_version(&preload_raw).ok_or_else(StorageEngineError::corrupted_preload)
+}
+
+/// Returns the format version from the header of a table's file, or `None` if the table
+/// has no file (volatile tables, or tables that haven't been flushed yet)
+fn table_version(
+    ksid: &ObjectID,
+    tblid: &ObjectID,
+    storage_code: u8,
+) -> StorageEngineResult<Option<u8>> {
+    match unflush::decode_storage_code(storage_code) {
+        Some((true, _, _)) => return Ok(None),
+        Some(_) => {}
+        None => return Err(StorageEngineError::bad_metadata_in_table(ksid, tblid)),
+    }
+    let filepath = unsafe { concat_path!(DIR_KSROOT, ksid.as_str(), tblid.as_str()) };
+    match interface::read_table_version(&filepath) {
+        Ok(version) => Ok(Some(version)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).map_err_context(format!("reading {}", filepath.to_string_lossy())),
+    }
+}
+
+/// Returns the format version for every table on disk, as `(<ks>:<tbl>, version)`
+pub fn container_versions() -> StorageEngineResult<Vec<(String, u8)>> {
+    let mut ret = Vec::new();
+    for ksid in unflush::read_preload()? {
+        for (tblid, (storage_code, _)) in unflush::read_partmap(&ksid)? {
+            if let Some(version) = self::table_version(&ksid, &tblid, storage_code)? {
+                let name = unsafe { concat_str!(ksid.as_str(), ":", tblid.as_str()) };
+                ret.push((name, version));
+            }
+        }
+    }
+    Ok(ret)
+}
+
+/// Read a single table with the legacy reader, write it back in the current format and
+/// verify it by reading it again
+pub fn migrate_table<T: UnflushableTable + FlushableTable>(
+    ksid: &ObjectID,
+    tblid: &ObjectID,
+    volatile: bool,
+    model_code: u8,
+    version_before: u8,
+) -> StorageEngineResult<MigrationSummary> {
+    let start = Instant::now();
+    let filepath = unsafe { concat_path!(DIR_KSROOT, ksid.as_str(), tblid.as_str()) };
+    let bytes_before = self::file_size(&filepath);
+    let table = unflush::read_table::<T>(ksid, tblid, volatile, model_code)?;
+    flush::oneshot::flush_table(&Autoflush, tblid, ksid, &table)
+        .map_err_context(format!("rewriting {}", filepath.to_string_lossy()))?;
+    // now verify what we just wrote: it should be exactly what the current writer produces
+    // for the table (the table isn't modified in between, so it is serialized in the same
+    // order) and it should load again
+    if !volatile {
+        let mut expected = Vec::new();
+        interface::serialize_into_slow_buffer(&mut expected, &table)?;
+        let actual = fs::read(&filepath)
+            .map_err_context(format!("reading {}", filepath.to_string_lossy()))?;
+        if expected != actual {
+            return Err(StorageEngineError::CorruptedFile(
+                filepath.to_string_lossy().to_string(),
+            ));
+        }
+    }
+    unflush::read_table::<T>(ksid, tblid, volatile, model_code)?;
+    unsafe {
+        Ok(MigrationSummary {
+            ksid: ksid.as_str().to_owned(),
+            tblid: tblid.as_str().to_owned(),
+            version_before,
+            version_after: TABLE_FORMAT_VERSION_CURRENT,
+            bytes_before,
+            bytes_after: self::file_size(&filepath),
+            duration_us: start.elapsed().as_micros(),
+        })
+    }
+}
+
+/// Migrate every container on disk to the current format, returning a summary for each
+/// table. This is a no-op on a new instance
+pub fn migrate_full() -> StorageEngineResult<Vec<MigrationSummary>> {
+    if unflush::is_new_instance()? {
+        return Ok(Vec::new());
+    }
+    let mut summary = Vec::new();
+    for ksid in unflush::read_preload()? {
+        for (tblid, (storage_code, model_code)) in unflush::read_partmap(&ksid)? {
+            let volatile = match unflush::decode_storage_code(storage_code) {
+                Some((volatile, _, _)) => volatile,
+                None => return Err(StorageEngineError::bad_metadata_in_table(&ksid, &tblid)),
+            };
+            let version_before = self::table_version(&ksid, &tblid, storage_code)?
+                .unwrap_or(TABLE_FORMAT_VERSION_CURRENT);
+            let migrated = if ksid == SYSTEM {
+                self::migrate_table::<SystemTable>(
+                    &ksid,
+                    &tblid,
+                    volatile,
+                    model_code,
+                    version_before,
+                )?
+            } else {
+                self::migrate_table::<Table>(&ksid, &tblid, volatile, model_code, version_before)?
+            };
+            summary.push(migrated);
+        }
+    }
+    // now rewrite the metadata: the partmaps and finally the preload
+    let store = unflush::read_full()?;
+    for keyspace in store.keyspaces.iter() {
+        if keyspace.key() != &SYSTEM {
+            flush::oneshot::flush_partmap(&Autoflush, keyspace.key(), keyspace.value().as_ref())?;
+        }
+    }
+    flush::oneshot::flush_partmap(&Autoflush, &SYSTEM, &store.system)?;
+    flush::oneshot::flush_preload(&Autoflush, &store)?;
+    if self::preload_version()? != preload::FORMAT_VERSION_CURRENT {
+        return Err(StorageEngineError::corrupted_preload());
+    }
+    Ok(summary)
+}
@@ -68,6 +68,7 @@ pub mod error;
 pub mod flush;
 pub mod interface;
 pub mod iter;
+pub mod migrate;
 pub mod preload;
 pub mod sengine;
 pub mod unflush;
@@ -236,6 +237,29 @@ mod se {
         Ok(())
     }
 
+    /// Serialize the deadlines of the keys in a table and write them to a provided buffer. This
+    /// uses the same layout as a map, with every value being a deadline (a unix timestamp in
+    /// milliseconds) as a little endian 64-bit integer
+    /// ```text
+    /// [LEN:8B]([KLEN:8B][VLEN:8B][K][DEADLINE:8B])*
+    /// ```
+    pub fn raw_serialize_deadlines<W: Write>(
+        deadlines: &Coremap<Data, u64>,
+        w: &mut W,
+    ) -> IoResult<()> {
+        unsafe {
+            w.write_all(raw_byte_repr(&to_64bit_native_endian!(deadlines.len())))?;
+            for kv in deadlines.iter() {
+                let key = kv.key().as_ref();
+                w.write_all(raw_byte_repr(&to_64bit_native_endian!(key.len())))?;
+                w.write_all(raw_byte_repr(&to_64bit_native_endian!(8)))?;
+                w.write_all(key)?;
+                w.write_all(&kv.value().to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
     /// Serialize a set and write it to a provided buffer
     pub fn raw_serialize_set<W, K, V>(map: &Coremap<K, V>, w: &mut W) -> IoResult<()>
     where
@@ -341,6 +365,7 @@ mod se {
 mod de {
     use super::iter::{RawSliceIter, RawSliceIterBorrowed};
     use super::{Array, Coremap, Data, Hash, HashSet};
+    use crate::auth::rules::UserRules;
     use crate::kvengine::LockedVec;
     use core::ptr;
     use parking_lot::RwLock;
@@ -409,6 +434,15 @@ mod de {
         }
     }
 
+    impl DeserializeFrom for UserRules {
+        fn is_expected_len(_clen: usize) -> bool {
+            true
+        }
+        fn from_slice(slice: &[u8]) -> Self {
+            Self::from_repr(slice)
+        }
+    }
+
     pub fn deserialize_map_ctype<T, U>(data: &[u8]) -> Option<Coremap<T, U>>
     where
         T: Eq + Hash + DeserializeFrom,
@@ -514,6 +548,29 @@ mod de {
         }
     }
 
+    /// Deserialize the deadlines of the keys in a table (see `se::raw_serialize_deadlines`)
+    pub fn deserialize_deadlines(data: &[u8]) -> Option<Vec<(Data, u64)>> {
+        let mut rawiter = RawSliceIter::new(data);
+        let len = rawiter.next_64bit_integer_to_usize()?;
+        let mut deadlines = Vec::new();
+        deadlines.try_reserve(len).ok()?;
+        for _ in 0..len {
+            let (lenkey, lenval) = rawiter.next_64bit_integer_pair_to_usize()?;
+            if lenval != 8 {
+                return None;
+            }
+            let key = rawiter.next_owned_data(lenkey)?;
+            let deadline = rawiter.next_borrowed_slice(8)?.try_into().ok()?;
+            deadlines.push((key, u64::from_le_bytes(deadline)));
+        }
+        if rawiter.end_of_allocation() {
+            Some(deadlines)
+        } else {
+            // nope, someone gave us more data
+            None
+        }
+    }
+
     pub fn deserialize_list_map(bytes: &[u8]) -> Option<Coremap<Data, LockedVec>> {
         let mut rawiter = RawSliceIter::new(bytes);
         // get the len
@@ -54,6 +54,20 @@ const META_SEGMENT: u8 = META_SEGMENT_LE;
 #[cfg(target_endian = "big")]
 const META_SEGMENT: u8 = META_SEGMENT_BE;
 
+/// The format version that we currently write
+pub const FORMAT_VERSION_CURRENT: u8 = 1;
+/// The version nibble used by the v1 (Cyanstore 1A) meta segment
+const META_VERSION_V1: u8 = 0b1000;
+
+/// Returns the format version marked in the meta segment of a `PRELOAD`, if it is one that
+/// we know of
+pub fn read_format_version(preload: &[u8]) -> Option<u8> {
+    match preload.first() {
+        Some(meta) if (meta >> 4) == META_VERSION_V1 && (meta & 0x0F) < 2 => Some(1),
+        _ => None,
+    }
+}
+
 /// Generate the `PRELOAD` disk file for this instance
 /// ```text
 /// [1B: Endian Mark/Version Mark (padded)] => Meta segment
@@ -60,6 +60,25 @@ fn test_ser_de_few_elements() {
         .all(|kv| cmap.get(kv.key()).unwrap().eq(kv.value())));
 }
 
+#[test]
+fn test_ser_de_acl_rules() {
+    use crate::auth::rules::{Rule, UserRules};
+    let cmap: Coremap<Array<u8, 40>, UserRules> = Coremap::new();
+    let mut rules = UserRules::default();
+    for rule in ["on", "~user:*", "+@string", "-DEL"] {
+        rules.apply(Rule::parse(rule.as_bytes()).unwrap());
+    }
+    cmap.upsert(unsafe { Array::from_slice("guest") }, rules);
+    cmap.upsert(unsafe { Array::from_slice("other") }, UserRules::default());
+    let mut ser = Vec::new();
+    se::raw_serialize_map(&cmap, &mut ser).unwrap();
+    let de: Coremap<Array<u8, 40>, UserRules> = de::deserialize_into(&ser).unwrap();
+    assert_eq!(de.len(), cmap.len());
+    assert!(de
+        .iter()
+        .all(|kv| cmap.get(kv.key()).unwrap().eq(kv.value())));
+}
+
 cfg_test!(
     use libstress::utils::generate_random_string_vector;
     use rand::thread_rng;
@@ -189,6 +208,160 @@ mod preload_tests {
     }
 }
 
+mod migrate_tests {
+    use super::*;
+    use crate::corestore::memstore::{Memstore, ObjectID};
+    use crate::corestore::table::Table;
+    use crate::corestore::Data;
+    use crate::storage::v1::flush::Autoflush;
+    use crate::storage::v1::interface::TABLE_FORMAT_VERSION_CURRENT;
+    use std::fs;
+    fn get_hello(tbl: &Table) -> Data {
+        tbl.get_kvstore()
+            .unwrap()
+            .get(Data::from("hello"))
+            .unwrap()
+            .unwrap()
+            .clone()
+    }
+    #[test]
+    fn test_preload_format_version() {
+        let mut v = Vec::new();
+        preload::raw_generate_preload(&mut v, &Memstore::new_default()).unwrap();
+        assert_eq!(
+            preload::read_format_version(&v).unwrap(),
+            preload::FORMAT_VERSION_CURRENT
+        );
+        // not a meta segment that we know of
+        assert!(preload::read_format_version(&[0b0100_0000]).is_none());
+        assert!(preload::read_format_version(&[]).is_none());
+    }
+    #[test]
+    fn test_table_header() {
+        let tbl = Table::new_default_kve();
+        let (mut v1, mut current) = (Vec::new(), Vec::new());
+        interface::serialize_into_slow_buffer_v1(&mut v1, &tbl).unwrap();
+        interface::serialize_into_slow_buffer(&mut current, &tbl).unwrap();
+        assert_eq!(interface::split_table_header(&v1), (1, &v1[..]));
+        assert_eq!(
+            interface::split_table_header(&current),
+            (TABLE_FORMAT_VERSION_CURRENT, &v1[..])
+        );
+    }
+    #[test]
+    fn test_migrate_table() {
+        let tbl = Table::new_default_kve();
+        tbl.get_kvstore()
+            .unwrap()
+            .set("hello".into(), "world".into())
+            .unwrap();
+        let tblid = unsafe { ObjectID::from_slice("mytbl") };
+        let ksid = unsafe { ObjectID::from_slice("mymigrateks") };
+        fs::create_dir_all("data/ks/mymigrateks").unwrap();
+        // write the table like an older version would have
+        let mut file = fs::File::create("data/ks/mymigrateks/mytbl").unwrap();
+        interface::serialize_into_slow_buffer_v1(&mut file, &tbl).unwrap();
+        drop(file);
+        let bytes_before = fs::metadata("data/ks/mymigrateks/mytbl").unwrap().len();
+        assert_eq!(
+            interface::read_table_version("data/ks/mymigrateks/mytbl").unwrap(),
+            1
+        );
+        let summary = migrate::migrate_table::<Table>(
+            &ksid,
+            &tblid,
+            false,
+            bytemarks::BYTEMARK_MODEL_KV_BIN_BIN,
+            1,
+        )
+        .unwrap();
+        assert_eq!(summary.ksid, "mymigrateks");
+        assert_eq!(summary.tblid, "mytbl");
+        assert_eq!(summary.version_before, 1);
+        assert_eq!(summary.version_after, TABLE_FORMAT_VERSION_CURRENT);
+        assert_eq!(summary.bytes_before, bytes_before);
+        // the only difference is the header
+        assert_eq!(summary.bytes_after, bytes_before + 8);
+        assert_eq!(
+            interface::read_table_version("data/ks/mymigrateks/mytbl").unwrap(),
+            TABLE_FORMAT_VERSION_CURRENT
+        );
+        // and the rewritten table should still load
+        let ret = unflush::read_table::<Table>(
+            &ksid,
+            &tblid,
+            false,
+            bytemarks::BYTEMARK_MODEL_KV_BIN_BIN,
+        )
+        .unwrap();
+        assert_eq!(get_hello(&ret), Data::from("world"));
+    }
+    #[test]
+    fn test_migrate_full_mixed_versions() {
+        let store = Memstore::new_default();
+        let ksid = ObjectID::try_from_slice("mixedmigrateks").unwrap();
+        assert!(store.create_keyspace(ksid.clone()));
+        let ks = store.get_keyspace_atomic_ref(&ksid).unwrap();
+        for tblname in ["oldtbl", "newtbl"] {
+            let tbl = Table::new_default_kve();
+            tbl.get_kvstore()
+                .unwrap()
+                .set("hello".into(), tblname.into())
+                .unwrap();
+            assert!(ks.create_table(ObjectID::try_from_slice(tblname).unwrap(), tbl));
+        }
+        interface::create_tree_fresh(&Autoflush, &store).unwrap();
+        flush::oneshot::flush_preload(&Autoflush, &store).unwrap();
+        flush::flush_full(Autoflush, &store).unwrap();
+        // now rewrite one of the tables with the v1 writer
+        let oldtbl = ks.get_table_atomic_ref("oldtbl".as_bytes()).unwrap();
+        let mut file = fs::File::create("data/ks/mixedmigrateks/oldtbl").unwrap();
+        interface::serialize_into_slow_buffer_v1(&mut file, oldtbl.as_ref()).unwrap();
+        drop(file);
+        let old_bytes_before = fs::metadata("data/ks/mixedmigrateks/oldtbl").unwrap().len();
+        let versions = migrate::container_versions().unwrap();
+        assert!(versions.contains(&("mixedmigrateks:oldtbl".to_owned(), 1)));
+        assert!(versions.contains(&(
+            "mixedmigrateks:newtbl".to_owned(),
+            TABLE_FORMAT_VERSION_CURRENT
+        )));
+        // migrate and check the summary
+        let summary = migrate::migrate_full().unwrap();
+        let get_summary = |tblid: &str| {
+            summary
+                .iter()
+                .find(|s| s.ksid == "mixedmigrateks" && s.tblid == tblid)
+                .unwrap()
+        };
+        let old = get_summary("oldtbl");
+        assert_eq!(old.version_before, 1);
+        assert_eq!(old.version_after, TABLE_FORMAT_VERSION_CURRENT);
+        assert_eq!(old.bytes_before, old_bytes_before);
+        assert_eq!(old.bytes_after, old_bytes_before + 8);
+        let new = get_summary("newtbl");
+        assert_eq!(new.version_before, TABLE_FORMAT_VERSION_CURRENT);
+        assert_eq!(new.version_after, TABLE_FORMAT_VERSION_CURRENT);
+        assert_eq!(new.bytes_before, new.bytes_after);
+        // every file is at the current version now
+        let versions = migrate::container_versions().unwrap();
+        assert!(versions
+            .iter()
+            .filter(|(name, _)| name.starts_with("mixedmigrateks:"))
+            .all(|(_, version)| *version == TABLE_FORMAT_VERSION_CURRENT));
+        // and loads correctly
+        for tblname in ["oldtbl", "newtbl"] {
+            let tbl = unflush::read_table::<Table>(
+                &ksid,
+                &ObjectID::try_from_slice(tblname).unwrap(),
+                false,
+                bytemarks::BYTEMARK_MODEL_KV_BIN_BIN,
+            )
+            .unwrap();
+            assert_eq!(get_hello(&tbl), Data::from(tblname));
+        }
+    }
+}
+
 mod bytemark_set_tests {
     use super::*;
     use crate::corestore::memstore::{Keyspace, ObjectID};
@@ -260,6 +433,64 @@ mod bytemark_set_tests {
         }
         assert_hmeq!(expected, ret);
     }
+    #[test]
+    fn test_bytemark_validate_json() {
+        let ks = Keyspace::empty();
+        unsafe {
+            ks.create_table(
+                ObjectID::from_slice("docs"),
+                Table::new_kve_with_volatile(true)
+                    .with_json_validation(true)
+                    .unwrap(),
+            );
+        }
+        let mut v = Vec::new();
+        se::raw_serialize_partmap(&mut v, &ks).unwrap();
+        let ret: HashMap<ObjectID, (u8, u8)> = de::deserialize_set_ctype_bytemark(&v).unwrap();
+        let (storage_code, model_code) = ret[unsafe { &ObjectID::from_slice("docs") }];
+        assert_eq!(
+            storage_code,
+            bytemarks::BYTEMARK_STORAGE_VOLATILE | bytemarks::BYTEMARK_PROPERTY_VALIDATE_JSON
+        );
+        assert_eq!(model_code, bytemarks::BYTEMARK_MODEL_KV_BIN_BIN);
+        assert_eq!(
+            unflush::decode_storage_code(storage_code),
+            Some((true, true, false))
+        );
+        assert_eq!(unflush::decode_storage_code(0), Some((false, false, false)));
+        // unknown properties are rejected
+        assert_eq!(unflush::decode_storage_code(0b1000), None);
+    }
+    #[test]
+    fn test_bytemark_ordered() {
+        let ks = Keyspace::empty();
+        unsafe {
+            ks.create_table(
+                ObjectID::from_slice("events"),
+                Table::new_kve_with_volatile(false)
+                    .with_ordered_keys(true)
+                    .unwrap(),
+            );
+        }
+        let mut v = Vec::new();
+        se::raw_serialize_partmap(&mut v, &ks).unwrap();
+        let ret: HashMap<ObjectID, (u8, u8)> = de::deserialize_set_ctype_bytemark(&v).unwrap();
+        let (storage_code, _) = ret[unsafe { &ObjectID::from_slice("events") }];
+        assert_eq!(
+            storage_code,
+            bytemarks::BYTEMARK_STORAGE_PERSISTENT | bytemarks::BYTEMARK_PROPERTY_ORDERED
+        );
+        assert_eq!(
+            unflush::decode_storage_code(storage_code),
+            Some((false, false, true))
+        );
+        // list tables can't keep an ordered index
+        assert!(
+            Table::new_kve_listmap_with_data(Default::default(), false, false, false)
+                .with_ordered_keys(true)
+                .is_none()
+        );
+    }
 }
 
 mod bytemark_actual_table_restore {
@@ -473,6 +704,47 @@ mod flush_routines {
         }
     }
     #[test]
+    fn test_flush_unflush_table_deadlines() {
+        let tbl = Table::new_default_kve();
+        let kve = tbl.get_kvstore().unwrap();
+        kve.set("hello".into(), "world".into()).unwrap();
+        kve.set("bye".into(), "world".into()).unwrap();
+        let deadline = crate::kvengine::expiry::now_millis() + 100_000;
+        assert!(kve.set_deadline(b"hello", Some(deadline)).unwrap());
+        let tblid = unsafe { ObjectID::from_slice("mytbl_ttl") };
+        let ksid = unsafe { ObjectID::from_slice("myks_ttl") };
+        // create the temp dir for this test
+        fs::create_dir_all("data/ks/myks_ttl").unwrap();
+        super::flush::oneshot::flush_table(&Autoflush, &tblid, &ksid, &tbl).unwrap();
+        let ret = super::unflush::read_table::<Table>(
+            &ksid,
+            &tblid,
+            false,
+            bytemarks::BYTEMARK_MODEL_KV_BIN_BIN,
+        )
+        .unwrap();
+        let ret_kve = ret.get_kvstore().unwrap();
+        let ttl = ret_kve.time_to_live(b"hello").unwrap().unwrap().unwrap();
+        assert!(ttl > 90_000 && ttl <= 100_000);
+        assert_eq!(ret_kve.time_to_live(b"bye").unwrap(), Some(None));
+        // once no key has a deadline, the deadlines file is removed
+        assert!(fs::metadata("data/ks/myks_ttl/mytbl_ttl.ttl").is_ok());
+        assert!(kve.set_deadline(b"hello", None).unwrap());
+        super::flush::oneshot::flush_table(&Autoflush, &tblid, &ksid, &tbl).unwrap();
+        assert!(fs::metadata("data/ks/myks_ttl/mytbl_ttl.ttl").is_err());
+        let ret = super::unflush::read_table::<Table>(
+            &ksid,
+            &tblid,
+            false,
+            bytemarks::BYTEMARK_MODEL_KV_BIN_BIN,
+        )
+        .unwrap();
+        assert_eq!(
+            ret.get_kvstore().unwrap().time_to_live(b"hello").unwrap(),
+            Some(None)
+        );
+    }
+    #[test]
     fn test_flush_unflush_keyspace() {
         // create the temp dir for this test
         fs::create_dir_all("data/ks/myks_1").unwrap();
@@ -535,6 +807,34 @@ mod flush_routines {
             );
         }
     }
+    #[test]
+    fn test_flush_table_full_made_persistent() {
+        // the keyspace's directory is created by the flush itself
+        let ksid = unsafe { ObjectID::from_slice("myks_alter") };
+        let tblid = unsafe { ObjectID::from_slice("mytbl_alter") };
+        let ks = Keyspace::empty();
+        assert!(ks.create_table(tblid.clone(), Table::new_kve_with_volatile(true)));
+        let tbl = ks.get_table_atomic_ref(&tblid).unwrap();
+        tbl.get_kvstore()
+            .unwrap()
+            .set("hello".into(), "world".into())
+            .unwrap();
+        assert!(tbl.set_volatile(false));
+        super::flush::flush_table_full(&Autoflush, &tblid, &ksid, &ks, &tbl).unwrap();
+        let ret = super::unflush::read_keyspace::<Keyspace>(&ksid).unwrap();
+        let tbl_ret = ret.tables.get(&tblid).unwrap();
+        assert!(!tbl_ret.is_volatile());
+        assert_eq!(
+            tbl_ret
+                .get_kvstore()
+                .unwrap()
+                .get(Data::from("hello"))
+                .unwrap()
+                .unwrap()
+                .clone(),
+            Data::from("world")
+        );
+    }
 }
 
 mod list_tests {
@@ -33,12 +33,13 @@ use crate::{
     corestore::{
         memstore::{Keyspace, Memstore, ObjectID, SystemKeyspace, SYSTEM},
         table::{SystemTable, Table},
+        Data,
     },
     storage::v1::{
         de::DeserializeInto,
         error::{ErrorContext, StorageEngineError, StorageEngineResult},
         flush::Autoflush,
-        interface::DIR_KSROOT,
+        interface::{self, DEADLINES_FILE_SUFFIX, DIR_KSROOT, TABLE_FORMAT_VERSION_CURRENT},
         preload::LoadedPartfile,
         Coremap,
     },
@@ -59,12 +60,17 @@ pub trait UnflushableKeyspace: Sized {
 impl UnflushableKeyspace for Keyspace {
     fn unflush_keyspace(partmap: LoadedPartfile, ksid: &ObjectID) -> StorageEngineResult<Self> {
         let ks: Coremap<ObjectID, Arc<Table>> = Coremap::with_capacity(partmap.len());
-        for (tableid, (table_storage_type, model_code)) in partmap.into_iter() {
-            if table_storage_type > 1 {
-                return Err(StorageEngineError::bad_metadata_in_table(ksid, &tableid));
-            }
-            let is_volatile = table_storage_type == bytemarks::BYTEMARK_STORAGE_VOLATILE;
-            let tbl = self::read_table::<Table>(ksid, &tableid, is_volatile, model_code)?;
+        for (tableid, (table_storage_code, model_code)) in partmap.into_iter() {
+            let (is_volatile, validate_json, ordered) =
+                match self::decode_storage_code(table_storage_code) {
+                    Some(decoded) => decoded,
+                    None => return Err(StorageEngineError::bad_metadata_in_table(ksid, &tableid)),
+                };
+            // the ordered index is built from the data that was just read
+            let tbl = self::read_table::<Table>(ksid, &tableid, is_volatile, model_code)?
+                .with_json_validation(validate_json)
+                .and_then(|tbl| tbl.with_ordered_keys(ordered))
+                .ok_or_else(|| StorageEngineError::bad_metadata_in_table(ksid, &tableid))?;
             ks.true_if_insert(tableid, Arc::new(tbl));
         }
         Ok(Keyspace::init_with_all_def_strategy(ks))
@@ -86,6 +92,21 @@ impl UnflushableKeyspace for SystemKeyspace {
     }
 }
 
+/// Decode a table's storage bytemark into (volatile, validate_json, ordered). Returns `None`
+/// if the bytemark has an invalid storage type or unknown properties
+pub const fn decode_storage_code(code: u8) -> Option<(bool, bool, bool)> {
+    let properties = code & !bytemarks::BYTEMARK_STORAGE_TYPE_MASK;
+    let known = bytemarks::BYTEMARK_PROPERTY_VALIDATE_JSON | bytemarks::BYTEMARK_PROPERTY_ORDERED;
+    if properties & !known != 0 {
+        return None;
+    }
+    Some((
+        code & bytemarks::BYTEMARK_STORAGE_TYPE_MASK == bytemarks::BYTEMARK_STORAGE_VOLATILE,
+        properties & bytemarks::BYTEMARK_PROPERTY_VALIDATE_JSON != 0,
+        properties & bytemarks::BYTEMARK_PROPERTY_ORDERED != 0,
+    ))
+}
+
 /// Tables that can be restored from disk storage
 pub trait UnflushableTable: Sized {
     /// Procedure to restore (deserialize) table from disk storage
@@ -106,7 +127,7 @@ impl UnflushableTable for Table {
         let ret = match model_code {
             // pure KVEBlob: [0, 3]
             x if x < 4 => {
-                let data = decode(filepath, volatile)?;
+                let data = decode(&filepath, volatile)?;
                 let (k_enc, v_enc) = unsafe {
                     // UNSAFE(@ohsayan): Safe because of the above match. Just a lil bitmagic
                     let key: bool = transmute(model_code >> 1);
@@ -117,7 +138,7 @@ impl UnflushableTable for Table {
             }
             // KVExtlistmap: [4, 7]
             x if x < 8 => {
-                let data = decode(filepath, volatile)?;
+                let data = decode(&filepath, volatile)?;
                 let (k_enc, v_enc) = unsafe {
                     // UNSAFE(@ohsayan): Safe because of the above match. Just a lil bitmagic
                     let code = model_code - 4;
@@ -133,6 +154,9 @@ impl UnflushableTable for Table {
                 ))
             }
         };
+        if !volatile {
+            ret.restore_deadlines(self::decode_deadlines(filepath)?);
+        }
         Ok(ret)
     }
 }
@@ -144,11 +168,15 @@ impl UnflushableTable for SystemTable {
         volatile: bool,
     ) -> StorageEngineResult<Self> {
         match model_code {
-            0 => {
+            bytemarks::SYSTEM_TABLE_AUTH => {
                 // this is the authmap
                 let authmap = decode(filepath, volatile)?;
                 Ok(SystemTable::new_auth(Arc::new(authmap)))
             }
+            bytemarks::SYSTEM_TABLE_ACL => {
+                let aclmap = decode(filepath, volatile)?;
+                Ok(SystemTable::new_acl(Arc::new(aclmap)))
+            }
             _ => Err(StorageEngineError::BadMetadata(
                 filepath.as_ref().to_string_lossy().to_string(),
             )),
@@ -168,12 +196,35 @@ fn decode<T: DeserializeInto>(
             "reading file {}",
             filepath.as_ref().to_string_lossy()
         ))?;
-        super::de::deserialize_into(&data).ok_or_else(|| {
+        // older table files are read as they are; `--migrate` rewrites them
+        let (version, data) = interface::split_table_header(&data);
+        if version > TABLE_FORMAT_VERSION_CURRENT {
+            return Err(StorageEngineError::BadMetadata(
+                filepath.as_ref().to_string_lossy().to_string(),
+            ));
+        }
+        super::de::deserialize_into(data).ok_or_else(|| {
             StorageEngineError::CorruptedFile(filepath.as_ref().to_string_lossy().to_string())
         })
     }
 }
 
+/// Read the key deadlines stored alongside the table at `filepath`. A missing file means
+/// that none of the keys had a deadline when the table was flushed
+fn decode_deadlines(filepath: impl AsRef<Path>) -> StorageEngineResult<Vec<(Data, u64)>> {
+    let mut path = filepath.as_ref().as_os_str().to_owned();
+    path.push(DEADLINES_FILE_SUFFIX);
+    let data = match fs::read(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(e).map_err_context(format!("reading file {}", path.to_string_lossy()))
+        }
+    };
+    super::de::deserialize_deadlines(&data)
+        .ok_or_else(|| StorageEngineError::CorruptedFile(path.to_string_lossy().to_string()))
+}
+
 /// Read a given table into a [`Table`] object
 ///
 /// This will take care of volatility and the model_code. Just make sure that you pass the proper
@@ -285,6 +285,193 @@ async fn auth_whoami_okay_rootuser() {
     )
 }
 
+// auth copy
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn auth_copy_okay() {
+    runeq!(
+        con,
+        query!("acl", "setuser", "copysrc", "on", "nopass", "~copy:*", "+get"),
+        Element::RespCode(RespCode::Okay)
+    );
+    runeq!(
+        con,
+        query!("auth", "copy", "copysrc", "copydst", "copypass"),
+        Element::RespCode(RespCode::Okay)
+    );
+    runeq!(
+        con,
+        query!("auth", "copy", "copysrc", "copydst", "copypass"),
+        Element::RespCode(RespCode::ErrorString("err-auth-already-claimed".to_owned()))
+    );
+    // the copy has a password of its own and the same permissions
+    assert_auth_bad_credentials!(con, query!("auth", "login", "copydst", "badpass"));
+    runeq!(
+        con,
+        query!("auth", "login", "copydst", "copypass"),
+        Element::RespCode(RespCode::Okay)
+    );
+    runeq!(
+        con,
+        query!("get", "copy:nosuchkey"),
+        Element::RespCode(RespCode::NotFound)
+    );
+    assert_auth_perm_error!(con, query!("get", "otherkey"));
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn auth_copy_fail_because_no_source() {
+    assert_auth_bad_credentials!(
+        con,
+        query!("auth", "copy", "nosuchuser", "copydst2", "copypass")
+    )
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_testuser = true)]
+async fn auth_copy_fail_because_not_root() {
+    assert_auth_perm_error!(
+        con,
+        query!("auth", "copy", "testuser", "copydst3", "copypass")
+    )
+}
+
+// acl
+#[sky_macros::dbtest_func]
+async fn acl_whoami_fail_because_disabled() {
+    assert_auth_disabled!(con, query!("acl", "whoami"))
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_testuser = true)]
+async fn acl_whoami_okay_testuser() {
+    runeq!(
+        con,
+        query!("acl", "whoami"),
+        Element::String(testsuite_data::TESTSUITE_TEST_USER.to_owned())
+    )
+}
+#[sky_macros::dbtest_func]
+async fn acl_cat_okay() {
+    let ret: Vec<String> = con.run_query(query!("acl", "cat")).await.unwrap();
+    assert_eq!(ret, vec!["string", "list", "generic", "server"]);
+}
+#[sky_macros::dbtest_func]
+async fn acl_cat_category_okay() {
+    let ret: Vec<String> = con.run_query(query!("acl", "cat", "list")).await.unwrap();
+    assert!(ret.contains(&"LGET".to_owned()));
+    assert!(!ret.contains(&"GET".to_owned()));
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_testuser = true)]
+async fn acl_log_has_denied_action() {
+    assert_auth_perm_error!(con, query!("auth", "listuser"));
+    // only root can read the log
+    runeq!(
+        con,
+        query!(
+            "auth",
+            "login",
+            testsuite_data::TESTSUITE_ROOT_USER,
+            testsuite_data::TESTSUITE_ROOT_TOKEN
+        ),
+        Element::RespCode(RespCode::Okay)
+    );
+    let ret = con.run_query_raw(&query!("acl", "log")).await.unwrap();
+    if let Element::Array(skytable::types::Array::Recursive(entries)) = ret {
+        assert!(entries.iter().any(|entry| match entry {
+            Element::Array(skytable::types::Array::Recursive(entry)) => {
+                entry[0] == Element::String("AUTH".to_owned())
+                    && entry[1] == Element::String(testsuite_data::TESTSUITE_TEST_USER.to_owned())
+            }
+            _ => false,
+        }));
+    } else {
+        panic!("Expected recursive array, got: {:?}", ret);
+    }
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn acl_log_reset_okay() {
+    runeq!(
+        con,
+        query!("acl", "log", "reset"),
+        Element::RespCode(RespCode::Okay)
+    )
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_testuser = true)]
+async fn acl_log_fail_because_not_root() {
+    assert_auth_perm_error!(con, query!("acl", "log"));
+    assert_auth_perm_error!(con, query!("acl", "log", "reset"));
+}
+#[sky_macros::dbtest_func]
+async fn acl_log_fail_because_disabled() {
+    assert_auth_disabled!(con, query!("acl", "log"))
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn acl_setuser_getuser_okay() {
+    runeq!(
+        con,
+        query!("acl", "setuser", "acluser1", "on", "nopass", "~acl:*", "+get"),
+        Element::RespCode(RespCode::Okay)
+    );
+    let ret: Vec<String> = con
+        .run_query(query!("acl", "getuser", "acluser1"))
+        .await
+        .unwrap();
+    assert_eq!(ret, vec!["on", "nopass", "~acl:*", "+GET"]);
+    runeq!(
+        con,
+        query!("acl", "getuser", "nosuchuser"),
+        Element::RespCode(RespCode::NotFound)
+    );
+    let ret: Vec<String> = con.run_query(query!("acl", "list")).await.unwrap();
+    assert!(ret.contains(&"user acluser1 on nopass ~acl:* +GET".to_owned()));
+    assert!(ret.contains(&"user root on ~* +@all".to_owned()));
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn acl_setuser_fail_bad_rule() {
+    runeq!(
+        con,
+        query!("acl", "setuser", "acluser2", "on", "+nosuchaction"),
+        Element::RespCode(RespCode::ErrorString("err-auth-bad-acl-rule".to_owned()))
+    );
+}
+#[sky_macros::dbtest_func(port = 2005, auth_testuser = true)]
+async fn acl_setuser_fail_because_not_root() {
+    assert_auth_perm_error!(con, query!("acl", "setuser", "acluser3", "on"))
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn acl_rules_are_enforced() {
+    runeq!(
+        con,
+        query!("acl", "setuser", "acluser4", "on", "nopass", "~acl:*", "+get"),
+        Element::RespCode(RespCode::Okay)
+    );
+    runeq!(
+        con,
+        query!("auth", "login", "acluser4", "anything"),
+        Element::RespCode(RespCode::Okay)
+    );
+    runeq!(
+        con,
+        query!("get", "acl:nosuchkey"),
+        Element::RespCode(RespCode::NotFound)
+    );
+    assert_auth_perm_error!(con, query!("get", "otherkey"));
+    assert_auth_perm_error!(con, query!("set", "acl:key", "value"));
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_rootuser = true)]
+async fn acl_save_load_fail_because_no_aclfile() {
+    runeq!(
+        con,
+        query!("acl", "save"),
+        Element::RespCode(RespCode::ErrorString("err-auth-no-aclfile".to_owned()))
+    );
+    runeq!(
+        con,
+        query!("acl", "load"),
+        Element::RespCode(RespCode::ErrorString("err-auth-no-aclfile".to_owned()))
+    );
+}
+#[sky_macros::dbtest_func(port = 2005, norun = true, auth_testuser = true)]
+async fn acl_save_load_fail_because_not_root() {
+    assert_auth_perm_error!(con, query!("acl", "save"));
+    assert_auth_perm_error!(con, query!("acl", "load"));
+}
+
 mod syntax_checks {
     use super::{NOAUTH, ONLYAUTH};
     use crate::auth::provider::testsuite_data::{
@@ -358,6 +545,15 @@ mod syntax_checks {
         );
     }
     #[sky_macros::dbtest_func(port = 2005, norun = true)]
+    async fn copy_aerr() {
+        assert_authn_aerr!(con, query!("auth", "copy", "src", "dst"), ONLYAUTH);
+        assert_authn_aerr!(
+            con,
+            query!("auth", "copy", "src", "dst", "password", "extra"),
+            ONLYAUTH
+        );
+    }
+    #[sky_macros::dbtest_func(port = 2005, norun = true)]
     async fn listuser_aerr() {
         assert_authn_aerr!(con, query!("auth", "listuser", "extra argument"), ONLYAUTH);
     }
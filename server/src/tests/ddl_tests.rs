@@ -109,6 +109,117 @@ mod __private {
             Element::RespCode(RespCode::Okay)
         );
     }
+    async fn test_create_table_validate_json() {
+        let mut rng = rand::thread_rng();
+        let tblname = utils::rand_alphastring(10, &mut rng);
+        let my_fqe = __MYKS__.to_owned() + ":" + &tblname;
+        query.push("create");
+        query.push("table");
+        query.push(&my_fqe);
+        query.push("keymap(str,str)");
+        query.push("volatile");
+        query.push("validate:json");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        let query = query!("inspect", "table", &my_fqe);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("Keymap { data:(str,str), volatile:true, validate:json }".to_owned())
+        );
+        let query = query!("use", &my_fqe);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        let query = query!("set", "x", r#"{"name": "sayan", "langs": ["rust"]}"#);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        let query = query!("set", "y", r#"{"name": "sayan""#);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString(
+                "err-invalid-json:16:unexpected end of input".to_owned()
+            ))
+        );
+        let query = query!("mset", "y", "[1, 2]", "z", "[1, 2,]");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString(
+                "err-invalid-json:6:unexpected character".to_owned()
+            ))
+        );
+        // nothing was written
+        let query = query!("exists", "y", "z");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(0)
+        );
+    }
+    async fn test_create_table_validate_json_wrong_model() {
+        let mut rng = rand::thread_rng();
+        let tblname = utils::rand_alphastring(10, &mut rng);
+        query.push("create");
+        query.push("table");
+        query.push(&tblname);
+        query.push("keymap(str,list<str>)");
+        query.push("validate:json");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("wrong-model".to_owned()))
+        );
+    }
+    async fn test_create_table_ordered() {
+        let mut rng = rand::thread_rng();
+        let tblname = utils::rand_alphastring(10, &mut rng);
+        let my_fqe = __MYKS__.to_owned() + ":" + &tblname;
+        query.push("create");
+        query.push("table");
+        query.push(&my_fqe);
+        query.push("keymap(str,str)");
+        query.push("validate:json");
+        query.push("ordered");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        let query = query!("inspect", "table", &my_fqe);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String(
+                "Keymap { data:(str,str), volatile:false, validate:json, ordered }".to_owned()
+            )
+        );
+        // list tables can't keep an ordered index
+        let query = query!(
+            "create",
+            "table",
+            tblname + "_lists",
+            "keymap(str,list<str>)",
+            "ordered"
+        );
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("wrong-model".to_owned()))
+        );
+    }
+    async fn test_create_table_duplicate_property() {
+        let mut rng = rand::thread_rng();
+        let tblname = utils::rand_alphastring(10, &mut rng);
+        query.push("create");
+        query.push("table");
+        query.push(&tblname);
+        query.push("keymap(str,str)");
+        query.push("volatile");
+        query.push("volatile");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("unknown-property".to_owned()))
+        );
+    }
     async fn test_drop_table() {
         let mut rng = rand::thread_rng();
         let tblname = utils::rand_alphastring(10, &mut rng);
@@ -150,6 +261,56 @@ mod __private {
             Element::RespCode(RespCode::Okay)
         );
     }
+    async fn test_alter_table_persistence() {
+        let mut rng = rand::thread_rng();
+        let tblname = utils::rand_alphastring(10, &mut rng);
+        let my_fqe = __MYKS__.to_owned() + ":" + &tblname;
+        runeq!(
+            con,
+            query!("create", "table", &my_fqe, "keymap(str,str)", "volatile"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("alter", "table", &my_fqe, "persistent"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("inspect", "table", &my_fqe),
+            Element::String("Keymap { data:(str,str), volatile:false }".to_owned())
+        );
+        runeq!(
+            con,
+            query!("alter", "table", &my_fqe, "volatile"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("inspect", "table", &my_fqe),
+            Element::String("Keymap { data:(str,str), volatile:true }".to_owned())
+        );
+    }
+    async fn test_alter_table_persistence_errors() {
+        let mut rng = rand::thread_rng();
+        let tblname = utils::rand_alphastring(10, &mut rng);
+        let my_fqe = __MYKS__.to_owned() + ":" + &tblname;
+        runeq!(
+            con,
+            query!("alter", "table", &my_fqe, "volatile"),
+            Element::RespCode(RespCode::ErrorString("container-not-found".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("alter", "table", &__MYENTITY__, "transient"),
+            Element::RespCode(RespCode::ErrorString("unknown-property".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("alter", "keyspace", &__MYKS__, "volatile"),
+            Element::RespCode(RespCode::ErrorString("unknown-ddl-query".to_owned()))
+        );
+    }
     async fn test_use() {
         query.push("USE");
         query.push(&__MYENTITY__);
@@ -197,4 +358,63 @@ mod __private {
             ]))
         );
     }
+    async fn test_whereami_new_connection() {
+        let mut other = skytable::AsyncConnection::new("127.0.0.1", 2003)
+            .await
+            .unwrap();
+        // a new connection always starts out in the default table
+        runeq!(
+            other,
+            query!("whereami"),
+            Element::Array(Array::NonNullStr(vec![
+                "default".to_owned(),
+                "default".to_owned()
+            ]))
+        );
+    }
+    async fn test_drop_table_in_use_by_another_connection() {
+        let mut other = skytable::AsyncConnection::new("127.0.0.1", 2003)
+            .await
+            .unwrap();
+        let still_in_use = Element::RespCode(RespCode::ErrorString("still-in-use".to_owned()));
+        // we're using the table, so it can't be dropped from under us
+        runeq!(other, query!("drop", "table", &__MYENTITY__), still_in_use);
+        runeq!(
+            con,
+            query!("set", "x", "100"),
+            Element::RespCode(RespCode::Okay)
+        );
+        // once we switch away, it can be dropped
+        runeq!(
+            con,
+            query!("use", "default"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            other,
+            query!("drop", "table", &__MYENTITY__),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("use", &__MYENTITY__),
+            Element::RespCode(RespCode::ErrorString("container-not-found".to_owned()))
+        );
+        // and then recreated and used again
+        runeq!(
+            other,
+            query!("create", "table", &__MYENTITY__, "keymap(str,str)"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("use", &__MYENTITY__),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("get", "x"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
 }
@@ -35,7 +35,11 @@
 #[sky_macros::dbtest_module]
 mod __private {
     #[cfg(test)]
-    use skytable::{types::Array, Element, Query, RespCode};
+    use skytable::{
+        query,
+        types::{Array, FlatElement},
+        Element, Query, RespCode,
+    };
     /// Test a HEYA query: The server should return HEY!
     async fn test_heya() {
         query.push("heya");
@@ -233,7 +237,7 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
         // now delete them
         let mut query = Query::new();
@@ -247,6 +251,57 @@ mod __private {
         );
     }
 
+    /// Test a DEL query with missing and repeated keys
+    async fn test_del_multiple_mixed() {
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        // a repeated key is only deleted (and counted) once
+        runeq!(
+            con,
+            query!("del", "x", "a", "x", "y", "b"),
+            Element::UnsignedInt(2)
+        );
+        runeq!(con, query!("exists", "x", "y"), Element::UnsignedInt(0));
+    }
+
+    /// Test an MDEL query: which is an alias for DEL
+    async fn test_mdel_multiple_mixed() {
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        runeq!(
+            con,
+            query!("mdel", "x", "a", "x", "y", "b"),
+            Element::UnsignedInt(2)
+        );
+        runeq!(con, query!("exists", "x", "y"), Element::UnsignedInt(0));
+        runeq!(
+            con,
+            query!("mdel"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+
+    /// Test a DEL query with more keys than the pipeline length limit
+    async fn test_del_more_than_pipeline_length() {
+        let mut query = Query::from("uset");
+        for i in 0..2000 {
+            query.push(format!("key{i}"));
+            query.push(i.to_string());
+        }
+        runeq!(con, query, Element::UnsignedInt(2000));
+        let mut query = Query::from("del");
+        for i in 0..2000 {
+            query.push(format!("key{i}"));
+        }
+        runeq!(con, query, Element::UnsignedInt(2000));
+    }
+
     /// Test a DEL query with an incorrect number of arguments
     async fn test_del_syntax_error() {
         query.push("del");
@@ -268,7 +323,7 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
         // now check if they exist
         let mut query = Query::new();
@@ -304,7 +359,7 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
         // now get them
         let mut query = Query::new();
@@ -334,7 +389,7 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
         let mut query = Query::new();
         query.push("mget");
@@ -372,7 +427,7 @@ mod __private {
         query.push("100");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(1)
+            Element::RespCode(RespCode::Okay)
         );
     }
 
@@ -388,7 +443,7 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
     }
 
@@ -404,13 +459,13 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
         // now try to set them again with just another new key
         let mut query = Query::new();
         query.push("mset");
         query.push("x");
-        query.push("100");
+        query.push("999");
         query.push("y");
         query.push("200");
         query.push("z");
@@ -419,8 +474,11 @@ mod __private {
         query.push("apple");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(1)
+            Element::RespCode(RespCode::Okay)
         );
+        // only the new key was set
+        runeq!(con, query!("get", "x"), Element::String("100".to_owned()));
+        runeq!(con, query!("get", "a"), Element::String("apple".to_owned()));
     }
 
     /// Test an MSET query with the wrong number of arguments
@@ -450,7 +508,7 @@ mod __private {
         query.push("100");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(1)
+            Element::RespCode(RespCode::Okay)
         );
         // now attempt to update it
         // first set the keys
@@ -476,7 +534,7 @@ mod __private {
         query.push("300");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::UnsignedInt(3)
+            Element::RespCode(RespCode::Okay)
         );
         // now try to update them with just another new key
         let mut query = Query::new();
@@ -815,6 +873,60 @@ mod __private {
         );
     }
 
+    /// Test `DBSIZE` for tables in another keyspace
+    async fn test_dbsize_other_keyspace() {
+        let mut rng = rand::thread_rng();
+        let ksname = libstress::utils::rand_alphastring(10, &mut rng);
+        let blobs = format!("{}:blobs", ksname);
+        let lists = format!("{}:lists", ksname);
+        runeq!(
+            con,
+            query!("create", "keyspace", &ksname),
+            Element::RespCode(RespCode::Okay)
+        );
+        create_table_and_switch!(con, &blobs, "keymap(str,str)");
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        create_table_and_switch!(con, &lists, "keymap(str,list<str>)");
+        runeq!(
+            con,
+            query!("lset", "mylist", "a", "b", "c"),
+            Element::RespCode(RespCode::Okay)
+        );
+        switch_entity!(con, &__MYENTITY__);
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(con, query!("dbsize", &blobs), Element::UnsignedInt(2));
+        runeq!(con, query!("dbsize", &lists), Element::UnsignedInt(1));
+        // the connection's entity is unchanged
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(1));
+        runeq!(
+            con,
+            query!("whereami"),
+            Element::Array(Array::NonNullStr(vec![__MYKS__, __MYTABLE__]))
+        );
+    }
+
+    /// Test `DBSIZE` for a table that doesn't exist
+    async fn test_dbsize_container_not_found() {
+        let not_found = Element::RespCode(RespCode::ErrorString("container-not-found".to_owned()));
+        runeq!(
+            con,
+            query!("dbsize", "nosuchks:nosuchtable"),
+            not_found.clone()
+        );
+        runeq!(
+            con,
+            query!("dbsize", format!("{}:nosuchtable", __MYKS__)),
+            not_found
+        );
+    }
+
     /// Test `DBSIZE` with an incorrect number of arguments
     async fn test_dbsize_syntax_error() {
         query.push("dbsize");
@@ -827,6 +939,125 @@ mod __private {
         );
     }
 
+    /// Test `DEBUG POPULATE`
+    async fn test_debug_populate() {
+        runeq!(
+            con,
+            query!("debug", "populate", "100"),
+            Element::UnsignedInt(100)
+        );
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(100));
+        runeq!(
+            con,
+            query!("exists", "key:0", "key:99", "key:100"),
+            Element::UnsignedInt(2)
+        );
+        runeq!(
+            con,
+            query!("debug", "populate", "10", "mykey", "64"),
+            Element::UnsignedInt(10)
+        );
+        runeq!(con, query!("keylen", "mykey:9"), Element::UnsignedInt(64));
+    }
+
+    /// Test that `DEBUG POPULATE` doesn't touch existing keys
+    async fn test_debug_populate_existing() {
+        setkeys!(
+            con,
+            "mykey:1":"100"
+        );
+        runeq!(
+            con,
+            query!("debug", "populate", "3", "mykey"),
+            Element::UnsignedInt(2)
+        );
+        runeq!(
+            con,
+            query!("get", "mykey:1"),
+            Element::String("100".to_owned())
+        );
+    }
+
+    /// Test `DEBUG POPULATE` with bad arguments
+    async fn test_debug_populate_syntax_error() {
+        runeq!(
+            con,
+            query!("debug", "populate"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("debug", "populate", "10", "mykey", "16", "extra"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("debug", "populate", "ten"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(
+            con,
+            query!("debug", "populate", "10", "mykey", "big"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        // absurd counts and sizes are refused before anything is set
+        runeq!(
+            con,
+            query!("debug", "populate", "10000001"),
+            Element::RespCode(RespCode::ErrorString("err-overflow".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("debug", "populate", "10", "mykey", "1048577"),
+            Element::RespCode(RespCode::ErrorString("err-overflow".to_owned()))
+        );
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(0));
+    }
+
+    /// Test `DEBUG STRINGMATCH-LEN`
+    async fn test_debug_stringmatch_len() {
+        let matched_and_consumed = |matched: u64, consumed: u64| {
+            Element::Array(Array::Flat(vec![
+                FlatElement::UnsignedInt(matched),
+                FlatElement::UnsignedInt(consumed),
+            ]))
+        };
+        runeq!(
+            con,
+            query!("debug", "stringmatch-len", "user:*", "user:1000"),
+            matched_and_consumed(1, 9)
+        );
+        runeq!(
+            con,
+            query!("debug", "stringmatch-len", "user:?0", "user:1000"),
+            matched_and_consumed(0, 7)
+        );
+        runeq!(
+            con,
+            query!("debug", "stringmatch-len", "USER:*", "user:1000"),
+            matched_and_consumed(0, 0)
+        );
+        runeq!(
+            con,
+            query!("debug", "stringmatch-len", "USER:*", "user:1000", "nocase"),
+            matched_and_consumed(1, 9)
+        );
+    }
+
+    /// Test `DEBUG STRINGMATCH-LEN` with bad arguments
+    async fn test_debug_stringmatch_len_syntax_error() {
+        runeq!(
+            con,
+            query!("debug", "stringmatch-len", "user:*"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("debug", "stringmatch-len", "user:*", "user:1", "exact"),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+
     /// Test `FLUSHDB`
     async fn test_flushdb_okay() {
         // first set the keys
@@ -895,6 +1126,59 @@ mod __private {
         );
     }
 
+    /// Test `FLUSHDB` on another table, which leaves the current table untouched
+    async fn test_flushdb_other_table() {
+        let other = __MYKS__.clone() + ":" + &__MYTABLE__ + "fl";
+        create_table_and_switch!(con, &other, "keymap(str,str)");
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        switch_entity!(con, &__MYENTITY__);
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(
+            con,
+            query!("flushdb", &other),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(con, query!("dbsize", &other), Element::UnsignedInt(0));
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(1));
+        runeq!(
+            con,
+            query!("whereami"),
+            Element::Array(Array::NonNullStr(vec![__MYKS__, __MYTABLE__]))
+        );
+    }
+
+    /// Test that large tables can only be flushed with `force` (the test server has
+    /// `protect_large_flush` enabled)
+    async fn test_flushdb_protected() {
+        let protected = Element::RespCode(RespCode::ErrorString("err-protected-flush".to_owned()));
+        runeq!(
+            con,
+            query!("debug", "populate", "100001"),
+            Element::UnsignedInt(100_001)
+        );
+        runeq!(con, query!("flushdb"), protected.clone());
+        runeq!(con, query!("flushdb", &__MYENTITY__), protected);
+        runeq!(
+            con,
+            query!("flushdb", &__MYENTITY__, "forced"),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(100_001));
+        runeq!(
+            con,
+            query!("flushdb", &__MYENTITY__, "force"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(0));
+    }
+
     /// Test `USET` which returns okay
     ///
     /// `USET` almost always returns okay for the correct number of key(s)/value(s)
@@ -965,6 +1249,51 @@ mod __private {
         );
     }
 
+    /// Test `KEYLEN` with a large value
+    async fn test_keylen_large_value() {
+        let value = "a".repeat(4 * 1024 * 1024);
+        query.push("set");
+        query.push("x");
+        query.push(value.as_str());
+        runeq!(con, query, Element::RespCode(RespCode::Okay));
+        // only the length is sent back
+        runeq!(
+            con,
+            query!("keylen", "x"),
+            Element::UnsignedInt(4 * 1024 * 1024)
+        );
+    }
+    async fn test_keylen_nil() {
+        runeq!(
+            con,
+            query!("keylen", "x"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+
+    /// Test `STRLEN`
+    async fn test_strlen() {
+        setkeys!(
+            con,
+            "x":"hello"
+        );
+        runeq!(con, query!("strlen", "x"), Element::UnsignedInt(5));
+        // unlike `KEYLEN`, missing keys have a length of 0
+        runeq!(con, query!("strlen", "y"), Element::UnsignedInt(0));
+    }
+    async fn test_strlen_syntax_error() {
+        runeq!(
+            con,
+            query!("strlen"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("strlen", "x", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+
     /// Test `KEYLEN` with an incorrect number of arguments
     async fn test_keylen_syntax_error_args_one() {
         query.push("keylen");
@@ -1178,32 +1507,1197 @@ mod __private {
                 Some("300".to_owned())
             ]))
         );
+        let mut query = Query::from("exists");
+        query.push(vec!["x", "y", "z"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(0)
+        );
     }
-    async fn test_pop_syntax_error() {
-        query.push("pop");
+    async fn test_mpop_single_key() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        query.push(vec!["mpop", "x"]);
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::RespCode(RespCode::ActionError)
+            Element::Array(Array::Str(vec![Some("100".to_owned())]))
+        );
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::Array(Array::Str(vec![None]))
         );
     }
-    async fn test_pop_okay() {
+    async fn test_swapkeys_okay() {
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        query.push(vec!["swapkeys", "x", "y"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        let mut query = Query::from("mget");
+        query.push(vec!["x", "y"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::Array(Array::Str(vec![
+                Some("200".to_owned()),
+                Some("100".to_owned())
+            ]))
+        );
+    }
+    async fn test_swapkeys_missing_untouched() {
         setkeys!(
             con,
             "x":"100"
         );
-        query.push("pop");
+        query.push(vec!["swapkeys", "x", "y"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("second-key-not-found".to_owned()))
+        );
+        let mut query = Query::from("swapkeys");
+        query.push(vec!["y", "x"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("first-key-not-found".to_owned()))
+        );
+        let mut query = Query::from("get");
         query.push("x");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
             Element::String("100".to_owned())
         );
     }
-    async fn test_pop_nil() {
-        query.push("pop");
+    async fn test_swapkeys_syntax_error() {
+        query.push(vec!["swapkeys", "x"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_move_okay() {
+        let dest = __MYTABLE__.clone() + "mv";
+        let mut query = Query::from("create");
+        query.push(vec!["table", &dest, "keymap(str,str)"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("move");
+        query.push(vec!["x", &dest]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(1)
+        );
+        // the key should no longer be in the source table
+        let mut query = Query::from("exists");
         query.push("x");
         assert_eq!(
             con.run_query_raw(&query).await.unwrap(),
-            Element::RespCode(RespCode::NotFound)
+            Element::UnsignedInt(0)
+        );
+        // and a missing key won't be moved
+        let mut query = Query::from("move");
+        query.push(vec!["x", &dest]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(0)
+        );
+    }
+    async fn test_move_dest_exists_untouched() {
+        let dest = __MYTABLE__.clone() + "mv";
+        let mut query = Query::from("create");
+        query.push(vec!["table", &dest, "keymap(str,str)"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("use");
+        query.push(__MYKS__.clone() + ":" + &dest);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        setkeys!(
+            con,
+            "x":"200"
+        );
+        let mut query = Query::from("use");
+        query.push(&__MYENTITY__);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        let mut query = Query::from("move");
+        query.push(vec!["x", &dest]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(0)
+        );
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+    }
+    async fn test_move_other_keyspace() {
+        let dest = __MYTABLE__.clone() + "mv";
+        let mut query = Query::from("create");
+        query.push(vec!["table", &dest, "keymap(str,str)"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        // a fully qualified entity in the same keyspace is fine
+        let target = __MYKS__.clone() + ":" + &dest;
+        let mut query = Query::from("move");
+        query.push(vec!["y", &target]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(1)
+        );
+        // but another keyspace isn't
+        let mut query = Query::from("move");
+        query.push(vec!["x", "default:default"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("err-cross-keyspace".to_owned()))
+        );
+        // and the key stays where it was
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+    }
+    async fn test_move_syntax_error() {
+        query.push(vec!["move", "x"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_pop_syntax_error() {
+        query.push("pop");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_pop_okay() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        query.push("pop");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+    }
+    async fn test_pop_nil() {
+        query.push("pop");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_getdel_okay() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(
+            con,
+            query!("getdel", "x"),
+            Element::String("100".to_owned())
+        );
+        runeq!(
+            con,
+            query!("get", "x"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_getdel_nil() {
+        runeq!(
+            con,
+            query!("getdel", "x"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_getdel_expired() {
+        runeq!(
+            con,
+            query!("setnxex", "x", "1", "100"),
+            Element::UnsignedInt(1)
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        runeq!(
+            con,
+            query!("getdel", "x"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_getdel_syntax_error() {
+        runeq!(
+            con,
+            query!("getdel"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("getdel", "x", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_object_encoding() {
+        setkeys!(
+            con,
+            "int":"12345",
+            "notint":"007",
+            "short":"sayan",
+            "long":"this is a value that is much longer than forty four bytes"
+        );
+        for (key, encoding) in [
+            ("int", "int"),
+            ("notint", "embstr"),
+            ("short", "embstr"),
+            ("long", "raw"),
+        ] {
+            let mut query = Query::from("object");
+            query.push(vec!["encoding", key]);
+            assert_eq!(
+                con.run_query_raw(&query).await.unwrap(),
+                Element::String(encoding.to_owned())
+            );
+        }
+    }
+    async fn test_object_encoding_nil() {
+        let mut query = Query::from("object");
+        query.push(vec!["encoding", "x"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_object_syntax_error() {
+        let mut query = Query::from("object");
+        query.push("encoding");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+        let mut query = Query::from("object");
+        query.push(vec!["refcount", "x"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+    async fn test_incr_okay() {
+        runeq!(con, query!("incr", "x"), Element::UnsignedInt(1));
+        runeq!(con, query!("incr", "x"), Element::UnsignedInt(2));
+        runeq!(con, query!("get", "x"), Element::String("2".to_owned()));
+        setkeys!(
+            con,
+            "y":"100"
+        );
+        runeq!(con, query!("incr", "y"), Element::UnsignedInt(101));
+    }
+    async fn test_decr_okay() {
+        setkeys!(
+            con,
+            "x":"1"
+        );
+        runeq!(con, query!("decr", "x"), Element::UnsignedInt(0));
+        // negative values are returned as strings
+        runeq!(con, query!("decr", "x"), Element::String("-1".to_owned()));
+        runeq!(con, query!("incr", "x"), Element::UnsignedInt(0));
+        runeq!(con, query!("decr", "y"), Element::String("-1".to_owned()));
+    }
+    async fn test_incr_wrongtype() {
+        setkeys!(
+            con,
+            "x":"sayan"
+        );
+        runeq!(
+            con,
+            query!("incr", "x"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(con, query!("get", "x"), Element::String("sayan".to_owned()));
+    }
+    async fn test_incr_overflow() {
+        setkeys!(
+            con,
+            "x":"9223372036854775807",
+            "y":"-9223372036854775808"
+        );
+        runeq!(
+            con,
+            query!("incr", "x"),
+            Element::RespCode(RespCode::ErrorString("err-overflow".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("decr", "y"),
+            Element::RespCode(RespCode::ErrorString("err-overflow".to_owned()))
+        );
+    }
+    async fn test_incr_syntax_error() {
+        runeq!(
+            con,
+            query!("incr"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("decr", "x", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_incrby_okay() {
+        runeq!(con, query!("incrby", "x", "10"), Element::UnsignedInt(10));
+        runeq!(con, query!("incrby", "x", "-4"), Element::UnsignedInt(6));
+        runeq!(con, query!("get", "x"), Element::String("6".to_owned()));
+    }
+    async fn test_decrby_okay() {
+        setkeys!(
+            con,
+            "x":"10"
+        );
+        runeq!(con, query!("decrby", "x", "4"), Element::UnsignedInt(6));
+        runeq!(
+            con,
+            query!("decrby", "x", "10"),
+            Element::String("-4".to_owned())
+        );
+        runeq!(con, query!("decrby", "x", "-4"), Element::UnsignedInt(0));
+    }
+    async fn test_incrby_bad_delta() {
+        runeq!(
+            con,
+            query!("incrby", "x", "ten"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(
+            con,
+            query!("decrby", "x", "99999999999999999999"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+    }
+    async fn test_incrby_overflow() {
+        setkeys!(
+            con,
+            "x":"9223372036854775800"
+        );
+        runeq!(
+            con,
+            query!("incrby", "x", "100"),
+            Element::RespCode(RespCode::ErrorString("err-overflow".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("decrby", "y", "-9223372036854775808"),
+            Element::RespCode(RespCode::ErrorString("err-overflow".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("get", "x"),
+            Element::String("9223372036854775800".to_owned())
+        );
+    }
+    async fn test_incrby_syntax_error() {
+        runeq!(
+            con,
+            query!("incrby", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("decrby", "x", "1", "2"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_incrbyfloat_okay() {
+        runeq!(
+            con,
+            query!("incrbyfloat", "x", "10.5"),
+            Element::String("10.5".to_owned())
+        );
+        runeq!(
+            con,
+            query!("incrbyfloat", "x", "-0.5"),
+            Element::String("10".to_owned())
+        );
+        runeq!(con, query!("get", "x"), Element::String("10".to_owned()));
+        // integer values can be incremented too
+        setkeys!(
+            con,
+            "y":"3"
+        );
+        runeq!(
+            con,
+            query!("incrbyfloat", "y", "5e-7"),
+            Element::String("3.0000005".to_owned())
+        );
+    }
+    async fn test_incrbyfloat_wrongtype() {
+        setkeys!(
+            con,
+            "x":"abc"
+        );
+        runeq!(
+            con,
+            query!("incrbyfloat", "x", "1.5"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(
+            con,
+            query!("incrbyfloat", "y", "nan"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(
+            con,
+            query!("incrbyfloat", "y", "inf"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(con, query!("exists", "y"), Element::UnsignedInt(0));
+    }
+    async fn test_incrbyfloat_syntax_error() {
+        runeq!(
+            con,
+            query!("incrbyfloat", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_setnx_okay() {
+        runeq!(con, query!("setnx", "x", "100"), Element::UnsignedInt(1));
+        runeq!(con, query!("get", "x"), Element::String("100".to_owned()));
+    }
+    async fn test_setnx_exists() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(con, query!("setnx", "x", "200"), Element::UnsignedInt(0));
+        runeq!(con, query!("get", "x"), Element::String("100".to_owned()));
+    }
+    async fn test_setnx_syntax_error() {
+        runeq!(
+            con,
+            query!("setnx", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("setnx", "x", "100", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_getset_new_key() {
+        runeq!(
+            con,
+            query!("getset", "x", "100"),
+            Element::RespCode(RespCode::NotFound)
+        );
+        runeq!(con, query!("get", "x"), Element::String("100".to_owned()));
+    }
+    async fn test_getset_existing_key() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(
+            con,
+            query!("getset", "x", "200"),
+            Element::String("100".to_owned())
+        );
+        runeq!(con, query!("get", "x"), Element::String("200".to_owned()));
+    }
+    async fn test_getset_syntax_error() {
+        runeq!(
+            con,
+            query!("getset", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("getset", "x", "100", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_setnxex_okay() {
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "100", "sayan"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(1)
+        );
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("sayan".to_owned())
+        );
+    }
+    async fn test_setnxex_exists() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "100", "200"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(0)
+        );
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+    }
+    async fn test_setnxex_expires() {
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "1", "100"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(1)
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::NotFound)
+        );
+        // so it can be set again
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "100", "200"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(1)
+        );
+    }
+    async fn test_setnxex_bad_expiry() {
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "0", "100"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("err-invalid-expire-time".to_owned()))
+        );
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "-1", "100"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+    }
+    async fn test_setnxex_syntax_error() {
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "100"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_getex_no_option() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("getex");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+        let mut query = Query::from("getex");
+        query.push("y");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_getex_px_expires() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "px", "500"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_getex_persist() {
+        let mut query = Query::from("setnxex");
+        query.push(vec!["x", "1", "100"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(1)
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "persist"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+    }
+    async fn test_getex_exat_in_the_past() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "exat", "1"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("100".to_owned())
+        );
+        let mut query = Query::from("exists");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(0)
+        );
+    }
+    async fn test_getex_bad_options() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "ex", "0"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("err-invalid-expire-time".to_owned()))
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "ex", "ten"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "ex"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "persist", "10"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+        let mut query = Query::from("getex");
+        query.push(vec!["x", "keepttl", "10"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+    async fn test_expire_and_ttl() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(con, query!("ttl", "x"), Element::String("-1".to_owned()));
+        runeq!(con, query!("expire", "x", "100"), Element::UnsignedInt(1));
+        // the time left is rounded down
+        match con.run_query_raw(&query!("ttl", "x")).await.unwrap() {
+            Element::UnsignedInt(ttl) => assert!(ttl == 99 || ttl == 100),
+            ret => panic!("expected an integer, got {ret:?}"),
+        }
+        // missing keys
+        runeq!(con, query!("expire", "y", "100"), Element::UnsignedInt(0));
+        runeq!(con, query!("ttl", "y"), Element::String("-2".to_owned()));
+    }
+    async fn test_pttl() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(con, query!("pttl", "x"), Element::String("-1".to_owned()));
+        runeq!(con, query!("expire", "x", "100"), Element::UnsignedInt(1));
+        match con.run_query_raw(&query!("pttl", "x")).await.unwrap() {
+            Element::UnsignedInt(pttl) => assert!(pttl > 90_000 && pttl <= 100_000),
+            ret => panic!("expected an integer, got {ret:?}"),
+        }
+        runeq!(con, query!("pttl", "y"), Element::String("-2".to_owned()));
+        runeq!(
+            con,
+            query!("pttl", "x", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_expireat() {
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let timestamp = (now + 100).to_string();
+        runeq!(
+            con,
+            query!("expireat", "x", &timestamp),
+            Element::UnsignedInt(1)
+        );
+        runeq!(
+            con,
+            query!("expiretime", "x"),
+            Element::UnsignedInt(now + 100)
+        );
+        let timestamp = ((now + 200) * 1000).to_string();
+        runeq!(
+            con,
+            query!("pexpireat", "x", &timestamp),
+            Element::UnsignedInt(1)
+        );
+        runeq!(
+            con,
+            query!("pexpiretime", "x"),
+            Element::UnsignedInt((now + 200) * 1000)
+        );
+        // timestamps that have passed expire the key right away
+        runeq!(con, query!("expireat", "y", "1"), Element::UnsignedInt(1));
+        runeq!(con, query!("exists", "y"), Element::UnsignedInt(0));
+        runeq!(con, query!("expireat", "z", "1"), Element::UnsignedInt(0));
+        runeq!(
+            con,
+            query!("pexpireat", "x", "0"),
+            Element::RespCode(RespCode::ErrorString("err-invalid-expire-time".to_owned()))
+        );
+    }
+    async fn test_expiretime() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(
+            con,
+            query!("expiretime", "x"),
+            Element::String("-1".to_owned())
+        );
+        runeq!(
+            con,
+            query!("pexpiretime", "x"),
+            Element::String("-1".to_owned())
+        );
+        runeq!(
+            con,
+            query!("pexpire", "x", "100000"),
+            Element::UnsignedInt(1)
+        );
+        let ret = con
+            .run_query_raw(&query!("pexpiretime", "x"))
+            .await
+            .unwrap();
+        let pexpiretime = match ret {
+            Element::UnsignedInt(pexpiretime) => pexpiretime,
+            ret => panic!("expected an integer, got {ret:?}"),
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        assert!(pexpiretime > now + 90_000 && pexpiretime <= now + 100_000);
+        runeq!(
+            con,
+            query!("expiretime", "x"),
+            Element::UnsignedInt(pexpiretime / 1000)
+        );
+        runeq!(
+            con,
+            query!("expiretime", "y"),
+            Element::String("-2".to_owned())
+        );
+        runeq!(
+            con,
+            query!("pexpiretime", "y"),
+            Element::String("-2".to_owned())
+        );
+        runeq!(
+            con,
+            query!("expiretime"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_persist() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(con, query!("persist", "x"), Element::UnsignedInt(0));
+        runeq!(con, query!("expire", "x", "100"), Element::UnsignedInt(1));
+        runeq!(con, query!("persist", "x"), Element::UnsignedInt(1));
+        runeq!(con, query!("ttl", "x"), Element::String("-1".to_owned()));
+        runeq!(con, query!("persist", "y"), Element::UnsignedInt(0));
+    }
+    async fn test_expire_expires() {
+        setkeys!(
+            con,
+            "x":"100",
+            "y":"200"
+        );
+        runeq!(con, query!("expire", "x", "1"), Element::UnsignedInt(1));
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        // expired keys aren't counted or listed, even before they're removed
+        runeq!(con, query!("dbsize"), Element::UnsignedInt(1));
+        runeq!(
+            con,
+            query!("lskeys"),
+            Element::Array(Array::Str(vec![Some("y".to_owned())]))
+        );
+        runeq!(con, query!("exists", "x"), Element::UnsignedInt(0));
+        runeq!(con, query!("ttl", "x"), Element::String("-2".to_owned()));
+    }
+    async fn test_expire_bad_time() {
+        runeq!(
+            con,
+            query!("expire", "x", "0"),
+            Element::RespCode(RespCode::ErrorString("err-invalid-expire-time".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("expire", "x", "ten"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+    }
+    async fn test_pexpire() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(
+            con,
+            query!("pexpire", "x", "100000"),
+            Element::UnsignedInt(1)
+        );
+        match con.run_query_raw(&query!("pttl", "x")).await.unwrap() {
+            Element::UnsignedInt(pttl) => assert!(pttl > 90_000 && pttl <= 100_000),
+            ret => panic!("expected an integer, got {ret:?}"),
+        }
+        runeq!(con, query!("pexpire", "y", "100"), Element::UnsignedInt(0));
+        runeq!(
+            con,
+            query!("pexpire", "x", "0"),
+            Element::RespCode(RespCode::ErrorString("err-invalid-expire-time".to_owned()))
+        );
+        runeq!(
+            con,
+            query!("pexpire", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_pexpire_expires() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(con, query!("pexpire", "x", "100"), Element::UnsignedInt(1));
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        runeq!(con, query!("exists", "x"), Element::UnsignedInt(0));
+    }
+    async fn test_ttl_syntax_error() {
+        runeq!(
+            con,
+            query!("expire", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("persist", "x", "y"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(con, query!("ttl"), Element::RespCode(RespCode::ActionError));
+    }
+    async fn test_scan_large_table() {
+        let mut query = Query::from("uset");
+        for i in 0..10_000 {
+            query.push(format!("key{i}"));
+            query.push(i.to_string());
+        }
+        runeq!(con, query, Element::UnsignedInt(10_000));
+        let mut keys = Vec::new();
+        let mut cursor = "0".to_owned();
+        loop {
+            let ret = con
+                .run_query_raw(&query!("scan", &cursor, "count", "100"))
+                .await
+                .unwrap();
+            let mut batch = match ret {
+                Element::Array(Array::Str(batch)) => batch.into_iter().map(|v| v.unwrap()),
+                ret => panic!("Expected flat string array, got: {:?}", ret),
+            };
+            cursor = batch.next().unwrap();
+            let batch: Vec<String> = batch.collect();
+            assert!(batch.len() <= 100);
+            keys.extend(batch);
+            if cursor == "0" {
+                break;
+            }
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), 10_000);
+    }
+    async fn test_scan_empty_table() {
+        runeq!(
+            con,
+            query!("scan", "0"),
+            Element::Array(Array::Str(vec![Some("0".to_owned())]))
+        );
+    }
+    async fn test_scan_syntax_error() {
+        runeq!(
+            con,
+            query!("scan"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("scan", "0", "count", "0"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("scan", "zero"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+        runeq!(
+            con,
+            query!("scan", "0", "limit", "10"),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+    async fn test_append_okay() {
+        runeq!(con, query!("append", "x", "hello"), Element::UnsignedInt(5));
+        runeq!(
+            con,
+            query!("append", "x", ", world"),
+            Element::UnsignedInt(12)
+        );
+        runeq!(
+            con,
+            query!("get", "x"),
+            Element::String("hello, world".to_owned())
+        );
+    }
+    async fn test_append_syntax_error() {
+        runeq!(
+            con,
+            query!("append", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("append", "x", "y", "z"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    async fn test_cset_new_changed_unchanged() {
+        query.push(vec!["cset", "x", "100"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("unchanged".to_owned()))
+        );
+        let mut query = Query::from("cset");
+        query.push(vec!["x", "200"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("updated".to_owned()))
+        );
+        let mut query = Query::from("get");
+        query.push("x");
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::String("200".to_owned())
+        );
+    }
+    async fn test_cset_binary_unsafe() {
+        use skytable::types::RawString;
+        query.push("cset");
+        query.push("x");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::Okay)
+        );
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("unchanged".to_owned()))
+        );
+        // only differs in the last byte
+        let mut query = Query::from("cset");
+        query.push("x");
+        query.push(RawString::from(b"Hello \xF0\x90\x80Worle".to_vec()));
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ErrorString("updated".to_owned()))
+        );
+    }
+    async fn test_cset_syntax_error() {
+        query.push(vec!["cset", "x"]);
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    /// Test `KEYRANGE`, making sure that the ordered index stays in sync with the table
+    async fn test_keyrange() {
+        let mut rng = rand::thread_rng();
+        let tblname = libstress::utils::rand_alphastring(10, &mut rng);
+        let ordered = format!("{}:{}", __MYKS__, tblname);
+        runeq!(
+            con,
+            query!("create", "table", &ordered, "keymap(str,str)", "ordered"),
+            Element::RespCode(RespCode::Okay)
+        );
+        switch_entity!(con, &ordered);
+        let keys = |keys: &[&str]| {
+            Element::Array(Array::Str(
+                keys.iter().map(|key| Some(key.to_string())).collect(),
+            ))
+        };
+        setkeys!(
+            con,
+            "2022-03":"c",
+            "2022-01":"a",
+            "2021-12":"z",
+            "2022-02":"b"
+        );
+        runeq!(
+            con,
+            query!("keyrange", "2022-01", "2022-12"),
+            keys(&["2022-01", "2022-02", "2022-03"])
+        );
+        // both ends are inclusive
+        runeq!(
+            con,
+            query!("keyrange", "2021-12", "2022-01"),
+            keys(&["2021-12", "2022-01"])
+        );
+        runeq!(
+            con,
+            query!("keyrange", "2022", "2023", "limit", "2"),
+            keys(&["2022-01", "2022-02"])
+        );
+        runeq!(con, query!("keyrange", "2023", "2022"), keys(&[]));
+        runeq!(
+            con,
+            query!("set", "2022-04", "d"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(
+            con,
+            query!("uset", "2022-05", "e", "2022-01", "aa"),
+            Element::UnsignedInt(2)
+        );
+        runeq!(
+            con,
+            query!("keyrange", "2022-03", "2022-12"),
+            keys(&["2022-03", "2022-04", "2022-05"])
+        );
+        runeq!(
+            con,
+            query!("del", "2022-02", "2022-04", "2022-07"),
+            Element::UnsignedInt(2)
+        );
+        runeq!(
+            con,
+            query!("keyrange", "2022", "2023"),
+            keys(&["2022-01", "2022-03", "2022-05"])
+        );
+        runeq!(con, query!("flushdb"), Element::RespCode(RespCode::Okay));
+        runeq!(con, query!("keyrange", "2022", "2023"), keys(&[]));
+        runeq!(
+            con,
+            query!("set", "2022-06", "f"),
+            Element::RespCode(RespCode::Okay)
+        );
+        runeq!(con, query!("keyrange", "2021", "2023"), keys(&["2022-06"]));
+    }
+    /// Test `KEYRANGE` on a table without an ordered index
+    async fn test_keyrange_unordered_table() {
+        setkeys!(
+            con,
+            "x":"100"
+        );
+        runeq!(
+            con,
+            query!("keyrange", "a", "z"),
+            Element::RespCode(RespCode::ErrorString("err-unordered-table".to_owned()))
+        );
+    }
+    async fn test_keyrange_syntax_error() {
+        runeq!(
+            con,
+            query!("keyrange", "a"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("keyrange", "a", "z", "limit"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("keyrange", "a", "z", "limit", "0"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("keyrange", "a", "z", "count", "10"),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
         );
     }
 }
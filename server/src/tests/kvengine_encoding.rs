@@ -26,7 +26,7 @@
 
 #[sky_macros::dbtest_module(table = "keymap(str,str)")]
 mod __private {
-    use skytable::types::RawString;
+    use skytable::types::{Array, RawString};
     use skytable::{Element, RespCode};
     async fn test_bad_encoding_set() {
         query.push("set");
@@ -65,4 +65,62 @@ mod __private {
         );
         runeq!(con, query, Element::RespCode(RespCode::EncodingError));
     }
+    async fn test_bad_encoding_mget() {
+        setkeys! {
+            con,
+            "x": "100",
+            "y": "200"
+        }
+        // the badly encoded key is returned as a null without failing the others
+        push!(
+            query,
+            "mget",
+            "x",
+            RawString::from(b"Hello \xF0\x90\x80World".to_vec()),
+            "y"
+        );
+        runeq!(
+            con,
+            query,
+            Element::Array(Array::Str(vec![
+                Some("100".to_owned()),
+                None,
+                Some("200".to_owned())
+            ]))
+        );
+    }
+    async fn test_bad_encoding_setnx() {
+        query.push("setnx");
+        query.push("x");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, query, Element::RespCode(RespCode::EncodingError));
+    }
+    async fn test_bad_encoding_getset() {
+        query.push("getset");
+        query.push("x");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, query, Element::RespCode(RespCode::EncodingError));
+    }
+    async fn test_bad_encoding_keylen() {
+        query.push("keylen");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, query, Element::RespCode(RespCode::EncodingError));
+    }
+    async fn test_bad_encoding_strlen() {
+        query.push("strlen");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, query, Element::RespCode(RespCode::EncodingError));
+    }
+    async fn test_bad_encoding_cset() {
+        query.push("cset");
+        query.push("x");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, query, Element::RespCode(RespCode::EncodingError));
+    }
+    async fn test_bad_encoding_append() {
+        query.push("append");
+        query.push("x");
+        query.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, query, Element::RespCode(RespCode::EncodingError));
+    }
 }
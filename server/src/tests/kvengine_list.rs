@@ -38,7 +38,20 @@ macro_rules! lset {
 
 #[sky_macros::dbtest_module(table = "keymap(str,list<str>)")]
 mod __private {
-    use skytable::{query, types::Array, Element, RespCode};
+    use skytable::{
+        query,
+        types::{Array, FlatElement},
+        Element, RespCode,
+    };
+
+    fn flat_strs(items: &[&str]) -> Element {
+        Element::Array(Array::Flat(
+            items
+                .iter()
+                .map(|item| FlatElement::String(item.to_string()))
+                .collect(),
+        ))
+    }
 
     // lset tests
     async fn test_lset_empty_okay() {
@@ -228,6 +241,46 @@ mod __private {
         let q = query!("lmod", "mylist", "push");
         runeq!(con, q, Element::RespCode(RespCode::ActionError));
     }
+    // lmod pushfirst
+    /// lmod pushfirst (okay; argument order is preserved)
+    async fn test_lmod_pushfirst_okay() {
+        lset!(con, "mylist", "x", "y");
+        let q = query!("lmod", "mylist", "pushfirst", "a", "b", "c");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c", "x", "y");
+    }
+    /// lmod pushfirst (empty list)
+    async fn test_lmod_pushfirst_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "pushfirst", "a");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a");
+    }
+    /// lmod pushfirst (nil)
+    async fn test_lmod_pushfirst_nil() {
+        let q = query!("lmod", "mylist", "pushfirst", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod pushfirst (syntax error)
+    async fn test_lmod_pushfirst_syntax_error() {
+        let q = query!("lmod", "mylist", "pushfirst");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    /// lmod prepend (same as pushfirst)
+    async fn test_lmod_prepend_okay() {
+        lset!(con, "mylist", "x");
+        let q = query!("lmod", "mylist", "prepend", "a", "b");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "x");
+    }
+    /// lmod prepend (nil)
+    async fn test_lmod_prepend_nil() {
+        let q = query!("lmod", "mylist", "prepend", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
     // lmod pop
     /// lmod pop (okay)
     async fn test_lmod_pop_noindex_okay() {
@@ -261,6 +314,42 @@ mod __private {
         let q = query!("lmod", "mylist", "pop", "whatthe", "whatthe2");
         runeq!(con, q, Element::RespCode(RespCode::ActionError));
     }
+    // lmod popcount
+    /// lmod popcount (okay; popped from the tail)
+    async fn test_lmod_popcount_okay() {
+        lset!(con, "mylist", "a", "b", "c", "d");
+        let q = query!("lmod", "mylist", "popcount", "2");
+        assert_skyhash_arrayeq!(str, con, q, "d", "c");
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b");
+    }
+    /// lmod popcount (more than the number of elements)
+    async fn test_lmod_popcount_drain() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lmod", "mylist", "popcount", "10");
+        assert_skyhash_arrayeq!(str, con, q, "b", "a");
+        let q = query!("lget", "mylist");
+        runeq!(con, q, Element::Array(Array::Str(vec![])));
+    }
+    /// lmod popcount (empty list)
+    async fn test_lmod_popcount_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "popcount", "1");
+        runeq!(con, q, Element::Array(Array::Str(vec![])));
+    }
+    /// lmod popcount (nil)
+    async fn test_lmod_popcount_nil() {
+        let q = query!("lmod", "mylist", "popcount", "1");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod popcount (bad count + syntax error)
+    async fn test_lmod_popcount_bad_args() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "popcount", "-1");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lmod", "mylist", "popcount");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
     // lmod clear
     /// lmod clear (okay)
     async fn test_lmod_clear_okay() {
@@ -278,6 +367,67 @@ mod __private {
         let q = query!("lmod", "mylist", "clear", "unneeded arg");
         runeq!(con, q, Element::RespCode(RespCode::ActionError));
     }
+    // lmod reverse
+    /// lmod reverse (okay)
+    async fn test_lmod_reverse_okay() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "reverse");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "c", "b", "a");
+    }
+    /// lmod reverse (empty list)
+    async fn test_lmod_reverse_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "reverse");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+    }
+    /// lmod reverse (nil)
+    async fn test_lmod_reverse_nil() {
+        let q = query!("lmod", "mylist", "reverse");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod reverse (syntax error)
+    async fn test_lmod_reverse_syntax_error() {
+        let q = query!("lmod", "mylist", "reverse", "unneeded arg");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod rotate
+    /// lmod rotate (left)
+    async fn test_lmod_rotate_left() {
+        lset!(con, "mylist", "a", "b", "c", "d");
+        let q = query!("lmod", "mylist", "rotate", "1");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "b", "c", "d", "a");
+    }
+    /// lmod rotate (right, with a count larger than the list)
+    async fn test_lmod_rotate_right_wrapping() {
+        lset!(con, "mylist", "a", "b", "c", "d");
+        let q = query!("lmod", "mylist", "rotate", "-5");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "d", "a", "b", "c");
+    }
+    /// lmod rotate (empty list)
+    async fn test_lmod_rotate_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "rotate", "3");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+    }
+    /// lmod rotate (nil)
+    async fn test_lmod_rotate_nil() {
+        let q = query!("lmod", "mylist", "rotate", "1");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod rotate (bad count)
+    async fn test_lmod_rotate_bad_count() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lmod", "mylist", "rotate", "one");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lmod", "mylist", "rotate");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
     // lmod remove
     /// lmod remove (okay)
     async fn test_lmod_remove_okay() {
@@ -347,6 +497,191 @@ mod __private {
             Element::RespCode(RespCode::ErrorString("bad-list-index".to_owned()))
         )
     }
+    // lmod replace
+    /// lmod replace (okay)
+    async fn test_lmod_replace_okay() {
+        lset!(con, "mylist", "a", "x", "c");
+        let q = query!("lmod", "mylist", "replace", "1", "b");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c");
+    }
+    /// lmod replace (nil)
+    async fn test_lmod_replace_nil() {
+        let q = query!("lmod", "mylist", "replace", "0", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod replace (present; non-existent index)
+    async fn test_lmod_replace_non_existent_index() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lmod", "mylist", "replace", "2", "c");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("bad-list-index".to_owned()))
+        );
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b");
+    }
+    /// lmod replace (bad encoding)
+    async fn test_lmod_replace_bad_encoding() {
+        use skytable::{types::RawString, Query};
+        lset!(con, "mylist", "a");
+        let mut q = Query::from("lmod");
+        q.push("mylist");
+        q.push("replace");
+        q.push("0");
+        q.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, q, Element::RespCode(RespCode::EncodingError));
+    }
+    /// lmod replace (syntax error)
+    async fn test_lmod_replace_syntax_error() {
+        let q = query!("lmod", "mylist", "replace", "1");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lmod", "mylist", "replace", "1badindex", "b");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+    }
+    // lmod swap
+    /// lmod swap (okay)
+    async fn test_lmod_swap_okay() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "swap", "0", "2");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "c", "b", "a");
+    }
+    /// lmod swap (same index; no-op)
+    async fn test_lmod_swap_same_index() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lmod", "mylist", "swap", "1", "1");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b");
+    }
+    /// lmod swap (empty list + non-existent index)
+    async fn test_lmod_swap_bad_index() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "swap", "0", "0");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("bad-list-index".to_owned()))
+        );
+        lset!(con, "mylist2", "a", "b");
+        let q = query!("lmod", "mylist2", "swap", "0", "2");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("bad-list-index".to_owned()))
+        );
+    }
+    /// lmod swap (nil)
+    async fn test_lmod_swap_nil() {
+        let q = query!("lmod", "mylist", "swap", "0", "1");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod swap (bad integer)
+    async fn test_lmod_swap_wrongtype() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lmod", "mylist", "swap", "0", "1a");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+    }
+    /// lmod swap (syntax error)
+    async fn test_lmod_swap_syntax_error() {
+        let q = query!("lmod", "mylist", "swap", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod slice
+    /// lmod slice (okay)
+    async fn test_lmod_slice_okay() {
+        lset!(con, "mylist", "a", "b", "c", "d");
+        let q = query!("lmod", "mylist", "slice", "1", "2");
+        assert_skyhash_arrayeq!(str, con, q, "b", "c");
+        // the list is unchanged
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c", "d");
+    }
+    /// lmod slice (out of bounds indices are clamped)
+    async fn test_lmod_slice_clamped() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "slice", "1", "100");
+        assert_skyhash_arrayeq!(str, con, q, "b", "c");
+        let q = query!("lmod", "mylist", "slice", "100", "200");
+        runeq!(con, q, Element::Array(Array::Str(vec![])));
+    }
+    /// lmod slice (start > stop)
+    async fn test_lmod_slice_start_after_stop() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "slice", "2", "1");
+        runeq!(con, q, Element::Array(Array::Str(vec![])));
+    }
+    /// lmod slice (empty list)
+    async fn test_lmod_slice_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "slice", "0", "0");
+        runeq!(con, q, Element::Array(Array::Str(vec![])));
+    }
+    /// lmod slice (nil)
+    async fn test_lmod_slice_nil() {
+        let q = query!("lmod", "mylist", "slice", "0", "1");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod slice (bad integer + syntax error)
+    async fn test_lmod_slice_bad_args() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "slice", "0", "-1");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lmod", "mylist", "slice", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod sort
+    /// lmod sort (okay; ascending is the default)
+    async fn test_lmod_sort_okay() {
+        lset!(con, "mylist", "c", "a", "b");
+        let q = query!("lmod", "mylist", "sort");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c");
+        let q = query!("lmod", "mylist", "sort", "desc");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "c", "b", "a");
+        let q = query!("lmod", "mylist", "sort", "asc");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c");
+    }
+    /// lmod sort (bytes are compared, so "10" < "9")
+    async fn test_lmod_sort_lexicographic() {
+        lset!(con, "mylist", "9", "10", "B", "a");
+        let q = query!("lmod", "mylist", "sort");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "10", "9", "B", "a");
+    }
+    /// lmod sort (empty list)
+    async fn test_lmod_sort_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "sort");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+    }
+    /// lmod sort (nil)
+    async fn test_lmod_sort_nil() {
+        let q = query!("lmod", "mylist", "sort");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod sort (bad direction + syntax error)
+    async fn test_lmod_sort_bad_args() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "sort", "sideways");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+        let q = query!("lmod", "mylist", "sort", "asc", "desc");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
     /// del <list> (existent; non-existent)
     async fn test_list_del() {
         // try an existent key
@@ -418,6 +753,333 @@ mod __private {
         runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
     }
 
+    // lswap
+    /// lswap (okay)
+    async fn test_lswap_okay() {
+        lset!(con, "mylist", "a", "b");
+        lset!(con, "myotherlist", "c");
+        let q = query!("lswap", "mylist", "myotherlist");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "c");
+        let q = query!("lget", "myotherlist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b");
+    }
+    /// lswap (missing lists leave everything untouched)
+    async fn test_lswap_missing() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lswap", "mylist", "nolist");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("second-key-not-found".to_owned()))
+        );
+        let q = query!("lswap", "nolist", "mylist");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("first-key-not-found".to_owned()))
+        );
+        let q = query!("lswap", "nolist", "nootherlist");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("both-keys-not-found".to_owned()))
+        );
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b");
+    }
+    /// lswap (syntax error)
+    async fn test_lswap_syntax_error() {
+        let q = query!("lswap", "mylist");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lswap", "mylist", "myotherlist", "extra");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // llen
+    /// llen (okay)
+    async fn test_llen_okay() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("llen", "mylist");
+        runeq!(con, q, Element::UnsignedInt(3));
+    }
+    /// llen (empty list)
+    async fn test_llen_empty() {
+        lset!(con, "mylist");
+        let q = query!("llen", "mylist");
+        runeq!(con, q, Element::UnsignedInt(0));
+    }
+    /// llen (nil)
+    async fn test_llen_nil() {
+        let q = query!("llen", "mylist");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// llen (syntax error)
+    async fn test_llen_syntax_error() {
+        let q = query!("llen");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("llen", "mylist", "extra");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // mllen
+    fn flat_lens(lens: &[Option<u64>]) -> Element {
+        Element::Array(Array::Flat(
+            lens.iter()
+                .map(|len| match len {
+                    Some(len) => FlatElement::UnsignedInt(*len),
+                    None => FlatElement::RespCode(RespCode::NotFound),
+                })
+                .collect(),
+        ))
+    }
+    /// mllen (mixed)
+    async fn test_mllen_mixed() {
+        lset!(con, "list1", "a", "b", "c");
+        lset!(con, "list2");
+        let q = query!("mllen", "list1", "nosuchlist", "list2", "list1");
+        runeq!(con, q, flat_lens(&[Some(3), None, Some(0), Some(3)]));
+    }
+    /// mllen (pipelined)
+    async fn test_mllen_pipeline() {
+        lset!(con, "list1", "a");
+        lset!(con, "list2", "a", "b");
+        let pipe = skytable::Pipeline::new()
+            .append(query!("mllen", "list1", "list2"))
+            .append(query!("mllen", "list2", "nosuchlist"));
+        let ret = con.run_pipeline(pipe).await.unwrap();
+        assert_eq!(
+            ret,
+            vec![flat_lens(&[Some(1), Some(2)]), flat_lens(&[Some(2), None])]
+        );
+    }
+    /// mllen (syntax error)
+    async fn test_mllen_syntax_error() {
+        let q = query!("mllen");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lrange
+    /// lrange (okay)
+    async fn test_lrange_okay() {
+        lset!(con, "mylist", "a", "b", "c", "d");
+        let q = query!("lrange", "mylist", "1", "2");
+        runeq!(con, q, flat_strs(&["b", "c"]));
+        let q = query!("lrange", "mylist", "0", "-1");
+        runeq!(con, q, flat_strs(&["a", "b", "c", "d"]));
+        let q = query!("lrange", "mylist", "-2", "-1");
+        runeq!(con, q, flat_strs(&["c", "d"]));
+    }
+    /// lrange (out-of-bounds indices are clamped)
+    async fn test_lrange_clamped() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lrange", "mylist", "-100", "100");
+        runeq!(con, q, flat_strs(&["a", "b", "c"]));
+        let q = query!("lrange", "mylist", "2", "100");
+        runeq!(con, q, flat_strs(&["c"]));
+    }
+    /// lrange (empty)
+    async fn test_lrange_empty() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lrange", "mylist", "2", "1");
+        runeq!(con, q, flat_strs(&[]));
+        let q = query!("lrange", "mylist", "3", "5");
+        runeq!(con, q, flat_strs(&[]));
+        lset!(con, "myemptylist");
+        let q = query!("lrange", "myemptylist", "0", "-1");
+        runeq!(con, q, flat_strs(&[]));
+    }
+    /// lrange (nil)
+    async fn test_lrange_nil() {
+        let q = query!("lrange", "mylist", "0", "-1");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lrange (bad index)
+    async fn test_lrange_wrongtype() {
+        lset!(con, "mylist", "a");
+        let q = query!("lrange", "mylist", "first", "-1");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+    }
+    /// lrange (syntax error)
+    async fn test_lrange_syntax_error() {
+        let q = query!("lrange", "mylist", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lrange", "mylist", "0", "1", "2");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lpos
+    /// lpos (okay)
+    async fn test_lpos_okay() {
+        lset!(con, "mylist", "a", "b", "a", "c", "a");
+        let q = query!("lpos", "mylist", "a");
+        runeq!(con, q, Element::UnsignedInt(0));
+        let q = query!("lpos", "mylist", "c");
+        runeq!(con, q, Element::UnsignedInt(3));
+        let q = query!("lpos", "mylist", "d");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lpos (rank)
+    async fn test_lpos_rank() {
+        lset!(con, "mylist", "a", "b", "a", "c", "a");
+        let q = query!("lpos", "mylist", "a", "rank", "2");
+        runeq!(con, q, Element::UnsignedInt(2));
+        let q = query!("lpos", "mylist", "a", "RANK", "-1");
+        runeq!(con, q, Element::UnsignedInt(4));
+        let q = query!("lpos", "mylist", "a", "rank", "4");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lpos (count)
+    async fn test_lpos_count() {
+        lset!(con, "mylist", "a", "b", "a", "c", "a");
+        let q = query!("lpos", "mylist", "a", "count", "0");
+        runeq!(con, q, flat_lens(&[Some(0), Some(2), Some(4)]));
+        let q = query!("lpos", "mylist", "a", "count", "2", "rank", "-1");
+        runeq!(con, q, flat_lens(&[Some(4), Some(2)]));
+        let q = query!("lpos", "mylist", "d", "count", "1");
+        runeq!(con, q, flat_lens(&[]));
+    }
+    /// lpos (maxlen)
+    async fn test_lpos_maxlen() {
+        lset!(con, "mylist", "a", "b", "a", "c", "a");
+        let q = query!("lpos", "mylist", "c", "maxlen", "3");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+        let q = query!("lpos", "mylist", "c", "maxlen", "4");
+        runeq!(con, q, Element::UnsignedInt(3));
+        let q = query!("lpos", "mylist", "a", "maxlen", "2", "count", "0");
+        runeq!(con, q, flat_lens(&[Some(0)]));
+    }
+    /// lpos (nil)
+    async fn test_lpos_nil() {
+        let q = query!("lpos", "mylist", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lpos (bad values)
+    async fn test_lpos_bad_values() {
+        lset!(con, "mylist", "a");
+        let q = query!("lpos", "mylist", "a", "rank", "first");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lpos", "mylist", "a", "count", "-1");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lpos", "mylist", "a", "rank", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lpos", "mylist", "a", "limit", "1");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+    /// lpos (syntax error)
+    async fn test_lpos_syntax_error() {
+        let q = query!("lpos", "mylist");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lpos", "mylist", "a", "rank");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lslist
+    /// lslist (okay)
+    async fn test_lslist_okay() {
+        lset!(con, "list1", "a");
+        lset!(con, "list2");
+        lset!(con, "list3", "b", "c");
+        let q = query!("lslist");
+        let mut keys: Vec<String> = match con.run_query_raw(&q).await.unwrap() {
+            Element::Array(Array::Flat(keys)) => keys
+                .into_iter()
+                .map(|key| match key {
+                    FlatElement::String(key) => key,
+                    key => panic!("Expected string, got: {:?}", key),
+                })
+                .collect(),
+            ret => panic!("Expected flat array, got: {:?}", ret),
+        };
+        keys.sort();
+        assert_eq!(keys, vec!["list1", "list2", "list3"]);
+    }
+    /// lslist (empty table)
+    async fn test_lslist_empty() {
+        let q = query!("lslist");
+        runeq!(con, q, flat_strs(&[]));
+    }
+    /// lslist (paginated)
+    async fn test_lslist_paginated() {
+        let expected: Vec<String> = (0..50).map(|i| format!("list{}", i)).collect();
+        for list in expected.iter() {
+            lset!(con, list.as_str());
+        }
+        let mut keys = vec![];
+        let mut cursor = 0;
+        loop {
+            let q = query!("lslist", cursor.to_string(), "7");
+            let ret = con.run_query_raw(&q).await.unwrap();
+            let mut page = match ret {
+                Element::Array(Array::Flat(page)) => page.into_iter(),
+                ret => panic!("Expected flat array, got: {:?}", ret),
+            };
+            cursor = match page.next() {
+                Some(FlatElement::UnsignedInt(next)) => next,
+                next => panic!("Expected cursor, got: {:?}", next),
+            };
+            let page: Vec<String> = page
+                .map(|key| match key {
+                    FlatElement::String(key) => key,
+                    key => panic!("Expected string, got: {:?}", key),
+                })
+                .collect();
+            assert!(page.len() <= 7);
+            keys.extend(page);
+            if cursor == 0 {
+                break;
+            }
+        }
+        keys.sort();
+        let mut expected = expected;
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+    /// lslist (bad cursor or count)
+    async fn test_lslist_wrongtype() {
+        let q = query!("lslist", "start", "10");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lslist", "0", "-1");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+    }
+    /// lslist (syntax error)
+    async fn test_lslist_syntax_error() {
+        let q = query!("lslist", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lslist", "0", "10", "extra");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lslist", "0", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+
+    // lskeys
+    /// lskeys (limit larger than the number of lists)
+    async fn test_lskeys_lists() {
+        lset!(con, "list1", "a");
+        lset!(con, "list2");
+        lset!(con, "list3", "b", "c");
+        let ret = con.run_query_raw(&query!("lskeys", "100")).await.unwrap();
+        if let Element::Array(Array::Str(keys)) = ret {
+            let mut keys: Vec<String> = keys.into_iter().map(|key| key.unwrap()).collect();
+            keys.sort();
+            assert_eq!(keys, vec!["list1", "list2", "list3"]);
+        } else {
+            panic!("Expected string array, got: {:?}", ret);
+        }
+    }
+    /// lskeys (binary list names)
+    async fn test_lskeys_binary_lists() {
+        let bintable = __MYKS__.clone() + ":" + &__MYTABLE__ + "bin";
+        create_table_and_switch!(con, &bintable, "keymap(binstr,list<str>)");
+        lset!(con, "list1", "a");
+        let ret = con.run_query_raw(&query!("lskeys")).await.unwrap();
+        if let Element::Array(Array::Bin(keys)) = ret {
+            assert_eq!(keys, vec![Some(b"list1".to_vec())]);
+        } else {
+            panic!("Expected binary array, got: {:?}", ret);
+        }
+    }
+
     // sanity tests
     async fn test_get_model_error() {
         query.push("GET");
@@ -428,6 +1090,13 @@ mod __private {
             Element::RespCode(RespCode::ErrorString("wrong-model".to_owned()))
         );
     }
+    async fn test_strlen_model_error() {
+        runeq!(
+            con,
+            query!("strlen", "mylist"),
+            Element::RespCode(RespCode::ErrorString("wrong-model".to_owned()))
+        );
+    }
     async fn test_set_model_error() {
         query.push("SET");
         query.push("mylist");
@@ -448,4 +1117,201 @@ mod __private {
             Element::RespCode(RespCode::ErrorString("wrong-model".to_owned()))
         );
     }
+    // lmod trim
+    /// lmod trim (okay)
+    async fn test_lmod_trim_okay() {
+        lset!(con, "mylist", "a", "b", "c", "d", "e");
+        let q = query!("lmod", "mylist", "trim", "1", "3");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "b", "c", "d");
+    }
+    /// lmod trim (stop is clamped)
+    async fn test_lmod_trim_clamped() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "trim", "1", "100");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "b", "c");
+    }
+    /// lmod trim (start is out of bounds, so the list is emptied)
+    async fn test_lmod_trim_out_of_bounds() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "trim", "3", "5");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        runeq!(con, q, Element::Array(Array::Str(vec![])));
+    }
+    /// lmod trim (nil)
+    async fn test_lmod_trim_nil() {
+        let q = query!("lmod", "mylist", "trim", "0", "1");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod trim (bad index + syntax error)
+    async fn test_lmod_trim_bad_args() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "trim", "0", "x");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lmod", "mylist", "trim", "0");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod contains
+    /// lmod contains (found)
+    async fn test_lmod_contains_found() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "contains", "b");
+        runeq!(con, q, Element::UnsignedInt(1));
+    }
+    /// lmod contains (not found)
+    async fn test_lmod_contains_not_found() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("lmod", "mylist", "contains", "d");
+        runeq!(con, q, Element::UnsignedInt(0));
+        // the list is unchanged
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c");
+    }
+    /// lmod contains (empty list)
+    async fn test_lmod_contains_empty_list() {
+        lset!(con, "mylist");
+        let q = query!("lmod", "mylist", "contains", "a");
+        runeq!(con, q, Element::UnsignedInt(0));
+    }
+    /// lmod contains (nil)
+    async fn test_lmod_contains_nil() {
+        let q = query!("lmod", "mylist", "contains", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod contains (syntax error)
+    async fn test_lmod_contains_syntax_error() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "contains");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lmod", "mylist", "contains", "a", "b");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod count
+    /// lmod count (okay)
+    async fn test_lmod_count_okay() {
+        lset!(con, "mylist", "a", "b", "a", "c", "a");
+        let q = query!("lmod", "mylist", "count", "a");
+        runeq!(con, q, Element::UnsignedInt(3));
+        let q = query!("lmod", "mylist", "count", "d");
+        runeq!(con, q, Element::UnsignedInt(0));
+    }
+    /// lmod count (nil)
+    async fn test_lmod_count_nil() {
+        let q = query!("lmod", "mylist", "count", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod count (bad encoding)
+    async fn test_lmod_count_bad_encoding() {
+        use skytable::{types::RawString, Query};
+        lset!(con, "mylist", "a");
+        let mut q = Query::from("lmod");
+        q.push("mylist");
+        q.push("count");
+        q.push(RawString::from(b"Hello \xF0\x90\x80World".to_vec()));
+        runeq!(con, q, Element::RespCode(RespCode::EncodingError));
+    }
+    /// lmod count (syntax error)
+    async fn test_lmod_count_syntax_error() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "count");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+        let q = query!("lmod", "mylist", "count", "a", "b");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod find
+    /// lmod find (okay)
+    async fn test_lmod_find_okay() {
+        lset!(con, "mylist", "a", "b", "a", "c");
+        let q = query!("lmod", "mylist", "find", "a");
+        runeq!(con, q, Element::UnsignedInt(0));
+        let q = query!("lmod", "mylist", "find", "c");
+        runeq!(con, q, Element::UnsignedInt(3));
+    }
+    /// lmod find (with a start index)
+    async fn test_lmod_find_from_index() {
+        lset!(con, "mylist", "a", "b", "a", "c");
+        let q = query!("lmod", "mylist", "find", "a", "1");
+        runeq!(con, q, Element::UnsignedInt(2));
+        let q = query!("lmod", "mylist", "find", "a", "2");
+        runeq!(con, q, Element::UnsignedInt(2));
+        let q = query!("lmod", "mylist", "find", "a", "3");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+        let q = query!("lmod", "mylist", "find", "a", "100");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod find (no match)
+    async fn test_lmod_find_no_match() {
+        lset!(con, "mylist", "a", "b");
+        let q = query!("lmod", "mylist", "find", "c");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod find (nil)
+    async fn test_lmod_find_nil() {
+        let q = query!("lmod", "mylist", "find", "a");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod find (bad index + syntax error)
+    async fn test_lmod_find_bad_args() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "find", "a", "x");
+        runeq!(con, q, Element::RespCode(RespCode::Wrongtype));
+        let q = query!("lmod", "mylist", "find");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // lmod dedup
+    /// lmod dedup (keeps the first occurrences)
+    async fn test_lmod_dedup_okay() {
+        lset!(con, "mylist", "a", "b", "a", "c", "b", "a");
+        let q = query!("lmod", "mylist", "dedup");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "c");
+    }
+    /// lmod dedup consecutive (like uniq)
+    async fn test_lmod_dedup_consecutive() {
+        lset!(con, "mylist", "a", "a", "b", "b", "a", "c", "c");
+        let q = query!("lmod", "mylist", "dedup", "consecutive");
+        runeq!(con, q, Element::RespCode(RespCode::Okay));
+        let q = query!("lget", "mylist");
+        assert_skyhash_arrayeq!(str, con, q, "a", "b", "a", "c");
+    }
+    /// lmod dedup (nil)
+    async fn test_lmod_dedup_nil() {
+        let q = query!("lmod", "mylist", "dedup");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
+    /// lmod dedup (bad mode + syntax error)
+    async fn test_lmod_dedup_bad_args() {
+        lset!(con, "mylist", "a");
+        let q = query!("lmod", "mylist", "dedup", "sometimes");
+        runeq!(
+            con,
+            q,
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+        let q = query!("lmod", "mylist", "dedup", "consecutive", "x");
+        runeq!(con, q, Element::RespCode(RespCode::ActionError));
+    }
+    // object encoding
+    /// object encoding (small list)
+    async fn test_object_encoding_listpack() {
+        lset!(con, "mylist", "a", "b", "c");
+        let q = query!("object", "encoding", "mylist");
+        runeq!(con, q, Element::String("listpack".to_owned()));
+    }
+    /// object encoding (list with a large element)
+    async fn test_object_encoding_quicklist() {
+        lset!(con, "mylist", "a", "x".repeat(65));
+        let q = query!("object", "encoding", "mylist");
+        runeq!(con, q, Element::String("quicklist".to_owned()));
+    }
+    /// object encoding (nil)
+    async fn test_object_encoding_list_nil() {
+        let q = query!("object", "encoding", "mylist");
+        runeq!(con, q, Element::RespCode(RespCode::NotFound));
+    }
 }
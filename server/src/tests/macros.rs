@@ -28,29 +28,25 @@ macro_rules! setkeys {
     ($con:ident, $($key:literal:$value:literal),*) => {
         let mut q = skytable::Query::new();
         q.push("MSET");
-        let mut count = 0;
         $(
             q.push($key);
             q.push($value);
-            count += 1;
         )*
         assert_eq!(
             $con.run_query_raw(&q).await.unwrap(),
-            Element::UnsignedInt(count)
+            Element::RespCode(skytable::RespCode::Okay)
         );
     };
     ($con:ident, $($key:expr => $value:expr),*) => {
         let mut q = ::skytable::Query::new();
         q.push("MSET");
-        let mut count = 0;
         $(
             q.push($key);
             q.push($value);
-            count += 1;
         )*
         assert_eq!(
             $con.run_query_raw(&q).await.unwrap(),
-            ::skytable::Element::UnsignedInt(count)
+            ::skytable::Element::RespCode(::skytable::RespCode::Okay)
         );
     };
 }
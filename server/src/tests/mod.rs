@@ -51,11 +51,180 @@ mod tls {
     }
 }
 
+mod idle_timeout {
+    use skytable::{query, Element};
+    use std::time::Duration;
+    // server3 disconnects clients that stay idle for more than 2 seconds
+    #[sky_macros::dbtest_func(port = 2007, norun = true)]
+    async fn idle_client_is_disconnected() {
+        runeq!(con, query!("heya"), Element::String("HEY!".to_owned()));
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(con.run_query_raw(&query!("heya")).await.is_err());
+    }
+}
+
+mod membudget {
+    use skytable::{
+        query,
+        types::{Array, FlatElement},
+        Element, Query, RespCode,
+    };
+    use std::time::Duration;
+    // server4 has a memory budget of 1 MiB
+    const BUDGET: usize = 1024 * 1024;
+    const VALUE_SIZE: usize = 64 * 1024;
+    fn is_memory_limit(ret: &Element) -> bool {
+        matches!(
+            ret,
+            Element::RespCode(RespCode::ErrorString(e)) if e.starts_with("err-memory-limit:")
+        )
+    }
+    #[sky_macros::dbtest_func(port = 2009)]
+    async fn writes_are_refused_till_memory_is_freed() {
+        let value = "x".repeat(VALUE_SIZE);
+        // the estimate is refreshed in the background, so keep writing till it catches up
+        let mut written = 0;
+        loop {
+            let ret = con
+                .run_query_raw(&query!("set", format!("key{written}"), value.clone()))
+                .await
+                .unwrap();
+            if is_memory_limit(&ret) {
+                break;
+            }
+            assert_eq!(ret, Element::RespCode(RespCode::Okay));
+            written += 1;
+            assert!(
+                written * VALUE_SIZE < BUDGET * 2,
+                "writes were never refused"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        // the error has the estimate and the budget
+        let ret = con
+            .run_query_raw(&query!("set", "onemore", value.clone()))
+            .await
+            .unwrap();
+        match ret {
+            Element::RespCode(RespCode::ErrorString(e)) => {
+                let parts: Vec<&str> = e.split(':').collect();
+                assert_eq!(parts[0], "err-memory-limit");
+                assert!(parts[1].parse::<usize>().unwrap() > BUDGET);
+                assert_eq!(parts[2].parse::<usize>().unwrap(), BUDGET);
+            }
+            ret => panic!("Expected err-memory-limit, got: {ret:?}"),
+        }
+        // the metric has the estimate, the budget and the state as well
+        match con
+            .run_query_raw(&query!("sys", "metric", "memory-budget"))
+            .await
+            .unwrap()
+        {
+            Element::Array(Array::Flat(usage)) => match usage.as_slice() {
+                [FlatElement::UnsignedInt(estimate), FlatElement::UnsignedInt(budget), FlatElement::String(state)] =>
+                {
+                    assert!(*estimate as usize > BUDGET);
+                    assert_eq!(*budget as usize, BUDGET);
+                    assert_eq!(state, "over-budget");
+                }
+                usage => panic!("Expected two integers and a string, got: {usage:?}"),
+            },
+            ret => panic!("Expected flat array, got: {ret:?}"),
+        }
+        // strong writes are refused as well
+        let ret = con
+            .run_query_raw(&query!("sset", "onemore", value.clone()))
+            .await
+            .unwrap();
+        assert!(is_memory_limit(&ret), "Unexpected response: {ret:?}");
+        let ret = con
+            .run_query_raw(&query!("supdate", "key0", value.clone()))
+            .await
+            .unwrap();
+        assert!(is_memory_limit(&ret), "Unexpected response: {ret:?}");
+        // deletes are still accepted
+        let mut query = Query::from("del");
+        (1..written).for_each(|i| query.push(format!("key{i}")));
+        assert_eq!(
+            con.run_query_raw(&query).await.unwrap(),
+            Element::UnsignedInt(written as u64 - 1)
+        );
+        // and writes are accepted again once the estimate catches up
+        let mut attempts = 0;
+        loop {
+            let ret = con
+                .run_query_raw(&query!("set", "onemore", value.clone()))
+                .await
+                .unwrap();
+            if ret == Element::RespCode(RespCode::Okay) {
+                break;
+            }
+            assert!(is_memory_limit(&ret), "Unexpected response: {ret:?}");
+            attempts += 1;
+            assert!(attempts < 100, "writes were never accepted again");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        // a populate that doesn't fit stops at the budget instead of overshooting it
+        let ret = con
+            .run_query_raw(&query!(
+                "debug",
+                "populate",
+                "100",
+                "pop",
+                VALUE_SIZE.to_string()
+            ))
+            .await
+            .unwrap();
+        assert!(is_memory_limit(&ret), "Unexpected response: {ret:?}");
+        match con.run_query_raw(&query!("dbsize")).await.unwrap() {
+            Element::UnsignedInt(size) => assert!((size as usize) < BUDGET / VALUE_SIZE + 2),
+            ret => panic!("Expected integer, got: {ret:?}"),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unixsock {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixStream,
+    };
+    // server3 also listens on a unix socket in its working directory
+    const SOCKET_PATH: &str = concat!(env!("ROOT_DIR"), "server3/skyd.sock");
+    async fn run_raw(con: &mut UnixStream, query: &[u8]) -> Vec<u8> {
+        con.write_all(query).await.unwrap();
+        let mut buf = vec![0; 64];
+        let read = con.read(&mut buf).await.unwrap();
+        buf.truncate(read);
+        buf
+    }
+    #[tokio::test]
+    async fn set_get_roundtrip() {
+        let mut con = UnixStream::connect(SOCKET_PATH).await.unwrap();
+        assert_eq!(
+            run_raw(&mut con, b"*3\n3\nSET12\nunixsock_key3\n100").await,
+            b"*!0\n"
+        );
+        assert_eq!(
+            run_raw(&mut con, b"*2\n3\nGET12\nunixsock_key").await,
+            b"*?3\n100"
+        );
+        assert_eq!(
+            run_raw(&mut con, b"*2\n3\nDEL12\nunixsock_key").await,
+            b"*:1\n"
+        );
+    }
+}
+
 mod sys {
     use crate::protocol::{PROTOCOL_VERSION, PROTOCOL_VERSIONSTRING};
     use libsky::VERSION;
     use sky_macros::dbtest_func as dbtest;
-    use skytable::{query, Element, RespCode};
+    use skytable::{
+        query,
+        types::{Array, FlatElement},
+        Element, Pipeline, RespCode,
+    };
     #[dbtest]
     async fn sys_info_aerr() {
         runeq!(
@@ -99,6 +268,42 @@ mod sys {
         )
     }
     #[dbtest]
+    async fn sys_info_uptime() {
+        let query = query!("sys", "info", "uptime");
+        let first = match con.run_query_raw(&query).await.unwrap() {
+            Element::UnsignedInt(uptime) => uptime,
+            ret => panic!("expected an integer, got {ret:?}"),
+        };
+        let second = match con.run_query_raw(&query).await.unwrap() {
+            Element::UnsignedInt(uptime) => uptime,
+            ret => panic!("expected an integer, got {ret:?}"),
+        };
+        assert!(second >= first);
+    }
+    #[dbtest]
+    async fn sys_info_startup_time() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        match con
+            .run_query_raw(&query!("sys", "info", "startup_time"))
+            .await
+            .unwrap()
+        {
+            Element::UnsignedInt(startup_time) => assert!(startup_time <= now),
+            ret => panic!("expected an integer, got {ret:?}"),
+        }
+    }
+    #[dbtest]
+    async fn sys_info_ttl_persistence() {
+        runeq!(
+            con,
+            query!("sys", "info", "ttl_persistence"),
+            Element::String("persistent".to_owned())
+        )
+    }
+    #[dbtest]
     async fn sys_metric_aerr() {
         runeq!(
             con,
@@ -127,4 +332,400 @@ mod sys {
             Element::UnsignedInt
         )
     }
+    #[dbtest]
+    async fn sys_metric_memory_budget() {
+        let ret = con
+            .run_query_raw(&query!("sys", "metric", "memory-budget"))
+            .await
+            .unwrap();
+        match ret {
+            Element::Array(Array::Flat(usage)) => {
+                assert_eq!(usage.len(), 3);
+                assert!(matches!(usage[0], FlatElement::UnsignedInt(_)));
+                // the test servers don't have a budget
+                assert_eq!(usage[1], FlatElement::UnsignedInt(0));
+                assert_eq!(usage[2], FlatElement::String("within-budget".to_owned()));
+            }
+            ret => panic!("Expected flat array, got: {:?}", ret),
+        }
+    }
+    #[dbtest]
+    async fn sys_metric_connections() {
+        // other tests may be running, so we can only check for a lower bound
+        let mut other = skytable::AsyncConnection::new("127.0.0.1", 2003)
+            .await
+            .unwrap();
+        let query = query!("sys", "metric", "connections");
+        for con in [&mut con, &mut other] {
+            match con.run_query_raw(&query).await.unwrap() {
+                Element::UnsignedInt(connections) => assert!(connections >= 2),
+                ret => panic!("expected an integer, got {ret:?}"),
+            }
+        }
+    }
+    #[dbtest]
+    async fn sys_metric_queries() {
+        let query = query!("sys", "metric", "queries");
+        let first = match con.run_query_raw(&query).await.unwrap() {
+            Element::UnsignedInt(queries) => queries,
+            ret => panic!("expected an integer, got {ret:?}"),
+        };
+        // a pipeline counts as one query per stage
+        let pipe = Pipeline::new()
+            .append(query!("heya"))
+            .append(query!("heya"))
+            .append(query!("heya"));
+        con.run_pipeline(pipe).await.unwrap();
+        let second = match con.run_query_raw(&query).await.unwrap() {
+            Element::UnsignedInt(queries) => queries,
+            ret => panic!("expected an integer, got {ret:?}"),
+        };
+        // the three heyas and the query itself
+        assert!(second >= first + 4);
+    }
+    #[dbtest]
+    async fn sys_metric_latency_action() {
+        runeq!(con, query!("heya"), Element::String("HEY!".to_owned()));
+        let ret = con
+            .run_query_raw(&query!("sys", "metric", "latency", "heya"))
+            .await
+            .unwrap();
+        if let Element::Array(Array::Flat(counts)) = ret {
+            assert_eq!(counts.len(), 5);
+            let total: u64 = counts
+                .iter()
+                .map(|count| match count {
+                    FlatElement::UnsignedInt(count) => *count,
+                    count => panic!("Expected an integer, got: {:?}", count),
+                })
+                .sum();
+            assert!(total >= 1);
+        } else {
+            panic!("Expected flat array, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn sys_metric_latency_all() {
+        runeq!(con, query!("heya"), Element::String("HEY!".to_owned()));
+        let ret = con
+            .run_query_raw(&query!("sys", "metric", "latency"))
+            .await
+            .unwrap();
+        if let Element::Array(Array::Recursive(histograms)) = ret {
+            // every action is paired with its five counts
+            assert!(histograms.iter().any(|histogram| match histogram {
+                Element::Array(Array::Recursive(pair)) => match pair.as_slice() {
+                    [Element::String(action), Element::Array(Array::Flat(counts))] => {
+                        assert_eq!(counts.len(), 5);
+                        action == "HEYA"
+                    }
+                    pair => panic!("Expected an action and its counts, got: {:?}", pair),
+                },
+                histogram => panic!("Expected an array, got: {:?}", histogram),
+            }));
+        } else {
+            panic!("Expected array, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn sys_metric_latency_nil() {
+        runeq!(
+            con,
+            query!("sys", "metric", "latency", "notanaction"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    #[dbtest]
+    async fn sys_migratestatus() {
+        let ret = con
+            .run_query_raw(&query!("sys", "migratestatus"))
+            .await
+            .unwrap();
+        let current = format!(
+            " v{}",
+            crate::storage::v1::interface::TABLE_FORMAT_VERSION_CURRENT
+        );
+        if let Element::Array(Array::NonNullStr(containers)) = ret {
+            assert!(containers.contains(&format!("default:default{current}")));
+            assert!(containers
+                .iter()
+                .all(|container| container.ends_with(&current)));
+        } else {
+            panic!("Expected non-null string array, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn sys_migratestatus_aerr() {
+        runeq!(
+            con,
+            query!("sys", "migratestatus", "but why this extra argument?"),
+            Element::RespCode(RespCode::ActionError)
+        )
+    }
+}
+
+mod latency {
+    use sky_macros::dbtest_func as dbtest;
+    use skytable::{query, Element, RespCode};
+    #[dbtest]
+    async fn latency_graph() {
+        for _ in 0..3 {
+            runeq!(con, query!("heya"), Element::String("HEY!".to_owned()));
+        }
+        let ret = con
+            .run_query_raw(&query!("latency", "graph", "heya"))
+            .await
+            .unwrap();
+        if let Element::String(graph) = ret {
+            assert!(!graph.is_empty() && graph.chars().count() <= 40);
+            assert!(graph.chars().all(|c| ('▁'..='█').contains(&c)));
+        } else {
+            panic!("Expected string, got: {:?}", ret);
+        }
+        // custom width
+        let ret = con
+            .run_query_raw(&query!("latency", "graph", "heya", "2"))
+            .await
+            .unwrap();
+        if let Element::String(graph) = ret {
+            assert_eq!(graph.chars().count(), 2);
+        } else {
+            panic!("Expected string, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn latency_graph_unknown_action() {
+        runeq!(
+            con,
+            query!("latency", "graph", "nosuchaction"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    #[dbtest]
+    async fn latency_graph_bad_width() {
+        runeq!(
+            con,
+            query!("latency", "graph", "heya", "0"),
+            Element::RespCode(RespCode::Wrongtype)
+        );
+    }
+    #[dbtest]
+    async fn latency_aerr() {
+        runeq!(
+            con,
+            query!("latency", "graph"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+}
+
+mod memory {
+    use sky_macros::dbtest_func as dbtest;
+    use skytable::{query, Element, RespCode};
+    #[dbtest]
+    async fn memory_malloc_stats() {
+        let ret = con
+            .run_query_raw(&query!("memory", "malloc-stats"))
+            .await
+            .unwrap();
+        if let Element::String(stats) = ret {
+            if cfg!(target_env = "msvc") {
+                assert_eq!(stats, "Stats not supported for the current allocator");
+            } else {
+                assert!(stats.contains("jemalloc statistics"));
+            }
+        } else {
+            panic!("Expected string, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn memory_aerr() {
+        runeq!(
+            con,
+            query!("memory"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("memory", "nosuchsubaction"),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+}
+
+mod command {
+    use sky_macros::dbtest_func as dbtest;
+    use skytable::{query, types::Array, Element, RespCode};
+    #[dbtest]
+    async fn command_docs_get() {
+        let string = |s: &str| Element::String(s.to_owned());
+        runeq!(
+            con,
+            query!("command", "docs", "get"),
+            Element::Array(Array::Recursive(vec![Element::Array(Array::Recursive(
+                vec![
+                    string("GET"),
+                    string("Get the value of a key"),
+                    string("0.1.0"),
+                    string("string"),
+                    string("O(1)"),
+                    Element::Array(Array::Recursive(vec![Element::Array(Array::Recursive(
+                        vec![string("key"), string("key"), Element::UnsignedInt(0)]
+                    ))]))
+                ]
+            ))]))
+        );
+    }
+    #[dbtest]
+    async fn command_docs_skips_unknown() {
+        let ret = con
+            .run_query_raw(&query!("command", "docs", "set", "nosuchaction", "lmod"))
+            .await
+            .unwrap();
+        if let Element::Array(Array::Recursive(docs)) = ret {
+            assert_eq!(docs.len(), 2);
+        } else {
+            panic!("Expected recursive array, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn command_docs_all() {
+        let ret = con.run_query_raw(&query!("command", "docs")).await.unwrap();
+        if let Element::Array(Array::Recursive(docs)) = ret {
+            assert!(docs.len() > 30);
+        } else {
+            panic!("Expected recursive array, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn command_aerr() {
+        runeq!(
+            con,
+            query!("command"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    #[dbtest]
+    async fn command_getkeys() {
+        assert_skyhash_arrayeq!(
+            bin,
+            con,
+            query!("command", "getkeys", "get", "x"),
+            b"x".to_vec()
+        );
+        assert_skyhash_arrayeq!(
+            bin,
+            con,
+            query!("command", "getkeys", "mset", "x", "1", "y", "2"),
+            b"x".to_vec(),
+            b"y".to_vec()
+        );
+        assert_skyhash_arrayeq!(
+            bin,
+            con,
+            query!("command", "getkeys", "object", "encoding", "x"),
+            b"x".to_vec()
+        );
+        runeq!(
+            con,
+            query!("command", "getkeys", "dbsize"),
+            Element::Array(Array::Bin(vec![]))
+        );
+    }
+    #[dbtest]
+    async fn command_getkeys_errors() {
+        runeq!(
+            con,
+            query!("command", "getkeys", "swapkeys", "x"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("command", "getkeys"),
+            Element::RespCode(RespCode::ActionError)
+        );
+        runeq!(
+            con,
+            query!("command", "getkeys", "nosuchaction", "x"),
+            Element::RespCode(RespCode::ErrorString("Unknown action".to_owned()))
+        );
+    }
+    #[dbtest]
+    async fn command_list() {
+        let ret = con.run_query_raw(&query!("command", "list")).await.unwrap();
+        if let Element::Array(Array::NonNullStr(names)) = ret {
+            assert!(names.contains(&"LMOD".to_owned()));
+            assert!(names.contains(&"COMMAND".to_owned()));
+        } else {
+            panic!("Expected non-null string array, got: {:?}", ret);
+        }
+    }
+    #[dbtest]
+    async fn command_list_filterby() {
+        runeq!(
+            con,
+            query!("command", "list", "filterby", "pattern", "l*"),
+            Element::Array(Array::NonNullStr(vec![
+                "LATENCY".to_owned(),
+                "LGET".to_owned(),
+                "LLEN".to_owned(),
+                "LMOD".to_owned(),
+                "LPOS".to_owned(),
+                "LRANGE".to_owned(),
+                "LSET".to_owned(),
+                "LSKEYS".to_owned(),
+                "LSLIST".to_owned(),
+                "LSWAP".to_owned(),
+            ]))
+        );
+        runeq!(
+            con,
+            query!("command", "list", "filterby", "aclcat", "list"),
+            Element::Array(Array::NonNullStr(vec![
+                "LGET".to_owned(),
+                "LLEN".to_owned(),
+                "LMOD".to_owned(),
+                "LPOS".to_owned(),
+                "LRANGE".to_owned(),
+                "LSET".to_owned(),
+                "LSLIST".to_owned(),
+                "LSWAP".to_owned(),
+                "MLLEN".to_owned(),
+            ]))
+        );
+        runeq!(
+            con,
+            query!("command", "list", "filterby", "module", "json"),
+            Element::Array(Array::NonNullStr(vec![]))
+        );
+        runeq!(
+            con,
+            query!("command", "list", "filterby", "pattern"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
+    #[dbtest]
+    async fn command_count() {
+        let ret = con.run_query_raw(&query!("command", "list")).await.unwrap();
+        let count = match ret {
+            Element::Array(Array::NonNullStr(names)) => names.len() as u64,
+            ret => panic!("Expected non-null string array, got: {:?}", ret),
+        };
+        runeq!(con, query!("command", "count"), Element::UnsignedInt(count));
+        runeq!(
+            con,
+            query!("command", "count", "filterby", "aclcat", "list"),
+            Element::UnsignedInt(9)
+        );
+        runeq!(
+            con,
+            query!("command", "count", "filterby", "aclcat", "nosuchcategory"),
+            Element::UnsignedInt(0)
+        );
+        runeq!(
+            con,
+            query!("command", "count", "filterby", "aclcat"),
+            Element::RespCode(RespCode::ActionError)
+        );
+    }
 }
@@ -82,4 +82,69 @@ mod tests {
             ]
         )
     }
+    async fn test_pipeline_abort_on_err() {
+        let pipe = Pipeline::new()
+            .append(query!("abort_on_err"))
+            .append(query!("set", "x", "100"))
+            .append(query!("dbsize", "default:nosuchtable"))
+            .append(query!("set", "y", "200"));
+        let ret = con.run_pipeline(pipe).await.unwrap();
+        assert_eq!(
+            ret,
+            vec![
+                Element::RespCode(RespCode::Okay),
+                Element::RespCode(RespCode::Okay),
+                Element::RespCode(RespCode::ErrorString("container-not-found".to_owned())),
+                Element::RespCode(RespCode::NotFound)
+            ]
+        );
+        // the last query was never run
+        runeq!(
+            con,
+            query!("get", "y"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_pipeline_abort_on_err_written_error() {
+        // SET writes the overwrite error itself instead of returning an action error
+        runeq!(
+            con,
+            query!("set", "x", "100"),
+            Element::RespCode(RespCode::Okay)
+        );
+        let pipe = Pipeline::new()
+            .append(query!("abort_on_err"))
+            .append(query!("set", "x", "200"))
+            .append(query!("set", "y", "300"));
+        let ret = con.run_pipeline(pipe).await.unwrap();
+        assert_eq!(
+            ret,
+            vec![
+                Element::RespCode(RespCode::Okay),
+                Element::RespCode(RespCode::OverwriteError),
+                Element::RespCode(RespCode::NotFound)
+            ]
+        );
+        // the last query was never run
+        runeq!(
+            con,
+            query!("get", "y"),
+            Element::RespCode(RespCode::NotFound)
+        );
+    }
+    async fn test_pipeline_abort_on_err_no_error() {
+        let pipe = Pipeline::new()
+            .append(query!("abort_on_err"))
+            .append(query!("heya", "first"))
+            .append(query!("heya", "second"));
+        let ret = con.run_pipeline(pipe).await.unwrap();
+        assert_eq!(
+            ret,
+            vec![
+                Element::RespCode(RespCode::Okay),
+                Element::String("first".to_owned()),
+                Element::String("second".to_owned())
+            ]
+        );
+    }
 }
@@ -102,6 +102,72 @@ pub fn err<T, E>(e: impl Into<E>) -> Result<T, E> {
     Err(e.into())
 }
 
+/// Returns true if `input` matches the glob `pattern`, where `*` matches any sequence of bytes
+/// (including an empty one) and `?` matches exactly one byte
+pub fn glob_match(pattern: &[u8], input: &[u8]) -> bool {
+    glob_match_len(pattern, input, false).0
+}
+
+/// Same as [`glob_match`], but optionally ignoring ASCII case. This also returns the number of
+/// bytes of `input` that the pattern consumed before the match succeeded or failed, which is
+/// the length of the input if it matched
+pub fn glob_match_len(pattern: &[u8], input: &[u8], nocase: bool) -> (bool, usize) {
+    let eq = |a: u8, b: u8| a == b || (nocase && a.eq_ignore_ascii_case(&b));
+    let (mut p, mut i) = (0, 0);
+    let mut consumed = 0;
+    // where to resume from if a match after the last `*` fails
+    let mut backtrack: Option<(usize, usize)> = None;
+    while i < input.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, i));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || eq(c, input[i]) => {
+                p += 1;
+                i += 1;
+                consumed = consumed.max(i);
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    // let the `*` eat one more byte
+                    p = star + 1;
+                    i = matched + 1;
+                    consumed = consumed.max(i);
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return (false, consumed),
+            },
+        }
+    }
+    (pattern[p..].iter().all(|c| *c == b'*'), consumed)
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match(b"*", b""));
+    assert!(glob_match(b"*", b"LMOD"));
+    assert!(glob_match(b"L*", b"LMOD"));
+    assert!(glob_match(b"*SET*", b"USET"));
+    assert!(glob_match(b"M?ET", b"MSET"));
+    assert!(glob_match(b"*E*T", b"SETNXEXT"));
+    assert!(!glob_match(b"M?ET", b"MSETX"));
+    assert!(!glob_match(b"L*", b"GET"));
+    assert!(!glob_match(b"", b"GET"));
+    assert!(!glob_match(b"*Y", b"SETNXEX"));
+}
+
+#[test]
+fn test_glob_match_len() {
+    assert_eq!(glob_match_len(b"L*", b"LMOD", false), (true, 4));
+    assert_eq!(glob_match_len(b"l*", b"LMOD", false), (false, 0));
+    assert_eq!(glob_match_len(b"l*", b"LMOD", true), (true, 4));
+    assert_eq!(glob_match_len(b"LM?X", b"LMOD", false), (false, 3));
+    assert_eq!(glob_match_len(b"*Y", b"SETNXEX", false), (false, 7));
+    assert_eq!(glob_match_len(b"SET", b"SETNX", false), (false, 3));
+    assert_eq!(glob_match_len(b"*", b"", false), (true, 0));
+}
+
 /// This is used to hack around multiple trait system boundaries
 /// like deref coercion recursions
 #[derive(Debug)]